@@ -1,6 +1,7 @@
 from fastapi import FastAPI, Depends, HTTPException
 from sqlalchemy.orm import Session
 from statistics import mean
+import time
 from fastapi.middleware.cors import CORSMiddleware
 from fastapi.staticfiles import StaticFiles
 from pathlib import Path
@@ -11,13 +12,14 @@ from aetherframe.core import repository
 from aetherframe.core.schemas import JobCreate, JobRead, PluginCreate, PluginRead, EventCreate, EventRead
 from aetherframe.utils.config import get_settings
 from aetherframe.core.celery_app import celery_app
-from aetherframe.core.models import Base, Job, Plugin, Event, Finding, Artifact, TraceEvent as TraceEventModel
+from aetherframe.core.models import Base, Job, JobStatus, Plugin, Event, Finding, Artifact, TraceEvent as TraceEventModel
 from aetherframe.utils.db import get_engine
 from fastapi.responses import PlainTextResponse
 from aetherframe.utils.license import enforce_or_raise
 from typing import List, Optional
 
 settings = get_settings()
+started_at = time.monotonic()
 
 static_dir = Path(__file__).parent / "static"
 app = FastAPI(
@@ -91,6 +93,8 @@ def status(db: Session = Depends(get_session)) -> dict:
 
     return {
         "service": "aetherframe",
+        "version": app.version,
+        "uptime_sec": round(time.monotonic() - started_at, 1),
         "env": settings.environment,
         "celery": "up" if pong else "down",
         "metrics": {
@@ -129,8 +133,8 @@ def create_job(payload: JobCreate, db: Session = Depends(get_session)):
 
 
 @app.get("/jobs", response_model=list[JobRead])
-def list_jobs(db: Session = Depends(get_session)):
-    return repository.list_jobs(db)
+def list_jobs(status: Optional[JobStatus] = None, db: Session = Depends(get_session)):
+    return repository.list_jobs(db, status)
 
 
 @app.get("/jobs/{job_id}", response_model=JobRead)
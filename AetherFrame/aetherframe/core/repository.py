@@ -30,8 +30,11 @@ def create_job(db: Session, target: str, plugin_id: Optional[int], pipeline_id:
     return job
 
 
-def list_jobs(db: Session) -> List[Job]:
-    return db.query(Job).order_by(Job.created_at.desc()).all()
+def list_jobs(db: Session, status: Optional[JobStatus] = None) -> List[Job]:
+    query = db.query(Job)
+    if status is not None:
+        query = query.filter(Job.status == status)
+    return query.order_by(Job.created_at.desc()).all()
 
 
 def update_job_status(db: Session, job_id: int, status: JobStatus, result=None) -> Optional[Job]:
@@ -29,6 +29,7 @@ class JobRead(BaseModel):
     id: int
     target: str
     status: JobStatus
+    progress: int = 0
     result: Optional[Dict[str, Any]] = None
     created_at: datetime
     updated_at: datetime
@@ -1,5 +1,13 @@
+import argparse
+import os
+
 import uvicorn
 
 
 if __name__ == "__main__":
-    uvicorn.run("aetherframe.api.main:app", host="0.0.0.0", port=8000, reload=False)
+    parser = argparse.ArgumentParser(description="AetherFrame API server")
+    parser.add_argument("--host", default=os.environ.get("AETHERFRAME_HOST", "0.0.0.0"))
+    parser.add_argument("--port", type=int, default=int(os.environ.get("AETHERFRAME_PORT", "8000")))
+    args = parser.parse_args()
+
+    uvicorn.run("aetherframe.api.main:app", host=args.host, port=args.port, reload=False)
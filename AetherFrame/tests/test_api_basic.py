@@ -57,10 +57,20 @@ def test_create_plugin_and_job(client):
     assert job["plugin_id"] == plugin_id
 
     status = client.get("/status").json()
+    assert status["version"] == "0.1.0"
+    assert status["uptime_sec"] >= 0
     assert status["metrics"]["jobs_total"] >= 1
     assert status["metrics"]["plugins_total"] >= 1
 
 
+def test_list_jobs_filters_by_status(client):
+    client.post("/jobs", json={"target": "filtered.bin"})
+    pending = client.get("/jobs", params={"status": "pending"}).json()
+    assert pending and all(j["status"] == "pending" for j in pending)
+    assert all("progress" in j for j in pending)
+    assert client.get("/jobs", params={"status": "running"}).json() == []
+
+
 def test_validation_and_trim(client):
     # missing fields should 422
     resp = client.post("/plugins", json={"name": " ", "version": " "})
@@ -1,3 +1,48 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+// Must match `SIDECAR_NAME` and the `externalBin` entry in tauri.conf.json
+const SIDECAR: &str = "binaries/aetherframe-backend";
+
 fn main() {
+    embed_sidecar_hash();
     tauri_build::build()
 }
+
+// Bake the sidecar's SHA-256 into the binary so it can be checked before every
+// launch (see `backend::integrity`). CI can pass the hash in directly when the
+// sidecar is produced elsewhere.
+fn embed_sidecar_hash() {
+    println!("cargo:rerun-if-env-changed=AETHERFRAME_BACKEND_SHA256");
+    if let Ok(hash) = env::var("AETHERFRAME_BACKEND_SHA256") {
+        println!("cargo:rustc-env=AETHERFRAME_BACKEND_SHA256={}", hash.trim());
+        return;
+    }
+    let target = env::var("TARGET").unwrap_or_default();
+    let suffix = if target.contains("windows") {
+        ".exe"
+    } else {
+        ""
+    };
+    let path = format!("{}-{}{}", SIDECAR, target, suffix);
+    println!("cargo:rerun-if-changed={}", path);
+    // No sidecar yet (e.g. a frontend-only dev build): nothing to embed. A
+    // release build like that won't launch any backend, so say so
+    let Ok(bytes) = fs::read(Path::new(&path)) else {
+        if env::var("PROFILE").as_deref() == Ok("release") {
+            println!(
+                "cargo:warning=No {} and no AETHERFRAME_BACKEND_SHA256; this build will refuse to start the backend",
+                path
+            );
+        }
+        return;
+    };
+    let hash: String = Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    println!("cargo:rustc-env=AETHERFRAME_BACKEND_SHA256={}", hash);
+}
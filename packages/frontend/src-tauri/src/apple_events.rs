@@ -0,0 +1,109 @@
+// macOS hands links and double-clicked documents to the running app as Apple
+// Events rather than on the command line, cold starts included: kAEGetURL
+// for aetherframe:// links, kAEOpenDocuments for project files.
+
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use objc::declare::ClassDecl;
+use objc::runtime::{Object, Sel};
+use objc::{class, msg_send, sel, sel_impl};
+use tauri::AppHandle;
+
+use crate::{deeplink, documents};
+
+// 'GURL' is both the event class and the event id
+const GET_URL: u32 = u32::from_be_bytes(*b"GURL");
+const CORE_EVENT: u32 = u32::from_be_bytes(*b"aevt");
+const OPEN_DOCUMENTS: u32 = u32::from_be_bytes(*b"odoc");
+const DIRECT_OBJECT: u32 = u32::from_be_bytes(*b"----");
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+unsafe fn string(value: *mut Object) -> Option<String> {
+    if value.is_null() {
+        return None;
+    }
+    let utf8: *const c_char = msg_send![value, UTF8String];
+    if utf8.is_null() {
+        return None;
+    }
+    Some(CStr::from_ptr(utf8).to_string_lossy().into_owned())
+}
+
+extern "C" fn get_url(_this: &Object, _cmd: Sel, event: *mut Object, _reply: *mut Object) {
+    let url = unsafe {
+        let descriptor: *mut Object = msg_send![event, paramDescriptorForKeyword: DIRECT_OBJECT];
+        if descriptor.is_null() {
+            return;
+        }
+        let value: *mut Object = msg_send![descriptor, stringValue];
+        string(value)
+    };
+    if let (Some(app), Some(url)) = (APP.get(), url) {
+        deeplink::open(app, &url);
+    }
+}
+
+// The direct object is a list of file URLs, 1-based
+extern "C" fn open_documents(_this: &Object, _cmd: Sel, event: *mut Object, _reply: *mut Object) {
+    let mut paths = Vec::new();
+    unsafe {
+        let list: *mut Object = msg_send![event, paramDescriptorForKeyword: DIRECT_OBJECT];
+        if list.is_null() {
+            return;
+        }
+        let count: isize = msg_send![list, numberOfItems];
+        for i in 1..=count {
+            let item: *mut Object = msg_send![list, descriptorAtIndex: i];
+            if item.is_null() {
+                continue;
+            }
+            let url: *mut Object = msg_send![item, fileURL];
+            if url.is_null() {
+                continue;
+            }
+            let path: *mut Object = msg_send![url, path];
+            paths.extend(string(path).map(PathBuf::from));
+        }
+    }
+    if let Some(app) = APP.get() {
+        for path in paths {
+            documents::open(app, &path);
+        }
+    }
+}
+
+// Called from setup, which runs on the main thread before the event loop
+// starts, so the event that launched the app isn't missed
+pub fn listen(app: &AppHandle) {
+    if APP.set(app.clone()).is_err() {
+        return;
+    }
+    let Some(mut decl) = ClassDecl::new("AetherFrameAppleEvents", class!(NSObject)) else {
+        return;
+    };
+    type Handler = extern "C" fn(&Object, Sel, *mut Object, *mut Object);
+    unsafe {
+        decl.add_method(sel!(handleGetURL:withReplyEvent:), get_url as Handler);
+        decl.add_method(
+            sel!(handleOpenDocuments:withReplyEvent:),
+            open_documents as Handler,
+        );
+        let class = decl.register();
+        let handler: *mut Object = msg_send![class, new];
+        let manager: *mut Object = msg_send![class!(NSAppleEventManager), sharedAppleEventManager];
+        let _: () = msg_send![manager,
+            setEventHandler: handler
+            andSelector: sel!(handleGetURL:withReplyEvent:)
+            forEventClass: GET_URL
+            andEventID: GET_URL];
+        let _: () = msg_send![manager,
+            setEventHandler: handler
+            andSelector: sel!(handleOpenDocuments:withReplyEvent:)
+            forEventClass: CORE_EVENT
+            andEventID: OPEN_DOCUMENTS];
+    }
+}
@@ -0,0 +1,268 @@
+// Interactive OAuth2 / OIDC sign-in: the authorization-code flow with PKCE,
+// run in the system browser. A one-shot listener on 127.0.0.1 catches the
+// redirect (RFC 8252 loopback), the code is exchanged here in Rust and the
+// tokens go straight to the keychain, so the webview never sees them.
+
+use std::time::Duration;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use rand::RngCore;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time::timeout;
+
+use super::{StoredToken, TokenResponse};
+use crate::external;
+use crate::i18n;
+use crate::profiles::Profile;
+use crate::state::AppState;
+use crate::window;
+
+// How long the user has to finish signing in in the browser
+const LOGIN_TIMEOUT: Duration = Duration::from_secs(300);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+const CALLBACK_PATH: &str = "/callback";
+const MAX_REQUEST: usize = 8 * 1024;
+
+// Per-profile identity provider settings
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OAuthSettings {
+    pub client_id: String,
+    // OIDC issuer; endpoints are discovered from its
+    // /.well-known/openid-configuration unless set explicitly below
+    pub issuer: Option<String>,
+    pub authorize_url: Option<String>,
+    pub token_url: Option<String>,
+    pub scopes: Vec<String>,
+    // Fixed loopback port for providers that don't accept any port; 0 or None picks a free one
+    pub redirect_port: Option<u16>,
+}
+
+struct Endpoints {
+    authorize: String,
+    token: String,
+}
+
+#[derive(Deserialize)]
+struct Discovery {
+    authorization_endpoint: String,
+    token_endpoint: String,
+}
+
+// Token response plus the OIDC id token
+#[derive(Deserialize)]
+struct LoginResponse {
+    #[serde(flatten)]
+    token: TokenResponse,
+    id_token: Option<String>,
+}
+
+async fn endpoints(app: &AppHandle, oauth: &OAuthSettings) -> Result<Endpoints, String> {
+    if let (Some(authorize), Some(token)) = (&oauth.authorize_url, &oauth.token_url) {
+        return Ok(Endpoints {
+            authorize: authorize.clone(),
+            token: token.clone(),
+        });
+    }
+    let issuer = oauth
+        .issuer
+        .as_deref()
+        .ok_or("Set an issuer or both authorize_url and token_url")?;
+    let url = format!(
+        "{}/.well-known/openid-configuration",
+        issuer.trim_end_matches('/')
+    );
+    let discovery: Discovery = app
+        .state::<AppState>()
+        .http
+        .client()
+        .get(url)
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("OIDC discovery failed: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Unexpected OIDC discovery document: {}", e))?;
+    Ok(Endpoints {
+        authorize: oauth
+            .authorize_url
+            .clone()
+            .unwrap_or(discovery.authorization_endpoint),
+        token: oauth.token_url.clone().unwrap_or(discovery.token_endpoint),
+    })
+}
+
+fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+// Run the whole flow for `profile` and return the tokens to store
+pub(super) async fn run(app: &AppHandle, profile: &Profile) -> Result<StoredToken, String> {
+    let oauth = profile.oauth.as_ref().ok_or_else(|| {
+        format!(
+            "Profile {} has no sign-in provider configured",
+            profile.name
+        )
+    })?;
+    let endpoints = endpoints(app, oauth).await?;
+
+    let listener = TcpListener::bind(("127.0.0.1", oauth.redirect_port.unwrap_or(0)))
+        .await
+        .map_err(|e| format!("Can't listen for the sign-in redirect: {}", e))?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+    let redirect_uri = format!("http://127.0.0.1:{}{}", port, CALLBACK_PATH);
+
+    let verifier = random_token();
+    let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+    let csrf = random_token();
+    let mut scopes = oauth.scopes.clone();
+    if oauth.issuer.is_some() && !scopes.iter().any(|s| s == "openid") {
+        scopes.insert(0, "openid".to_string());
+    }
+    let mut url =
+        Url::parse(&endpoints.authorize).map_err(|e| format!("Invalid authorize URL: {}", e))?;
+    url.query_pairs_mut()
+        .append_pair("response_type", "code")
+        .append_pair("client_id", &oauth.client_id)
+        .append_pair("redirect_uri", &redirect_uri)
+        .append_pair("scope", &scopes.join(" "))
+        .append_pair("state", &csrf)
+        .append_pair("code_challenge", &challenge)
+        .append_pair("code_challenge_method", "S256");
+    // The provider comes from the user's own profile, not the webview
+    external::launch(url.to_string()).await?;
+
+    let code = timeout(LOGIN_TIMEOUT, wait_for_code(app, &listener, &csrf))
+        .await
+        .map_err(|_| "Timed out waiting for sign-in to finish".to_string())??;
+    window::activate(app);
+
+    let response: LoginResponse = app
+        .state::<AppState>()
+        .http
+        .client()
+        .post(&endpoints.token)
+        .form(&[
+            ("grant_type", "authorization_code"),
+            ("code", code.as_str()),
+            ("redirect_uri", redirect_uri.as_str()),
+            ("client_id", oauth.client_id.as_str()),
+            ("code_verifier", verifier.as_str()),
+        ])
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Token exchange failed: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Unexpected token response: {}", e))?;
+    let mut token = response.token.into_stored(None);
+    token.id_token = response.id_token;
+    // Remembered so refreshes don't need discovery again
+    token.token_url = Some(endpoints.token);
+    token.client_id = Some(oauth.client_id.clone());
+    Ok(token)
+}
+
+// Serve the loopback listener until the provider redirects back with our
+// state. Stray requests (favicons, port scanners) are answered and ignored.
+async fn wait_for_code(
+    app: &AppHandle,
+    listener: &TcpListener,
+    csrf: &str,
+) -> Result<String, String> {
+    loop {
+        let (mut stream, _) = listener.accept().await.map_err(|e| e.to_string())?;
+        let Some(query) = read_callback(&mut stream).await else {
+            respond(&mut stream, "404 Not Found", "").await;
+            continue;
+        };
+        let param = |name: &str| {
+            query
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.clone())
+        };
+        if param("state").as_deref() != Some(csrf) {
+            respond(&mut stream, "400 Bad Request", "").await;
+            continue;
+        }
+        let result = match (param("code"), param("error")) {
+            (Some(code), None) => Ok(code),
+            (_, error) => {
+                let detail = param("error_description").or(error).unwrap_or_default();
+                Err(format!("Sign-in was not completed: {}", detail))
+            }
+        };
+        let message = match &result {
+            Ok(_) => i18n::text(app, "login.success"),
+            Err(_) => i18n::text(app, "login.failed"),
+        };
+        respond(&mut stream, "200 OK", &message).await;
+        return result;
+    }
+}
+
+// Query pairs of a GET to the callback path, None for anything else
+async fn read_callback(stream: &mut TcpStream) -> Option<Vec<(String, String)>> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+    while !buf.windows(4).any(|w| w == b"\r\n\r\n") && buf.len() < MAX_REQUEST {
+        let read = timeout(REQUEST_TIMEOUT, stream.read(&mut chunk))
+            .await
+            .ok()?
+            .ok()?;
+        if read == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..read]);
+    }
+    let request = String::from_utf8_lossy(&buf);
+    let target = request
+        .lines()
+        .next()?
+        .strip_prefix("GET ")?
+        .split(' ')
+        .next()?;
+    let url = Url::parse(&format!("http://127.0.0.1{}", target)).ok()?;
+    if url.path() != CALLBACK_PATH {
+        return None;
+    }
+    Some(url.query_pairs().into_owned().collect())
+}
+
+async fn respond(stream: &mut TcpStream, status: &str, message: &str) {
+    let body = format!(
+        "<!DOCTYPE html><meta charset=\"utf-8\"><title>AetherFrame</title>\
+         <body style=\"font-family:sans-serif;text-align:center;margin-top:20vh\">{}</body>",
+        message
+    );
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+// Display claims from the id token. Its signature isn't checked: the token
+// came straight from the token endpoint over TLS and is only used for the UI.
+pub fn claims(id_token: &str) -> Option<Value> {
+    let payload = id_token.split('.').nth(1)?;
+    let json = URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?;
+    serde_json::from_slice(&json).ok()
+}
@@ -0,0 +1,299 @@
+// Bearer tokens for backend calls: loaded from the keychain per profile,
+// refreshed shortly before they expire and attached centrally
+
+pub mod login;
+
+use std::time::Duration;
+
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::Mutex;
+
+use crate::profiles::{self, Profile};
+use crate::secrets;
+use crate::state::AppState;
+use crate::util::now_millis;
+
+// Refresh this long before expiry so in-flight requests don't race the deadline
+const REFRESH_MARGIN: Duration = Duration::from_secs(60);
+const REFRESH_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+struct StoredToken {
+    access_token: String,
+    refresh_token: Option<String>,
+    // Unix millis; None for tokens that don't expire
+    expires_at: Option<u64>,
+    // Set by `login`: the OIDC identity and where to refresh
+    #[serde(default)]
+    id_token: Option<String>,
+    #[serde(default)]
+    token_url: Option<String>,
+    #[serde(default)]
+    client_id: Option<String>,
+}
+
+impl StoredToken {
+    fn needs_refresh(&self) -> bool {
+        self.expires_at
+            .is_some_and(|at| at <= now_millis() + REFRESH_MARGIN.as_millis() as u64)
+    }
+}
+
+// Standard OAuth2 token endpoint response
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: Option<u64>,
+}
+
+impl TokenResponse {
+    fn into_stored(self, previous_refresh: Option<String>) -> StoredToken {
+        StoredToken {
+            access_token: self.access_token,
+            // Providers may omit the refresh token when it's unchanged
+            refresh_token: self.refresh_token.or(previous_refresh),
+            expires_at: self.expires_in.map(|s| now_millis() + s * 1000),
+            id_token: None,
+            token_url: None,
+            client_id: None,
+        }
+    }
+}
+
+fn secret_key(profile: &str) -> String {
+    format!("token:{}", profile)
+}
+
+// Cached token for one profile; the async lock also serializes refreshes
+#[derive(Default)]
+pub struct TokenManager {
+    cached: Mutex<Option<(String, StoredToken)>>,
+    // Held for the duration of an interactive sign-in
+    login: Mutex<()>,
+}
+
+impl TokenManager {
+    // Authorization header for the active profile, if it has credentials
+    pub async fn header(&self, app: &AppHandle) -> Result<Option<HeaderValue>, String> {
+        let state = app.state::<AppState>();
+        // Nothing goes out with credentials until the user unlocks again
+        if state.idle.is_locked() {
+            return Ok(None);
+        }
+        let profile = profiles::active(&state.settings.get());
+
+        let mut cached = self.cached.lock().await;
+        if cached.as_ref().map(|(name, _)| name) != Some(&profile.name) {
+            *cached = load(&profile)?.map(|token| (profile.name.clone(), token));
+        }
+        let Some((_, token)) = cached.as_mut() else {
+            return Ok(None);
+        };
+
+        if token.needs_refresh() {
+            match refresh(app, &profile, token).await {
+                Ok(fresh) => {
+                    secrets::set(&secret_key(&profile.name), &to_json(&fresh)?)?;
+                    *token = fresh;
+                }
+                Err(e) => {
+                    *cached = None;
+                    let _ = app.emit_all("auth-expired", &profile.name);
+                    return Err(e);
+                }
+            }
+        }
+
+        HeaderValue::from_str(&format!("Bearer {}", token.access_token))
+            .map(Some)
+            .map_err(|_| "Invalid auth token".to_string())
+    }
+
+    // Headers to merge into a request; failures are logged and sent unauthenticated
+    pub async fn headers(&self, app: &AppHandle) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        match self.header(app).await {
+            Ok(Some(value)) => {
+                headers.insert(AUTHORIZATION, value);
+            }
+            Ok(None) => {}
+            Err(e) => tracing::warn!("{}", e),
+        }
+        headers
+    }
+
+    pub async fn clear(&self) {
+        *self.cached.lock().await = None;
+    }
+}
+
+// Keychain first; the plaintext `auth_token` in the profile is a legacy fallback
+fn load(profile: &Profile) -> Result<Option<StoredToken>, String> {
+    if let Some(raw) = secrets::get(&secret_key(&profile.name))? {
+        let token = serde_json::from_str(&raw)
+            .map_err(|e| format!("Corrupt token for {}: {}", profile.name, e))?;
+        return Ok(Some(token));
+    }
+    Ok(profile.auth_token.clone().map(|access_token| StoredToken {
+        access_token,
+        refresh_token: None,
+        expires_at: None,
+        ..Default::default()
+    }))
+}
+
+async fn refresh(
+    app: &AppHandle,
+    profile: &Profile,
+    token: &StoredToken,
+) -> Result<StoredToken, String> {
+    let token_url = token.token_url.as_ref().or(profile.token_url.as_ref());
+    let (Some(refresh_token), Some(token_url)) = (&token.refresh_token, token_url) else {
+        return Err(format!("Session for {} has expired", profile.name));
+    };
+    let state = app.state::<AppState>();
+    // Relative token URLs are resolved against the backend
+    let (url, client) = if token_url.starts_with('/') {
+        (
+            format!("{}{}", state.backend.url(), token_url),
+            state.http.backend(),
+        )
+    } else {
+        (token_url.clone(), state.http.client())
+    };
+    let mut form = vec![
+        ("grant_type", "refresh_token"),
+        ("refresh_token", refresh_token.as_str()),
+    ];
+    // Public clients (as used by `login`) identify themselves on refresh too
+    if let Some(client_id) = &token.client_id {
+        form.push(("client_id", client_id));
+    }
+    let response: TokenResponse = client
+        .post(url)
+        .form(&form)
+        .timeout(REFRESH_TIMEOUT)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Token refresh failed: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Unexpected token response: {}", e))?;
+    let mut fresh = response.into_stored(token.refresh_token.clone());
+    fresh.id_token = token.id_token.clone();
+    fresh.token_url = token.token_url.clone();
+    fresh.client_id = token.client_id.clone();
+    Ok(fresh)
+}
+
+// The next request loads the new credentials, and nothing fetched under the
+// old ones is served from the response cache
+async fn identity_changed(state: &AppState) {
+    state.auth.clear().await;
+    state.http.cache.clear();
+}
+
+fn to_json(token: &StoredToken) -> Result<String, String> {
+    serde_json::to_string(token).map_err(|e| e.to_string())
+}
+
+// Store credentials for a profile (the active one by default)
+#[tauri::command]
+pub async fn set_auth_token(
+    state: State<'_, AppState>,
+    profile: Option<String>,
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: Option<u64>,
+) -> Result<(), String> {
+    let profile = profile.unwrap_or_else(|| profiles::active_name(&state.settings.get()));
+    let token = TokenResponse {
+        access_token,
+        refresh_token,
+        expires_in,
+    }
+    .into_stored(None);
+    secrets::set(&secret_key(&profile), &to_json(&token)?)?;
+    identity_changed(&state).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn clear_auth_token(
+    state: State<'_, AppState>,
+    profile: Option<String>,
+) -> Result<(), String> {
+    let profile = profile.unwrap_or_else(|| profiles::active_name(&state.settings.get()));
+    secrets::delete(&secret_key(&profile))?;
+    identity_changed(&state).await;
+    Ok(())
+}
+
+#[derive(Serialize)]
+pub struct Session {
+    pub profile: String,
+    pub signed_in: bool,
+    pub expires_at: Option<u64>,
+    // Claims from the OIDC id token (sub, name, email, ...), when there is one
+    pub claims: Option<Value>,
+}
+
+fn session(profile: &Profile) -> Result<Session, String> {
+    let token = load(profile)?;
+    Ok(Session {
+        profile: profile.name.clone(),
+        signed_in: token.is_some(),
+        expires_at: token.as_ref().and_then(|t| t.expires_at),
+        claims: token
+            .as_ref()
+            .and_then(|t| t.id_token.as_deref())
+            .and_then(login::claims),
+    })
+}
+
+fn profile_named(app: &AppHandle, name: Option<String>) -> Result<Profile, String> {
+    let settings = app.state::<AppState>().settings.get();
+    let name = name.unwrap_or_else(|| profiles::active_name(&settings));
+    profiles::all(&settings)
+        .into_iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| format!("No such profile: {}", name))
+}
+
+// Sign in through the profile's identity provider in the system browser
+#[tauri::command]
+pub async fn login(app: AppHandle, profile: Option<String>) -> Result<Session, String> {
+    let profile = profile_named(&app, profile)?;
+    let state = app.state::<AppState>();
+    let _guard = state
+        .auth
+        .login
+        .try_lock()
+        .map_err(|_| "A sign-in is already in progress".to_string())?;
+    let token = login::run(&app, &profile).await?;
+    secrets::set(&secret_key(&profile.name), &to_json(&token)?)?;
+    identity_changed(&state).await;
+    let session = session(&profile)?;
+    let _ = app.emit_all("session-changed", &session);
+    Ok(session)
+}
+
+#[tauri::command]
+pub async fn logout(app: AppHandle, profile: Option<String>) -> Result<(), String> {
+    let profile = profile_named(&app, profile)?;
+    secrets::delete(&secret_key(&profile.name))?;
+    let state = app.state::<AppState>();
+    identity_changed(&state).await;
+    let _ = app.emit_all("session-changed", &session(&profile)?);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_session(app: AppHandle, profile: Option<String>) -> Result<Session, String> {
+    session(&profile_named(&app, profile)?)
+}
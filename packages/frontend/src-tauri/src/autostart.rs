@@ -0,0 +1,224 @@
+// Start at login: a Run key value on Windows, a LaunchAgent on macOS and an
+// XDG autostart entry on Linux. The OS entry is the only record, so it's
+// checked fresh rather than mirrored in settings. Login launches pass
+// `--minimized` by default, so the app comes up in the tray like an agent.
+
+use tauri::{AppHandle, Manager};
+
+use crate::tray;
+use crate::util::launcher;
+
+fn args(minimized: bool) -> Vec<&'static str> {
+    if minimized {
+        vec!["--minimized"]
+    } else {
+        Vec::new()
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use windows::core::HSTRING;
+    use windows::Win32::Foundation::ERROR_SUCCESS;
+    use windows::Win32::System::Registry::{
+        RegDeleteKeyValueW, RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_SZ,
+    };
+
+    use tauri::AppHandle;
+
+    const RUN_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\Run";
+
+    fn value_name(app: &AppHandle) -> HSTRING {
+        HSTRING::from(app.package_info().name.as_str())
+    }
+
+    pub fn enable(app: &AppHandle, program: &str, args: &[&str]) -> Result<(), String> {
+        let mut command = format!("\"{}\"", program);
+        for arg in args {
+            command.push(' ');
+            command.push_str(arg);
+        }
+        crate::util::set_registry_string(RUN_KEY, Some(&app.package_info().name), &command)
+            .map_err(|e| format!("Failed to register for startup: {}", e))
+    }
+
+    pub fn disable(app: &AppHandle) -> Result<(), String> {
+        let status = unsafe {
+            RegDeleteKeyValueW(HKEY_CURRENT_USER, &HSTRING::from(RUN_KEY), &value_name(app))
+        };
+        // Already gone is what we wanted
+        if status != ERROR_SUCCESS && status.0 != 2 {
+            return Err(format!(
+                "Failed to unregister from startup: error {}",
+                status.0
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn is_enabled(app: &AppHandle) -> bool {
+        let status = unsafe {
+            RegGetValueW(
+                HKEY_CURRENT_USER,
+                &HSTRING::from(RUN_KEY),
+                &value_name(app),
+                RRF_RT_REG_SZ,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            )
+        };
+        status == ERROR_SUCCESS
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use tauri::AppHandle;
+
+    fn plist(app: &AppHandle) -> Option<PathBuf> {
+        let name = format!("{}.plist", app.config().tauri.bundle.identifier);
+        Some(
+            tauri::api::path::home_dir()?
+                .join("Library/LaunchAgents")
+                .join(name),
+        )
+    }
+
+    fn escape(value: &str) -> String {
+        value
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    }
+
+    pub fn enable(app: &AppHandle, program: &str, args: &[&str]) -> Result<(), String> {
+        let path = plist(app).ok_or("No home directory")?;
+        let arguments: String = std::iter::once(program)
+            .chain(args.iter().copied())
+            .map(|arg| format!("        <string>{}</string>\n", escape(arg)))
+            .collect();
+        let contents = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+             <plist version=\"1.0\">\n\
+             <dict>\n\
+             \x20   <key>Label</key>\n\
+             \x20   <string>{}</string>\n\
+             \x20   <key>ProgramArguments</key>\n\
+             \x20   <array>\n{}\x20   </array>\n\
+             \x20   <key>RunAtLoad</key>\n\
+             \x20   <true/>\n\
+             </dict>\n\
+             </plist>\n",
+            escape(&app.config().tauri.bundle.identifier),
+            arguments
+        );
+        crate::util::write_atomic(&path, contents.as_bytes())
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    pub fn disable(app: &AppHandle) -> Result<(), String> {
+        let path = plist(app).ok_or("No home directory")?;
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(format!("Failed to remove {}: {}", path.display(), e))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn is_enabled(app: &AppHandle) -> bool {
+        plist(app).is_some_and(|path| path.exists())
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use tauri::AppHandle;
+
+    fn entry(app: &AppHandle) -> Option<PathBuf> {
+        let name = format!("{}.desktop", app.package_info().crate_name);
+        Some(tauri::api::path::config_dir()?.join("autostart").join(name))
+    }
+
+    pub fn enable(app: &AppHandle, program: &str, args: &[&str]) -> Result<(), String> {
+        let path = entry(app).ok_or("No config directory")?;
+        let mut exec = crate::util::exec_quote(program);
+        for arg in args {
+            exec.push(' ');
+            exec.push_str(arg);
+        }
+        let contents = format!(
+            "[Desktop Entry]\nType=Application\nName={}\nExec={}\nTerminal=false\nX-GNOME-Autostart-enabled=true\n",
+            app.package_info().name,
+            exec
+        );
+        crate::util::write_atomic(&path, contents.as_bytes())
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    pub fn disable(app: &AppHandle) -> Result<(), String> {
+        let path = entry(app).ok_or("No config directory")?;
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(format!("Failed to remove {}: {}", path.display(), e))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn is_enabled(app: &AppHandle) -> bool {
+        entry(app).is_some_and(|path| path.exists())
+    }
+}
+
+pub fn is_enabled(app: &AppHandle) -> bool {
+    platform::is_enabled(app)
+}
+
+pub fn enable(app: &AppHandle, minimized: bool) -> Result<(), String> {
+    let program = launcher()?;
+    platform::enable(app, &program.to_string_lossy(), &args(minimized))?;
+    tray::rebuild(app);
+    let _ = app.emit_all("autostart-changed", true);
+    Ok(())
+}
+
+pub fn disable(app: &AppHandle) -> Result<(), String> {
+    platform::disable(app)?;
+    tray::rebuild(app);
+    let _ = app.emit_all("autostart-changed", false);
+    Ok(())
+}
+
+// Tray checkbox
+pub fn toggle(app: &AppHandle) -> Result<(), String> {
+    if is_enabled(app) {
+        disable(app)
+    } else {
+        enable(app, true)
+    }
+}
+
+// `minimized` (default on) starts in the tray at login
+#[tauri::command]
+pub async fn enable_autostart(app: AppHandle, minimized: Option<bool>) -> Result<(), String> {
+    enable(&app, minimized.unwrap_or(true))
+}
+
+#[tauri::command]
+pub async fn disable_autostart(app: AppHandle) -> Result<(), String> {
+    disable(&app)
+}
+
+#[tauri::command]
+pub async fn is_autostart_enabled(app: AppHandle) -> Result<bool, String> {
+    Ok(is_enabled(&app))
+}
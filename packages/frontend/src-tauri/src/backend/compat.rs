@@ -0,0 +1,130 @@
+// Backend/frontend API version compatibility
+
+use reqwest::header::HeaderMap;
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::http::retry::{send_with_retry, RetryPolicy};
+use crate::state::AppState;
+
+// Backend API versions this build of the app knows how to talk to
+pub const SUPPORTED_API: &str = ">=0.1.0, <0.2.0";
+
+#[derive(Serialize)]
+pub struct Compatibility {
+    pub compatible: bool,
+    pub app_version: String,
+    pub backend_version: Option<String>,
+    pub supported_range: String,
+    pub message: String,
+}
+
+#[derive(Deserialize)]
+struct StatusVersion {
+    version: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct OpenApi {
+    info: OpenApiInfo,
+}
+
+#[derive(Deserialize)]
+struct OpenApiInfo {
+    version: String,
+}
+
+// Older backends don't report a version in /status; the OpenAPI document always has one
+async fn fetch_backend_version(
+    client: &reqwest::Client,
+    auth: &HeaderMap,
+    policy: RetryPolicy,
+    base_url: &str,
+) -> Result<String, String> {
+    let status: StatusVersion = send_with_retry(policy, || {
+        client
+            .get(format!("{}/status", base_url))
+            .headers(auth.clone())
+    })
+    .await
+    .and_then(|r| r.error_for_status())
+    .map_err(|e| format!("Backend is not reachable: {}", e))?
+    .json()
+    .await
+    .map_err(|e| format!("Unexpected /status response: {}", e))?;
+    if let Some(version) = status.version {
+        return Ok(version);
+    }
+
+    let openapi: OpenApi = send_with_retry(policy, || {
+        client
+            .get(format!("{}/openapi.json", base_url))
+            .headers(auth.clone())
+    })
+    .await
+    .and_then(|r| r.error_for_status())
+    .map_err(|e| format!("Backend does not report a version: {}", e))?
+    .json()
+    .await
+    .map_err(|e| format!("Unexpected /openapi.json response: {}", e))?;
+    Ok(openapi.info.version)
+}
+
+fn evaluate(backend_version: &str) -> (bool, String) {
+    let req = VersionReq::parse(SUPPORTED_API).expect("invalid SUPPORTED_API range");
+    match Version::parse(backend_version.trim_start_matches('v')) {
+        Ok(version) if req.matches(&version) => (true, "Backend version is supported".to_string()),
+        Ok(_) => (
+            false,
+            format!(
+                "Backend {} is outside the supported range {}",
+                backend_version, SUPPORTED_API
+            ),
+        ),
+        Err(_) => (
+            false,
+            format!("Backend reported an invalid version: {}", backend_version),
+        ),
+    }
+}
+
+// Version of the running backend, for callers other than the check below
+pub async fn backend_version(app: &AppHandle) -> Result<String, String> {
+    let state = app.state::<AppState>();
+    let policy = state.settings.get().http.policy("check_compatibility");
+    let auth = state.auth.headers(app).await;
+    fetch_backend_version(&state.http.backend(), &auth, policy, &state.backend.url()).await
+}
+
+#[tauri::command]
+pub async fn check_compatibility(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Compatibility, String> {
+    state.backend.ensure_available()?;
+    let app_version = app.package_info().version.to_string();
+    let policy = state.settings.get().http.policy("check_compatibility");
+    let client = state.http.backend();
+    let auth = state.auth.headers(&app).await;
+    let result = match fetch_backend_version(&client, &auth, policy, &state.backend.url()).await {
+        Ok(version) => {
+            let (compatible, message) = evaluate(&version);
+            Compatibility {
+                compatible,
+                app_version,
+                backend_version: Some(version),
+                supported_range: SUPPORTED_API.to_string(),
+                message,
+            }
+        }
+        Err(message) => Compatibility {
+            compatible: false,
+            app_version,
+            backend_version: None,
+            supported_range: SUPPORTED_API.to_string(),
+            message,
+        },
+    };
+    Ok(result)
+}
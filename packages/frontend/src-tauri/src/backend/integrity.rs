@@ -0,0 +1,149 @@
+// Checks the sidecar against the SHA-256 embedded at build time (see build.rs)
+// before it is launched, so a tampered or half-updated binary is never run.
+// Backend updates are checked against what they were installed with instead.
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager};
+
+use super::logs::{self, Level};
+use super::update::Installed;
+use super::SIDECAR_NAME;
+use crate::state::AppState;
+use crate::updater::download;
+
+const EXPECTED: Option<&str> = option_env!("AETHERFRAME_BACKEND_SHA256");
+
+#[derive(Clone, Serialize)]
+struct IntegrityFailure {
+    path: String,
+    expected: String,
+    actual: Option<String>,
+    error: String,
+}
+
+// Path, size and mtime of the last binary that passed, so restarts don't rehash it
+#[derive(Default)]
+pub struct IntegrityCache {
+    verified: Mutex<Option<(PathBuf, u64, SystemTime)>>,
+}
+
+// Where Tauri resolves the sidecar: next to our own executable
+fn sidecar_path() -> io::Result<PathBuf> {
+    let exe = std::env::current_exe()?;
+    let dir = exe
+        .parent()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no executable directory"))?;
+    Ok(dir.join(format!("{}{}", SIDECAR_NAME, std::env::consts::EXE_SUFFIX)))
+}
+
+fn hash_file(path: &PathBuf) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buf)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+// Ok when the sidecar matches. Only debug builds may skip the check when no
+// hash was embedded (frontend-only dev builds); a release build without one
+// refuses to launch the sidecar at all.
+pub fn verify(app: &AppHandle) -> Result<(), String> {
+    let Some(expected) = EXPECTED.map(|h| h.to_ascii_lowercase()) else {
+        if cfg!(debug_assertions) {
+            return Ok(());
+        }
+        return Err("This build has no backend checksum to verify the sidecar against".into());
+    };
+    let path = sidecar_path().map_err(|e| format!("Backend sidecar not found: {}", e))?;
+    check(app, &path, expected, None)
+}
+
+// A bundle from `update_backend` must still match the hash and signature it
+// was installed with; returns where to launch it from
+pub fn verify_installed(app: &AppHandle, installed: &Installed) -> Result<PathBuf, String> {
+    let path = installed.path(app).ok_or("No app data directory")?;
+    check(
+        app,
+        &path,
+        installed.sha256.clone(),
+        Some(&installed.signature),
+    )?;
+    Ok(path)
+}
+
+fn check(
+    app: &AppHandle,
+    path: &PathBuf,
+    expected: String,
+    signature: Option<&str>,
+) -> Result<(), String> {
+    let cache = &app.state::<AppState>().backend.integrity;
+    let stamp = path
+        .metadata()
+        .ok()
+        .and_then(|m| Some((path.clone(), m.len(), m.modified().ok()?)));
+    if stamp.is_some() && *cache.verified.lock().unwrap() == stamp {
+        return Ok(());
+    }
+
+    let (actual, error) = match hash_file(path) {
+        Ok(actual) if actual == expected => {
+            let signed = match signature {
+                Some(signature) => download::verify(app, path, signature),
+                None => Ok(()),
+            };
+            match signed {
+                Ok(()) => {
+                    *cache.verified.lock().unwrap() = stamp;
+                    return Ok(());
+                }
+                Err(e) => (Some(actual), e),
+            }
+        }
+        Ok(actual) => (
+            Some(actual),
+            "Backend binary does not match this build".to_string(),
+        ),
+        Err(e) => (None, format!("Can't read backend binary: {}", e)),
+    };
+    *cache.verified.lock().unwrap() = None;
+    let repair = if signature.is_some() {
+        "Falling back to the bundled backend."
+    } else {
+        "Reinstall AetherFrame to repair it."
+    };
+    let message = format!(
+        "{}; refusing to start it ({}). {}",
+        error,
+        path.display(),
+        repair
+    );
+    logs::internal(app, Level::Error, message.clone());
+    let _ = app.emit_all(
+        "backend-integrity-failed",
+        IntegrityFailure {
+            path: path.display().to_string(),
+            expected,
+            actual,
+            error,
+        },
+    );
+    Err(message)
+}
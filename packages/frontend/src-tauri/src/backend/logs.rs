@@ -0,0 +1,114 @@
+// Captured backend output: forwarded live as `backend-log` events and kept in
+// a ring buffer so a log panel can catch up on demand. The shell's own notices
+// go through the same path as `app` lines.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+
+use crate::state::AppState;
+use crate::util::now_millis;
+
+const CAPACITY: usize = 1000;
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Stream {
+    Stdout,
+    Stderr,
+    App,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Debug,
+    Info,
+    Warning,
+    Error,
+}
+
+impl Level {
+    // Python logging and uvicorn both put the level name near the start of the line
+    fn guess(stream: Stream, line: &str) -> Self {
+        let head: String = line.chars().take(64).collect::<String>().to_uppercase();
+        if head.contains("ERROR") || head.contains("CRITICAL") || head.contains("TRACEBACK") {
+            Level::Error
+        } else if head.contains("WARN") {
+            Level::Warning
+        } else if head.contains("DEBUG") {
+            Level::Debug
+        } else if head.contains("INFO") {
+            Level::Info
+        } else {
+            match stream {
+                Stream::Stderr => Level::Warning,
+                _ => Level::Info,
+            }
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct LogLine {
+    pub stream: Stream,
+    pub level: Level,
+    pub line: String,
+    // Milliseconds since the Unix epoch
+    pub timestamp: u64,
+}
+
+#[derive(Default)]
+pub struct BackendLogs {
+    lines: Mutex<VecDeque<LogLine>>,
+}
+
+impl BackendLogs {
+    fn push(&self, line: LogLine) {
+        let mut lines = self.lines.lock().unwrap();
+        if lines.len() == CAPACITY {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+
+    pub fn tail(&self, limit: usize) -> Vec<LogLine> {
+        let lines = self.lines.lock().unwrap();
+        let skip = lines.len().saturating_sub(limit);
+        lines.iter().skip(skip).cloned().collect()
+    }
+}
+
+pub fn record(app: &AppHandle, stream: Stream, line: String) {
+    let line = line.trim_end().to_string();
+    push(app, stream, Level::guess(stream, &line), line);
+}
+
+// A notice from the shell itself (restarts, health transitions, ...)
+pub fn internal(app: &AppHandle, level: Level, message: impl Into<String>) {
+    push(app, Stream::App, level, message.into());
+}
+
+fn push(app: &AppHandle, stream: Stream, level: Level, line: String) {
+    let line = LogLine {
+        stream,
+        level,
+        line,
+        timestamp: now_millis(),
+    };
+    let state = app.state::<AppState>();
+    state.logs.push(line.clone());
+    state.console.queue(app, &line);
+    state.search.index_log(&line);
+    let _ = app.emit_all("backend-log", line);
+}
+
+#[tauri::command]
+pub async fn get_backend_logs(
+    state: State<'_, AppState>,
+    limit: Option<usize>,
+) -> Result<Vec<LogLine>, String> {
+    Ok(state.logs.tail(limit.unwrap_or(CAPACITY)))
+}
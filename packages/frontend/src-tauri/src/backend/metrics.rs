@@ -0,0 +1,96 @@
+// Resource usage of the managed backend process
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use sysinfo::{Pid, System};
+use tauri::{AppHandle, Manager, State};
+
+use crate::state::AppState;
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Serialize)]
+pub struct BackendMetrics {
+    pub pid: u32,
+    // Percent of one core, so it can exceed 100 on multi-core machines
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+    // Only available where the OS exposes it cheaply
+    pub open_files: Option<usize>,
+}
+
+// Keeps one `System` around: CPU usage is computed between consecutive refreshes
+#[derive(Default)]
+pub struct ResourceMonitor {
+    system: Mutex<System>,
+    latest: Mutex<Option<BackendMetrics>>,
+}
+
+impl ResourceMonitor {
+    pub fn sample(&self, pid: u32) -> Option<BackendMetrics> {
+        let mut system = self.system.lock().unwrap();
+        let sys_pid = Pid::from_u32(pid);
+        if !system.refresh_process(sys_pid) {
+            return None;
+        }
+        let process = system.process(sys_pid)?;
+        let metrics = BackendMetrics {
+            pid,
+            cpu_percent: process.cpu_usage(),
+            memory_bytes: process.memory(),
+            open_files: open_files(pid),
+        };
+        *self.latest.lock().unwrap() = Some(metrics.clone());
+        Some(metrics)
+    }
+
+    // Last sample for the current process, without taking a new one
+    pub fn latest(&self, pid: u32) -> Option<BackendMetrics> {
+        self.latest.lock().unwrap().clone().filter(|m| m.pid == pid)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn open_files(pid: u32) -> Option<usize> {
+    std::fs::read_dir(format!("/proc/{}/fd", pid))
+        .ok()
+        .map(|entries| entries.count())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn open_files(_pid: u32) -> Option<usize> {
+    None
+}
+
+// Emit `backend-metrics` while we own a running backend
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(SAMPLE_INTERVAL).await;
+            let state = app.state::<AppState>();
+            if state.backend.is_stopping() {
+                break;
+            }
+            let Some(pid) = state.backend.pid() else {
+                continue;
+            };
+            if let Some(metrics) = state.resources.sample(pid) {
+                let _ = app.emit_all("backend-metrics", metrics);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn backend_resources(state: State<'_, AppState>) -> Result<BackendMetrics, String> {
+    let pid = state
+        .backend
+        .pid()
+        .ok_or("No managed backend process is running")?;
+    state
+        .resources
+        .sample(pid)
+        .ok_or_else(|| format!("Backend process {} is not available", pid))
+}
@@ -0,0 +1,300 @@
+// Lifecycle of the bundled Python backend (Tauri sidecar)
+
+pub mod compat;
+pub mod integrity;
+pub mod logs;
+pub mod metrics;
+pub mod port;
+pub mod supervisor;
+pub mod update;
+
+use std::sync::atomic::{AtomicBool, AtomicU16, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::api::process::{Command, CommandChild, CommandEvent};
+use tauri::{AppHandle, Manager, State};
+
+use crate::settings::BackendSettings;
+use crate::state::AppState;
+
+// Must match the `externalBin` entry in tauri.conf.json
+pub const SIDECAR_NAME: &str = "aetherframe-backend";
+
+// How long the backend gets to exit on its own before it is killed
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Handle to the running backend, held in managed state
+pub struct BackendProcess {
+    child: Mutex<Option<CommandChild>>,
+    port: AtomicU16,
+    // Externally managed backend we attached to; never spawned or killed by us
+    attached: Mutex<Option<String>>,
+    // Bumped on every spawn so watchers can tell a fresh process from the old one
+    generation: AtomicU64,
+    // Set once the app is exiting so nothing restarts the backend behind our back
+    stopping: AtomicBool,
+    // Set while a restart is in progress; cleared when the new process is healthy
+    restarting: AtomicBool,
+    integrity: integrity::IntegrityCache,
+    // Set while `update_backend` runs
+    updating: AtomicBool,
+}
+
+impl BackendProcess {
+    pub fn new(port: u16) -> Self {
+        Self {
+            child: Mutex::new(None),
+            port: AtomicU16::new(port),
+            attached: Mutex::new(None),
+            generation: AtomicU64::new(0),
+            stopping: AtomicBool::new(false),
+            restarting: AtomicBool::new(false),
+            integrity: integrity::IntegrityCache::default(),
+            updating: AtomicBool::new(false),
+        }
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port.load(Ordering::SeqCst)
+    }
+
+    pub fn url(&self) -> String {
+        match self.attached.lock().unwrap().as_ref() {
+            Some(url) => url.clone(),
+            None => format!("http://127.0.0.1:{}", self.port()),
+        }
+    }
+
+    pub fn is_attached(&self) -> bool {
+        self.attached.lock().unwrap().is_some()
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+
+    pub fn pid(&self) -> Option<u32> {
+        self.child.lock().unwrap().as_ref().map(|child| child.pid())
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.child.lock().unwrap().is_some()
+    }
+
+    pub fn is_stopping(&self) -> bool {
+        self.stopping.load(Ordering::SeqCst)
+    }
+
+    pub fn set_restarting(&self, restarting: bool) {
+        self.restarting.store(restarting, Ordering::SeqCst);
+    }
+
+    // Fail fast instead of letting callers hang on a backend that is coming back up
+    pub fn ensure_available(&self) -> Result<(), String> {
+        if self.restarting.load(Ordering::SeqCst) {
+            return Err("Backend is restarting, try again shortly".into());
+        }
+        Ok(())
+    }
+
+    // Stop the backend for good (app exit): ask it to terminate, wait, then kill
+    pub async fn shutdown(&self) {
+        self.stopping.store(true, Ordering::SeqCst);
+        let Some(pid) = self.pid() else { return };
+
+        if terminate(pid) {
+            let deadline = Instant::now() + SHUTDOWN_TIMEOUT;
+            while self.is_running() && Instant::now() < deadline {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        }
+        self.kill();
+    }
+
+    // `shutdown` for the panic hook: no async runtime, and no waiting on the lock
+    pub fn shutdown_now(&self) {
+        self.stopping.store(true, Ordering::SeqCst);
+        let Ok(child) = self.child.try_lock() else {
+            return;
+        };
+        let Some(pid) = child.as_ref().map(|c| c.pid()) else {
+            return;
+        };
+        drop(child);
+
+        // The supervisor clears the handle once it sees the process exit
+        if terminate(pid) {
+            let deadline = Instant::now() + SHUTDOWN_TIMEOUT;
+            while Instant::now() < deadline
+                && self.child.try_lock().is_ok_and(|child| child.is_some())
+            {
+                std::thread::sleep(Duration::from_millis(100));
+            }
+        }
+        if let Some(child) = self
+            .child
+            .try_lock()
+            .ok()
+            .and_then(|mut child| child.take())
+        {
+            let _ = child.kill();
+        }
+    }
+
+    pub fn kill(&self) {
+        if let Some(child) = self.child.lock().unwrap().take() {
+            let _ = child.kill();
+        }
+    }
+
+    // Forget the handle once the process is gone, unless it was already replaced
+    fn clear_if(&self, pid: u32) {
+        let mut child = self.child.lock().unwrap();
+        if child.as_ref().map(|c| c.pid()) == Some(pid) {
+            *child = None;
+        }
+    }
+}
+
+// Start the sidecar if it isn't already running
+pub fn spawn(app: &AppHandle) -> Result<(), String> {
+    let app_state = app.state::<AppState>();
+    let state = &app_state.backend;
+    let mut slot = state.child.lock().unwrap();
+    if slot.is_some() || state.is_stopping() || state.is_attached() {
+        return Ok(());
+    }
+
+    // Someone may have grabbed our port since the last start
+    let mut port = state.port();
+    if !port::is_free(port) {
+        port = port::pick_free_port()?;
+        state.port.store(port, Ordering::SeqCst);
+    }
+
+    // Never run a binary that isn't the one we shipped or a signed update of it
+    let installed = match update::installed(app) {
+        Some(installed) => match integrity::verify_installed(app, &installed) {
+            Ok(path) => Some(path),
+            Err(_) => {
+                update::discard(app);
+                None
+            }
+        },
+        None => None,
+    };
+    if installed.is_none() {
+        integrity::verify(app)?;
+    }
+
+    let launch = app_state.settings.get().backend;
+    let command = match installed {
+        Some(path) => Command::new(path.to_string_lossy()),
+        None => Command::new_sidecar(SIDECAR_NAME)
+            .map_err(|e| format!("Backend sidecar not found: {}", e))?,
+    };
+    let mut command = command
+        .args(["--host", "127.0.0.1", "--port", &port.to_string()])
+        .args(&launch.args)
+        .envs(launch.env);
+    if let Some(dir) = launch.working_dir {
+        command = command.current_dir(dir);
+    }
+
+    let (mut rx, child) = command
+        .spawn()
+        .map_err(|e| format!("Failed to start backend: {}", e))?;
+    let pid = child.pid();
+    *slot = Some(child);
+    state.generation.fetch_add(1, Ordering::SeqCst);
+    drop(slot);
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        while let Some(event) = rx.recv().await {
+            match event {
+                CommandEvent::Stdout(line) => logs::record(&app, logs::Stream::Stdout, line),
+                CommandEvent::Stderr(line) => logs::record(&app, logs::Stream::Stderr, line),
+                CommandEvent::Terminated(_) => {
+                    app.state::<AppState>().backend.clear_if(pid);
+                    break;
+                }
+                _ => {}
+            }
+        }
+    });
+
+    Ok(())
+}
+
+#[derive(Clone, Serialize)]
+struct AttachedPayload {
+    url: String,
+    reachable: bool,
+}
+
+// Point the app at an external backend and report whether it answers yet
+pub fn attach(app: &AppHandle, url: &str) {
+    let url = url.trim_end_matches('/').to_string();
+    *app.state::<AppState>().backend.attached.lock().unwrap() = Some(url.clone());
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let client = app.state::<AppState>().http.backend();
+        let reachable = match client.get(format!("{}/health", url)).send().await {
+            Ok(response) => response.status().is_success(),
+            Err(_) => false,
+        };
+        if !reachable {
+            tracing::warn!("Attached backend at {} is not reachable yet", url);
+        }
+        let _ = app.emit_all("backend-attached", AttachedPayload { url, reachable });
+    });
+}
+
+// Go back to managing our own sidecar
+pub fn detach(app: &AppHandle) {
+    *app.state::<AppState>().backend.attached.lock().unwrap() = None;
+}
+
+pub fn restart(app: &AppHandle, reason: &str) -> Result<(), String> {
+    let app_state = app.state::<AppState>();
+    let state = &app_state.backend;
+    if state.is_attached() {
+        return Err("The backend is managed externally and can't be restarted from here".into());
+    }
+    state.set_restarting(true);
+    supervisor::notify_restarting(app, 0, Duration::ZERO, reason);
+    // Killing drops open connections, so in-flight requests error out right away
+    state.kill();
+    spawn(app)
+}
+
+#[tauri::command]
+pub async fn restart_backend(app: AppHandle) -> Result<(), String> {
+    restart(&app, "restart requested")
+}
+
+// Persist new launch options and restart the backend with them
+#[tauri::command]
+pub async fn relaunch_backend_with_args(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    options: BackendSettings,
+) -> Result<(), String> {
+    state.settings.update(|s| s.backend = options)?;
+    restart(&app, "launch options changed")
+}
+
+// Politely ask the process to exit; returns false where that isn't possible
+#[cfg(unix)]
+fn terminate(pid: u32) -> bool {
+    unsafe { libc::kill(pid as libc::pid_t, libc::SIGTERM) == 0 }
+}
+
+#[cfg(not(unix))]
+fn terminate(_pid: u32) -> bool {
+    false
+}
@@ -0,0 +1,21 @@
+// Port discovery for the spawned backend
+
+use std::net::{Ipv4Addr, TcpListener};
+
+// Historical default; kept when available so bookmarks and scripts keep working
+pub const PREFERRED_PORT: u16 = 8000;
+
+pub fn is_free(port: u16) -> bool {
+    TcpListener::bind((Ipv4Addr::LOCALHOST, port)).is_ok()
+}
+
+// Pick the preferred port if nobody holds it, otherwise let the OS choose
+pub fn pick_free_port() -> Result<u16, String> {
+    if is_free(PREFERRED_PORT) {
+        return Ok(PREFERRED_PORT);
+    }
+    TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .and_then(|listener| listener.local_addr())
+        .map(|addr| addr.port())
+        .map_err(|e| format!("No free port for the backend: {}", e))
+}
@@ -0,0 +1,135 @@
+// Keeps the backend alive: restarts it with exponential backoff when the
+// process exits or stops answering its health endpoint
+
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use super::logs::{self, Level};
+use crate::state::AppState;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(2);
+// Python needs a moment to boot; don't count failed probes before this
+const STARTUP_GRACE: Duration = Duration::from_secs(30);
+const MAX_HEALTH_FAILURES: u32 = 3;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Serialize)]
+struct RestartingPayload {
+    attempt: u32,
+    delay_ms: u64,
+    reason: String,
+}
+
+#[derive(Clone, Serialize)]
+struct UpPayload {
+    pid: Option<u32>,
+}
+
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(run(app));
+}
+
+async fn run(app: AppHandle) {
+    let mut attempt = 0;
+    let mut failures = 0;
+    let mut up = false;
+    let mut started_at = Instant::now();
+    let mut generation = app.state::<AppState>().backend.generation();
+
+    loop {
+        app.state::<AppState>().power.pause(POLL_INTERVAL).await;
+        let state = app.state::<AppState>();
+        let process = &state.backend;
+        if process.is_stopping() {
+            break;
+        }
+        if process.is_attached() {
+            continue;
+        }
+
+        // A new process (ours or a manual relaunch) gets a fresh startup window
+        if process.generation() != generation {
+            generation = process.generation();
+            failures = 0;
+            up = false;
+            started_at = Instant::now();
+        }
+
+        let reason = if !process.is_running() {
+            Some("backend process exited".to_string())
+        } else if is_healthy(&app, &process.url()).await {
+            failures = 0;
+            if !up {
+                up = true;
+                attempt = 0;
+                process.set_restarting(false);
+                let _ = app.emit_all("backend-up", UpPayload { pid: process.pid() });
+            }
+            None
+        } else {
+            // Right after wake the network may not be back yet
+            let counts = up || started_at.elapsed() > STARTUP_GRACE;
+            if counts && !state.power.recently_resumed() {
+                failures += 1;
+            }
+            (failures >= MAX_HEALTH_FAILURES)
+                .then(|| format!("health check failed {} times in a row", failures))
+        };
+
+        let Some(reason) = reason else { continue };
+
+        attempt += 1;
+        let delay = backoff(attempt);
+        process.set_restarting(true);
+        notify_restarting(&app, attempt, delay, &reason);
+
+        process.kill();
+        tokio::time::sleep(delay).await;
+        if process.is_stopping() {
+            break;
+        }
+        if let Err(e) = super::spawn(&app) {
+            tracing::warn!("{}", e);
+        }
+    }
+}
+
+// `attempt` is 0 for restarts the user asked for
+pub fn notify_restarting(app: &AppHandle, attempt: u32, delay: Duration, reason: &str) {
+    logs::internal(
+        app,
+        Level::Warning,
+        format!("Restarting backend in {:?}: {}", delay, reason),
+    );
+    let _ = app.emit_all(
+        "backend-restarting",
+        RestartingPayload {
+            attempt,
+            delay_ms: delay.as_millis() as u64,
+            reason: reason.to_string(),
+        },
+    );
+}
+
+async fn is_healthy(app: &AppHandle, base_url: &str) -> bool {
+    let state = app.state::<AppState>();
+    let request = state
+        .http
+        .backend()
+        .get(format!("{}/health", base_url))
+        .headers(state.auth.headers(app).await)
+        .timeout(HEALTH_TIMEOUT);
+    match request.send().await {
+        Ok(response) => response.status().is_success(),
+        Err(_) => false,
+    }
+}
+
+fn backoff(attempt: u32) -> Duration {
+    let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+    INITIAL_BACKOFF.saturating_mul(factor).min(MAX_BACKOFF)
+}
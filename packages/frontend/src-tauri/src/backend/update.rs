@@ -0,0 +1,240 @@
+// Updates for the Python backend, which is released separately from the shell.
+// Bundles from the backend feed are downloaded into the app data dir, checked
+// against the feed's SHA-256 and the release signing key, and switched to by
+// rewriting `current.json` in one rename before the sidecar is restarted. The
+// binary shipped with the shell stays untouched as the fallback.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use super::logs::{self, Level};
+use super::{compat, SIDECAR_NAME};
+use crate::http::transfer;
+use crate::state::AppState;
+use crate::updater::{self, download};
+use crate::util::write_atomic;
+use crate::workers::Priority;
+
+const DIR_NAME: &str = "backend";
+const POINTER: &str = "current.json";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+const FEED: &str =
+    "https://github.com/ind4skylivey/aetherframe/releases/download/backend/latest.json";
+
+// `{"version", "platforms": {"<os>-<arch>": {"url", "sha256", "signature"}}}`,
+// keyed like the shell's update feed
+#[derive(Deserialize)]
+struct Feed {
+    version: String,
+    platforms: HashMap<String, Artifact>,
+}
+
+#[derive(Deserialize)]
+struct Artifact {
+    url: String,
+    sha256: String,
+    signature: String,
+}
+
+// What `current.json` records about the bundle in use
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Installed {
+    pub version: String,
+    pub sha256: String,
+    pub signature: String,
+}
+
+impl Installed {
+    pub fn path(&self, app: &AppHandle) -> Option<PathBuf> {
+        Some(bundle_path(&dir(app)?, &self.version))
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct BackendUpdate {
+    pub previous: Option<String>,
+    pub version: String,
+    pub updated: bool,
+}
+
+fn dir(app: &AppHandle) -> Option<PathBuf> {
+    app.path_resolver()
+        .app_local_data_dir()
+        .map(|dir| dir.join(DIR_NAME))
+}
+
+fn bundle_path(dir: &Path, version: &str) -> PathBuf {
+    dir.join(version)
+        .join(format!("{}{}", SIDECAR_NAME, std::env::consts::EXE_SUFFIX))
+}
+
+// The downloaded bundle to launch instead of the shipped one, if any
+pub fn installed(app: &AppHandle) -> Option<Installed> {
+    let text = fs::read_to_string(dir(app)?.join(POINTER)).ok()?;
+    let installed: Installed = serde_json::from_str(&text).ok()?;
+    // The version names a directory; never let it point anywhere else
+    Version::parse(&installed.version).ok()?;
+    Some(installed)
+}
+
+// Go back to the shipped binary, e.g. after a bundle fails its integrity check
+pub fn discard(app: &AppHandle) {
+    if let Some(dir) = dir(app) {
+        let _ = fs::remove_file(dir.join(POINTER));
+    }
+}
+
+// Drops every bundle but the one in use
+fn clean(dir: &Path, keep: &str) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if entry.path().is_dir() && entry.file_name() != keep {
+            let _ = fs::remove_dir_all(entry.path());
+        }
+    }
+}
+
+fn parse(version: &str) -> Option<Version> {
+    Version::parse(version.trim_start_matches('v')).ok()
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+        .map_err(|e| format!("Can't make the backend executable: {}", e))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<(), String> {
+    Ok(())
+}
+
+async fn update(app: &AppHandle, force: bool) -> Result<BackendUpdate, String> {
+    let state = app.state::<AppState>();
+    if state.backend.is_attached() {
+        return Err("The backend is managed externally and can't be updated from here".into());
+    }
+    // Signed with the same key as app updates; without it nothing would verify
+    if !updater::can_install(app) {
+        return Err("This build has no update signing key".into());
+    }
+    let running = state.jobs.running().len();
+    if running > 0 && !force {
+        return Err(format!("{} jobs are still running", running));
+    }
+
+    let url = state
+        .settings
+        .get()
+        .updater
+        .backend_feed_url
+        .unwrap_or_else(|| FEED.to_string());
+    let feed: Feed = state
+        .http
+        .client()
+        .get(&url)
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Backend update check failed: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Unexpected backend update feed: {}", e))?;
+    let version = parse(&feed.version)
+        .ok_or_else(|| format!("Backend feed has an invalid version: {}", feed.version))?;
+
+    // Older backends may not report a version; then the feed's is taken as newer
+    let previous = match installed(app) {
+        Some(installed) => Some(installed.version),
+        None => compat::backend_version(app).await.ok(),
+    };
+    if previous
+        .as_deref()
+        .and_then(parse)
+        .is_some_and(|p| p >= version)
+    {
+        return Ok(BackendUpdate {
+            previous: previous.clone(),
+            version: previous.unwrap_or_default(),
+            updated: false,
+        });
+    }
+
+    let target =
+        tauri::updater::target().ok_or("Backend updates aren't built for this platform")?;
+    let artifact = feed
+        .platforms
+        .get(&target)
+        .ok_or_else(|| format!("The backend feed has no bundle for {}", target))?;
+    let root = dir(app).ok_or("No app data directory")?;
+    let version = version.to_string();
+    let dest = bundle_path(&root, &version);
+    let request = state.http.client().get(&artifact.url);
+    let id = format!("backend-update:{}", version);
+    transfer::download(app, request, dest.clone(), Some(&artifact.sha256), Some(id)).await?;
+    let (handle, path, signature) = (app.clone(), dest.clone(), artifact.signature.clone());
+    let verified = state
+        .workers
+        .run(Priority::Normal, "verify backend update", move || {
+            download::verify(&handle, &path, &signature)
+        })
+        .await;
+    if let Err(e) = verified.and_then(|_| make_executable(&dest)) {
+        let _ = fs::remove_dir_all(root.join(&version));
+        return Err(e);
+    }
+
+    // The swap: one rename, so a crash leaves either the old bundle or the new one
+    let installed = Installed {
+        version: version.clone(),
+        sha256: artifact.sha256.to_ascii_lowercase(),
+        signature: artifact.signature.clone(),
+    };
+    let json = serde_json::to_vec_pretty(&installed).map_err(|e| e.to_string())?;
+    write_atomic(&root.join(POINTER), &json)
+        .map_err(|e| format!("Failed to switch to the new backend: {}", e))?;
+    logs::internal(
+        app,
+        Level::Info,
+        format!(
+            "Backend updated from {} to {}",
+            previous.as_deref().unwrap_or("unknown"),
+            version
+        ),
+    );
+    super::restart(app, "backend updated")?;
+    // After the restart so the old process no longer holds its files
+    clean(&root, &version);
+
+    let result = BackendUpdate {
+        previous,
+        version,
+        updated: true,
+    };
+    let _ = app.emit_all("backend-updated", &result);
+    Ok(result)
+}
+
+// Without `force`, refuses while backend jobs are running, like `install_update`
+#[tauri::command]
+pub async fn update_backend(app: AppHandle, force: Option<bool>) -> Result<BackendUpdate, String> {
+    let backend = &app.state::<AppState>().backend;
+    if backend.updating.swap(true, Ordering::SeqCst) {
+        return Err("A backend update is already in progress".into());
+    }
+    let result = update(&app, force.unwrap_or(false)).await;
+    backend.updating.store(false, Ordering::SeqCst);
+    result
+}
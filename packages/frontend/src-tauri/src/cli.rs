@@ -0,0 +1,38 @@
+// Command-line flags understood by the desktop shell
+
+use std::path::{Path, PathBuf};
+
+use crate::{deeplink, documents};
+
+#[derive(Default)]
+pub struct LaunchArgs {
+    // --attach <url>: use an already running backend instead of spawning one
+    pub attach: Option<String>,
+    // --minimized: start hidden in the tray (e.g. from autostart)
+    pub minimized: bool,
+    // aetherframe://...: the deep link the OS started us for
+    pub link: Option<String>,
+    // Project files to open, as given (relative to the launch directory)
+    pub files: Vec<PathBuf>,
+}
+
+impl LaunchArgs {
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Self {
+        let mut parsed = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if arg == "--attach" {
+                parsed.attach = args.next();
+            } else if let Some(url) = arg.strip_prefix("--attach=") {
+                parsed.attach = Some(url.to_string());
+            } else if arg == "--minimized" {
+                parsed.minimized = true;
+            } else if arg.starts_with(&format!("{}:", deeplink::SCHEME)) {
+                parsed.link = Some(arg);
+            } else if !arg.starts_with("--") && documents::is_project(Path::new(&arg)) {
+                parsed.files.push(PathBuf::from(arg));
+            }
+        }
+        parsed
+    }
+}
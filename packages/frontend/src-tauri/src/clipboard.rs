@@ -0,0 +1,142 @@
+// Native clipboard access, so copying results and pasting screenshots doesn't
+// depend on the webview's clipboard permissions. Images cross the bridge as
+// base64 PNG. Reading is limited to the main window, like other private data.
+
+use std::borrow::Cow;
+use std::sync::Mutex;
+
+use arboard::ImageData;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::state::AppState;
+use crate::util::decode_png;
+use crate::workers::Priority;
+
+// On X11 and Wayland what we copied lives only as long as the clipboard
+// handle that copied it, so one is kept for the app's lifetime
+#[derive(Default)]
+pub struct Clipboard {
+    handle: Mutex<Option<arboard::Clipboard>>,
+}
+
+#[derive(Serialize)]
+pub struct ClipboardImage {
+    pub width: usize,
+    pub height: usize,
+    // base64 PNG
+    pub png: String,
+}
+
+// Runs `f` on a worker thread with the shared handle, opening it on first use
+async fn with_clipboard<T, F>(app: AppHandle, f: F) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce(&mut arboard::Clipboard) -> Result<T, String> + Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<AppState>();
+        let mut handle = state.clipboard.handle.lock().unwrap();
+        if handle.is_none() {
+            let clipboard = arboard::Clipboard::new()
+                .map_err(|e| format!("Clipboard is not available: {}", e))?;
+            *handle = Some(clipboard);
+        }
+        f(handle.as_mut().unwrap())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn encode_png(image: &ImageData) -> Result<String, String> {
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, image.width as u32, image.height as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&image.bytes))
+        .map_err(|e| format!("Failed to encode the image: {}", e))?;
+    Ok(STANDARD.encode(png))
+}
+
+fn decode_base64_png(data: &str) -> Result<ImageData<'static>, String> {
+    // Accept a data URL straight from a canvas
+    let data = match data.split_once(";base64,") {
+        Some((prefix, rest)) if prefix.starts_with("data:") => rest,
+        _ => data,
+    };
+    let png = STANDARD
+        .decode(data.trim())
+        .map_err(|e| format!("Image is not valid base64: {}", e))?;
+    let image = decode_png(&png).map_err(|e| format!("Image is not a valid PNG: {}", e))?;
+    Ok(ImageData {
+        width: image.width as usize,
+        height: image.height as usize,
+        bytes: Cow::Owned(image.pixels),
+    })
+}
+
+#[tauri::command]
+pub async fn clipboard_write_text(app: AppHandle, text: String) -> Result<(), String> {
+    with_clipboard(app, move |clipboard| {
+        clipboard
+            .set_text(text)
+            .map_err(|e| format!("Failed to copy: {}", e))
+    })
+    .await
+}
+
+// None when the clipboard holds no text
+#[tauri::command]
+pub async fn clipboard_read_text(app: AppHandle) -> Result<Option<String>, String> {
+    with_clipboard(app, |clipboard| match clipboard.get_text() {
+        Ok(text) => Ok(Some(text)),
+        Err(arboard::Error::ContentNotAvailable) => Ok(None),
+        Err(e) => Err(format!("Failed to paste: {}", e)),
+    })
+    .await
+}
+
+// `png` is base64, optionally as a data: URL
+#[tauri::command]
+pub async fn clipboard_write_image(app: AppHandle, png: String) -> Result<(), String> {
+    let workers = &app.state::<AppState>().workers;
+    let image = workers
+        .run(Priority::High, "decode clipboard image", move || {
+            decode_base64_png(&png)
+        })
+        .await?;
+    with_clipboard(app, move |clipboard| {
+        clipboard
+            .set_image(image)
+            .map_err(|e| format!("Failed to copy the image: {}", e))
+    })
+    .await
+}
+
+// None when the clipboard holds no image
+#[tauri::command]
+pub async fn clipboard_read_image(app: AppHandle) -> Result<Option<ClipboardImage>, String> {
+    let image = with_clipboard(app.clone(), |clipboard| match clipboard.get_image() {
+        Ok(image) => Ok(Some(image.to_owned_img())),
+        Err(arboard::Error::ContentNotAvailable) => Ok(None),
+        Err(e) => Err(format!("Failed to paste the image: {}", e)),
+    })
+    .await?;
+    let Some(image) = image else {
+        return Ok(None);
+    };
+    let workers = &app.state::<AppState>().workers;
+    workers
+        .run(Priority::High, "encode clipboard image", move || {
+            Ok(Some(ClipboardImage {
+                width: image.width,
+                height: image.height,
+                png: encode_png(&image)?,
+            }))
+        })
+        .await
+}
@@ -0,0 +1,86 @@
+// The last thing a panic that takes the app down does: stop the backend so it
+// isn't left running on its own, then tell the user what happened instead of
+// the window just disappearing. Runs inside the panic hook, so it takes no
+// lock that might be held by the panicking thread and can't use Tauri's
+// dialogs, which would wait on the (now stuck) event loop.
+
+use std::sync::OnceLock;
+
+use rfd::{MessageButtons, MessageDialog, MessageLevel};
+use tauri::{AppHandle, Manager};
+
+use super::CrashReport;
+use crate::i18n;
+use crate::state::AppState;
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+pub fn set_app(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+}
+
+// A panic on the main thread ends the event loop, and with it the app; other
+// threads and async tasks are unwound and the app carries on
+pub fn is_fatal() -> bool {
+    cfg!(panic = "abort") || std::thread::current().name() == Some("main")
+}
+
+fn details(report: &CrashReport) -> String {
+    let mut details = format!(
+        "AetherFrame {} on {} ({})\n{}\nat {}\nthread {}\n",
+        report.app_version,
+        report.os,
+        report.arch,
+        report.message.as_deref().unwrap_or("Unknown panic"),
+        report.location.as_deref().unwrap_or("unknown location"),
+        report.thread.as_deref().unwrap_or("<unnamed>"),
+    );
+    if let Some(backtrace) = &report.backtrace {
+        details.push('\n');
+        details.push_str(backtrace);
+    }
+    details
+}
+
+fn copy(text: String) {
+    let copied = arboard::Clipboard::new().and_then(|mut clipboard| clipboard.set_text(text));
+    if let Err(e) = copied {
+        tracing::warn!("Failed to copy the crash details: {}", e);
+    }
+}
+
+pub fn handle(report: &CrashReport, log_dir: Option<&std::path::Path>) {
+    let app = APP.get();
+    let state = app.and_then(|app| app.try_state::<AppState>());
+    let settings = state
+        .as_ref()
+        .and_then(|state| state.settings.try_get())
+        .unwrap_or_default();
+    if let Some(state) = &state {
+        tracing::info!("Shutting down the backend after a fatal error");
+        state.backend.shutdown_now();
+    }
+
+    let language = i18n::language(&settings);
+    let message = i18n::format(
+        language,
+        "crash.fatal_message",
+        &[report.message.as_deref().unwrap_or("")],
+    );
+    let copy_details = MessageDialog::new()
+        .set_level(MessageLevel::Error)
+        .set_title(i18n::lookup(language, "crash.fatal_title"))
+        .set_description(&message)
+        .set_buttons(MessageButtons::OkCancelCustom(
+            i18n::lookup(language, "crash.copy_details").to_string(),
+            i18n::lookup(language, "crash.open_logs").to_string(),
+        ))
+        .show();
+    if copy_details {
+        copy(details(report));
+    } else if let Some(dir) = log_dir {
+        if let Err(e) = open::that(dir) {
+            tracing::warn!("Failed to open {}: {}", dir.display(), e);
+        }
+    }
+}
@@ -0,0 +1,56 @@
+// Native crashes on Windows: an unhandled-exception filter writes a minidump
+// (stacks and thread info only, no heap) next to the crash reports, then lets
+// Windows carry on with its own handling. The report for it is put together
+// on the next launch, see `collect`.
+
+use std::fs::File;
+use std::os::windows::io::AsRawHandle;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use windows::Win32::Foundation::HANDLE;
+use windows::Win32::System::Diagnostics::Debug::{
+    MiniDumpNormal, MiniDumpWithThreadInfo, MiniDumpWriteDump, SetUnhandledExceptionFilter,
+    EXCEPTION_POINTERS, MINIDUMP_EXCEPTION_INFORMATION,
+};
+use windows::Win32::System::Threading::{
+    GetCurrentProcess, GetCurrentProcessId, GetCurrentThreadId,
+};
+
+// Let the next filter (or Windows Error Reporting) run too
+const EXCEPTION_CONTINUE_SEARCH: i32 = 0;
+
+static DUMP_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+unsafe extern "system" fn on_exception(pointers: *const EXCEPTION_POINTERS) -> i32 {
+    let Some(dir) = DUMP_DIR.get() else {
+        return EXCEPTION_CONTINUE_SEARCH;
+    };
+    let path = dir.join(format!("{}{}", super::new_id(), super::DUMP_EXTENSION));
+    let Ok(file) = File::create(&path) else {
+        return EXCEPTION_CONTINUE_SEARCH;
+    };
+    let exception = MINIDUMP_EXCEPTION_INFORMATION {
+        ThreadId: GetCurrentThreadId(),
+        ExceptionPointers: pointers as *mut _,
+        ClientPointers: false.into(),
+    };
+    MiniDumpWriteDump(
+        GetCurrentProcess(),
+        GetCurrentProcessId(),
+        HANDLE(file.as_raw_handle() as isize),
+        MiniDumpNormal | MiniDumpWithThreadInfo,
+        &exception,
+        std::ptr::null(),
+        std::ptr::null(),
+    );
+    EXCEPTION_CONTINUE_SEARCH
+}
+
+pub fn install(dir: PathBuf) {
+    if DUMP_DIR.set(dir).is_ok() {
+        unsafe {
+            SetUnhandledExceptionFilter(Some(on_exception));
+        }
+    }
+}
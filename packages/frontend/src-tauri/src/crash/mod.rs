@@ -0,0 +1,449 @@
+// Crash reports, kept in the app's local data dir until the user agrees to
+// send them. Three kinds are caught: Rust panics (with a backtrace), native
+// crashes on Windows (a minidump, see `minidump`), and on every platform a
+// session that never reached a clean exit, found by a marker file left behind.
+// Each report carries the last lines of the log before it happened. A panic
+// that takes the app down also stops the backend and says so, see `fatal`.
+//
+// On the next launch pending reports are offered for `crash.endpoint`,
+// according to `crash.consent`; a report is scrubbed of personal details (see
+// `scrub`) only on its way out, so the copy on disk stays useful locally.
+// Minidumps can't be scrubbed (stack memory may hold tokens and paths), so they
+// stay behind unless `submit_crash_reports` is asked for them explicitly.
+
+mod fatal;
+#[cfg(windows)]
+mod minidump;
+mod scrub;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{Duration, UNIX_EPOCH};
+
+use reqwest::multipart::{Form, Part};
+use serde::{Deserialize, Serialize};
+use tauri::api::dialog;
+use tauri::{AppHandle, Manager};
+
+use crate::i18n;
+use crate::logging;
+use crate::state::AppState;
+use crate::storage::blocking;
+use crate::util::{now_millis, write_atomic};
+
+const DIR_NAME: &str = "crashes";
+// Holds the start time of the session running now; gone after a clean exit
+const MARKER: &str = "running";
+const REPORT_EXTENSION: &str = ".json";
+const DUMP_EXTENSION: &str = ".dmp";
+const KEEP_REPORTS: usize = 20;
+const LOG_LINES: usize = 50;
+const SUBMIT_TIMEOUT: Duration = Duration::from_secs(60);
+// Lets the main window come up before asking
+const ASK_DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Consent {
+    Ask,
+    Always,
+    Never,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CrashSettings {
+    // Where reports are POSTed; None keeps them on this machine
+    pub endpoint: Option<String>,
+    pub consent: Consent,
+}
+
+impl Default for CrashSettings {
+    fn default() -> Self {
+        Self {
+            endpoint: None,
+            consent: Consent::Ask,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Kind {
+    Panic,
+    Native,
+    UncleanExit,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Pending,
+    Submitted,
+    Declined,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CrashReport {
+    pub id: String,
+    pub kind: Kind,
+    pub status: Status,
+    pub created_at: u64,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub message: Option<String>,
+    pub location: Option<String>,
+    pub thread: Option<String>,
+    pub backtrace: Option<String>,
+    // Raw log lines, oldest first
+    pub logs: Vec<String>,
+    // A .dmp file with the same id sits next to the report
+    pub minidump: bool,
+}
+
+struct Session {
+    dir: PathBuf,
+    log_dir: Option<PathBuf>,
+    app_version: String,
+    started_at: u64,
+}
+
+static SESSION: OnceLock<Session> = OnceLock::new();
+
+fn new_id() -> String {
+    format!("{}-{:08x}", now_millis(), rand::random::<u32>())
+}
+
+impl Session {
+    fn report(&self, kind: Kind, created_at: u64) -> CrashReport {
+        CrashReport {
+            id: new_id(),
+            kind,
+            status: Status::Pending,
+            created_at,
+            app_version: self.app_version.clone(),
+            os: sysinfo::System::long_os_version()
+                .unwrap_or_else(|| std::env::consts::OS.to_string()),
+            arch: std::env::consts::ARCH.to_string(),
+            message: None,
+            location: None,
+            thread: None,
+            backtrace: None,
+            logs: Vec::new(),
+            minidump: false,
+        }
+    }
+
+    fn logs_before(&self, before: u64) -> Vec<String> {
+        self.log_dir
+            .as_deref()
+            .map(|dir| logging::query::recent_lines(dir, LOG_LINES, before))
+            .unwrap_or_default()
+    }
+}
+
+fn save(dir: &Path, report: &CrashReport) -> Result<(), String> {
+    let json = serde_json::to_vec_pretty(report).map_err(|e| e.to_string())?;
+    let path = dir.join(format!("{}{}", report.id, REPORT_EXTENSION));
+    write_atomic(&path, &json).map_err(|e| format!("Failed to save crash report: {}", e))
+}
+
+// Newest first
+fn load_all(dir: &Path) -> Vec<CrashReport> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut reports: Vec<CrashReport> = entries
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .ends_with(REPORT_EXTENSION)
+        })
+        .filter_map(|entry| fs::read(entry.path()).ok())
+        .filter_map(|bytes| serde_json::from_slice(&bytes).ok())
+        .collect();
+    reports.sort_by_key(|report: &CrashReport| std::cmp::Reverse(report.created_at));
+    reports
+}
+
+fn remove(dir: &Path, id: &str) {
+    for extension in [REPORT_EXTENSION, DUMP_EXTENSION] {
+        let _ = fs::remove_file(dir.join(format!("{}{}", id, extension)));
+    }
+}
+
+// Reports for whatever the last session left behind: minidumps nobody wrote a
+// report for, and the marker of a session that never exited cleanly
+fn collect(session: &Session, previous_start: Option<u64>) {
+    let mut reports = load_all(&session.dir);
+    let dumps = fs::read_dir(&session.dir).into_iter().flatten().flatten();
+    for entry in dumps {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let Some(id) = name.strip_suffix(DUMP_EXTENSION) else {
+            continue;
+        };
+        if reports.iter().any(|report| report.id == id) {
+            continue;
+        }
+        let created_at = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map_or(session.started_at, |d| d.as_millis() as u64);
+        let mut report = session.report(Kind::Native, created_at);
+        report.id = id.to_string();
+        report.minidump = true;
+        report.logs = session.logs_before(created_at);
+        reports.push(report.clone());
+        if let Err(e) = save(&session.dir, &report) {
+            tracing::warn!("{}", e);
+        }
+    }
+    if let Some(previous_start) = previous_start {
+        let explained = reports.iter().any(|r| r.created_at >= previous_start);
+        if !explained {
+            let mut report = session.report(Kind::UncleanExit, session.started_at);
+            report.message = Some("The previous session ended without shutting down".to_string());
+            report.logs = session.logs_before(session.started_at);
+            reports.push(report.clone());
+            if let Err(e) = save(&session.dir, &report) {
+                tracing::warn!("{}", e);
+            }
+        }
+    }
+    reports.sort_by_key(|report| std::cmp::Reverse(report.created_at));
+    for old in reports.iter().skip(KEEP_REPORTS) {
+        remove(&session.dir, &old.id);
+    }
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Box<dyn Any>".to_string()
+    }
+}
+
+// Called first thing in `main`, so even a panic during setup is caught
+pub fn install(config: &tauri::Config, app_version: &str) {
+    let Some(dir) = tauri::api::path::app_local_data_dir(config).map(|dir| dir.join(DIR_NAME))
+    else {
+        return;
+    };
+    if let Err(e) = fs::create_dir_all(&dir) {
+        tracing::warn!("Crash reports are off: {}: {}", dir.display(), e);
+        return;
+    }
+    let session = Session {
+        log_dir: logging::log_dir(config),
+        app_version: app_version.to_string(),
+        started_at: now_millis(),
+        dir,
+    };
+    let marker = session.dir.join(MARKER);
+    let previous_start = fs::read_to_string(&marker)
+        .ok()
+        .map(|start| start.trim().parse().unwrap_or(0));
+    collect(&session, previous_start);
+    if let Err(e) = fs::write(&marker, session.started_at.to_string()) {
+        tracing::warn!("Failed to write {}: {}", marker.display(), e);
+    }
+    #[cfg(windows)]
+    minidump::install(session.dir.clone());
+    if SESSION.set(session).is_err() {
+        return;
+    }
+
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if let Some(session) = SESSION.get() {
+            let message = panic_message(info.payload());
+            let location = info.location().map(|l| l.to_string());
+            let backtrace = std::backtrace::Backtrace::force_capture().to_string();
+            tracing::error!(
+                backtrace = %backtrace,
+                "Panic at {}: {}",
+                location.as_deref().unwrap_or("unknown location"),
+                message
+            );
+            let mut report = session.report(Kind::Panic, now_millis());
+            report.thread = std::thread::current().name().map(str::to_string);
+            report.backtrace = Some(backtrace);
+            report.logs = session.logs_before(u64::MAX);
+            report.message = Some(message);
+            report.location = location;
+            if let Err(e) = save(&session.dir, &report) {
+                tracing::warn!("{}", e);
+            }
+            if fatal::is_fatal() {
+                fatal::handle(&report, session.log_dir.as_deref());
+            }
+        }
+        previous(info);
+    }));
+}
+
+// Wherever the app quits on purpose
+pub fn clean_exit() {
+    if let Some(session) = SESSION.get() {
+        let _ = fs::remove_file(session.dir.join(MARKER));
+    }
+}
+
+fn session() -> Result<&'static Session, String> {
+    SESSION.get().ok_or("Crash reports are off".to_string())
+}
+
+async fn submit(
+    app: &AppHandle,
+    reports: Vec<CrashReport>,
+    minidumps: bool,
+) -> Result<usize, String> {
+    let state = app.state::<AppState>();
+    let endpoint = state
+        .settings
+        .get()
+        .crash
+        .endpoint
+        .ok_or("No crash report endpoint is configured (crash.endpoint)")?;
+    let dir = &session()?.dir;
+    let mut sent = 0;
+    for mut report in reports {
+        let json = serde_json::to_string(&report).map_err(|e| e.to_string())?;
+        let mut form = Form::new().text("report", scrub::scrub(&json));
+        if report.minidump && minidumps {
+            let name = format!("{}{}", report.id, DUMP_EXTENSION);
+            if let Ok(bytes) = tokio::fs::read(dir.join(&name)).await {
+                form = form.part("minidump", Part::bytes(bytes).file_name(name));
+            }
+        }
+        state
+            .http
+            .client()
+            .post(&endpoint)
+            .multipart(form)
+            .timeout(SUBMIT_TIMEOUT)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("Failed to send crash report {}: {}", report.id, e))?;
+        report.status = Status::Submitted;
+        save(dir, &report)?;
+        sent += 1;
+    }
+    Ok(sent)
+}
+
+fn decline(reports: &[CrashReport]) {
+    let Ok(session) = session() else {
+        return;
+    };
+    for report in reports {
+        let mut report = report.clone();
+        report.status = Status::Declined;
+        if let Err(e) = save(&session.dir, &report) {
+            tracing::warn!("{}", e);
+        }
+    }
+}
+
+// Offers what the last session left, once the window is up
+pub fn start(app: &AppHandle) {
+    fatal::set_app(app);
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(ASK_DELAY).await;
+        let Ok(session) = session() else {
+            return;
+        };
+        let settings = app.state::<AppState>().settings.get();
+        if settings.crash.endpoint.is_none() || settings.crash.consent == Consent::Never {
+            return;
+        }
+        let pending: Vec<CrashReport> = load_all(&session.dir)
+            .into_iter()
+            .filter(|report| report.status == Status::Pending)
+            .collect();
+        if pending.is_empty() {
+            return;
+        }
+        if settings.crash.consent == Consent::Always {
+            if let Err(e) = submit(&app, pending, false).await {
+                tracing::warn!("{}", e);
+            }
+            return;
+        }
+        let language = i18n::language(&settings);
+        let title = i18n::lookup(language, "crash.title");
+        let message = match pending.len() {
+            1 => i18n::lookup(language, "crash.message_one").to_string(),
+            n => i18n::format(language, "crash.message_many", &[&n.to_string()]),
+        };
+        let handle = app.clone();
+        dialog::ask(None::<&tauri::Window>, title, message, move |send| {
+            if !send {
+                decline(&pending);
+                return;
+            }
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = submit(&handle, pending, false).await {
+                    tracing::warn!("{}", e);
+                }
+            });
+        });
+    });
+}
+
+// Newest first
+#[tauri::command]
+pub async fn list_crash_reports(app: AppHandle) -> Result<Vec<CrashReport>, String> {
+    blocking(app, |_| Ok(load_all(&session()?.dir))).await
+}
+
+// Without `ids`, sends every pending report. Minidumps go along only with
+// `include_minidumps`, which the UI should ask about on its own. Returns how
+// many were sent
+#[tauri::command]
+pub async fn submit_crash_reports(
+    app: AppHandle,
+    ids: Option<Vec<String>>,
+    include_minidumps: Option<bool>,
+) -> Result<usize, String> {
+    let reports = blocking(app.clone(), |_| Ok(load_all(&session()?.dir))).await?;
+    let reports = reports
+        .into_iter()
+        .filter(|report| match &ids {
+            Some(ids) => ids.contains(&report.id),
+            None => report.status == Status::Pending,
+        })
+        .collect();
+    submit(&app, reports, include_minidumps.unwrap_or(false)).await
+}
+
+// Without `ids`, deletes them all
+#[tauri::command]
+pub async fn delete_crash_reports(
+    app: AppHandle,
+    ids: Option<Vec<String>>,
+) -> Result<usize, String> {
+    blocking(app, move |_| {
+        let dir = &session()?.dir;
+        let mut removed = 0;
+        for report in load_all(dir) {
+            if ids.as_ref().map_or(true, |ids| ids.contains(&report.id)) {
+                remove(dir, &report.id);
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    })
+    .await
+}
@@ -0,0 +1,66 @@
+// Strips personal details from a crash report before it leaves the machine:
+// the home folder (and with it the user name in most paths), the computer's
+// name, email and IP addresses, and anything that looks like a credential.
+
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+struct Patterns {
+    email: Regex,
+    ipv4: Regex,
+    bearer: Regex,
+    secret_param: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| Patterns {
+        email: Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}").unwrap(),
+        // Loopback stays: it's never personal and often the point
+        ipv4: Regex::new(r"\b(\d{1,3})\.\d{1,3}\.\d{1,3}\.\d{1,3}\b").unwrap(),
+        bearer: Regex::new(r"(?i)\b(bearer|basic)\s+[A-Za-z0-9._~+/=-]+").unwrap(),
+        secret_param: Regex::new(
+            r#"(?i)\b(token|access_token|refresh_token|api_key|apikey|key|password|passwd|secret|auth)=[^&\s"']+"#,
+        )
+        .unwrap(),
+    })
+}
+
+// Paths can show up with either separator, and doubled inside JSON strings
+fn path_variants(path: &str) -> Vec<String> {
+    let forward = path.replace('\\', "/");
+    let back = path.replace('/', "\\");
+    let mut variants = vec![back.replace('\\', "\\\\"), back, forward];
+    variants.sort_by_key(|v| std::cmp::Reverse(v.len()));
+    variants.dedup();
+    variants
+}
+
+pub fn scrub(text: &str) -> String {
+    let mut text = text.to_string();
+    if let Some(home) = tauri::api::path::home_dir() {
+        let home = home.to_string_lossy().into_owned();
+        // "/" or "C:\" on its own would take out every path separator
+        if home.len() > 3 {
+            for variant in path_variants(&home) {
+                text = text.replace(&variant, "~");
+            }
+        }
+    }
+    if let Some(host) = sysinfo::System::host_name().filter(|host| host.len() >= 3) {
+        text = text.replace(&host, "<host>");
+    }
+    let patterns = patterns();
+    let text = patterns.email.replace_all(&text, "<email>");
+    let text = patterns.ipv4.replace_all(&text, |caps: &regex::Captures| {
+        if &caps[1] == "127" {
+            caps[0].to_string()
+        } else {
+            "<ip>".to_string()
+        }
+    });
+    let text = patterns.bearer.replace_all(&text, "$1 <redacted>");
+    let text = patterns.secret_param.replace_all(&text, "$1=<redacted>");
+    text.into_owned()
+}
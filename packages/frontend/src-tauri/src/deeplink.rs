@@ -0,0 +1,200 @@
+// aetherframe:// links from emails, docs or the backend's web UI. The scheme
+// is registered with the OS at startup (Info.plist on macOS). A link
+// launches the app, or focuses the running one, and reaches the frontend as
+// `deep-link` with the URL already split up: `aetherframe://job/123?tab=logs`
+// becomes route "/job/123", segments ["job", "123"] and query {tab: "logs"}.
+// Links come from anywhere, so the frontend treats them like user input.
+// aetherframe://notification/<id>/<action> links are how Windows toasts
+// report a click, and are answered by `notifications` instead.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use percent_encoding::percent_decode_str;
+use reqwest::Url;
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Window};
+
+use crate::notifications::native;
+use crate::state::AppState;
+use crate::window;
+
+pub const SCHEME: &str = "aetherframe";
+
+#[derive(Clone, Serialize)]
+pub struct DeepLink {
+    pub url: String,
+    pub route: String,
+    pub segments: Vec<String>,
+    pub query: HashMap<String, String>,
+}
+
+// Held until the main window's page has loaded and can listen for it
+#[derive(Default)]
+pub struct DeepLinks {
+    inbox: Mutex<Inbox>,
+}
+
+#[derive(Default)]
+struct Inbox {
+    loaded: bool,
+    pending: Option<DeepLink>,
+}
+
+pub fn parse(raw: &str) -> Result<DeepLink, String> {
+    let url = Url::parse(raw.trim()).map_err(|e| format!("Invalid link {:?}: {}", raw, e))?;
+    if url.scheme() != SCHEME {
+        return Err(format!("Not an {} link: {}", SCHEME, raw));
+    }
+    // The first part lands in the host for "aetherframe://job/123" but in the
+    // path for "aetherframe:job/123"
+    let segments: Vec<String> = url
+        .host_str()
+        .into_iter()
+        .chain(url.path().split('/'))
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| percent_decode_str(segment).decode_utf8_lossy().into_owned())
+        .collect();
+    let route = format!("/{}", segments.join("/"));
+    Ok(DeepLink {
+        url: url.to_string(),
+        route,
+        segments,
+        query: url.query_pairs().into_owned().collect(),
+    })
+}
+
+// Entry point for every link, however it arrived
+pub fn open(app: &AppHandle, raw: &str) {
+    let link = match parse(raw) {
+        Ok(link) => link,
+        Err(e) => {
+            tracing::warn!("{}", e);
+            return;
+        }
+    };
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let mut inbox = state.links.inbox.lock().unwrap();
+    if !inbox.loaded {
+        // Startup shows the window itself once the backend is up
+        inbox.pending = Some(link);
+        return;
+    }
+    drop(inbox);
+    deliver(app, link);
+}
+
+// Notification links are answered by the shell; everything else is the
+// frontend's to route
+fn deliver(app: &AppHandle, link: DeepLink) {
+    if native::open_link(app, &link) {
+        return;
+    }
+    if let Some(main) = window::activate(app) {
+        let _ = main.emit("deep-link", link);
+    }
+}
+
+pub fn on_page_load(window: &Window) {
+    if window.label() != window::MAIN {
+        return;
+    }
+    let Some(state) = window.try_state::<AppState>() else {
+        return;
+    };
+    let mut inbox = state.links.inbox.lock().unwrap();
+    inbox.loaded = true;
+    if let Some(link) = inbox.pending.take() {
+        drop(inbox);
+        deliver(&window.app_handle(), link);
+    }
+}
+
+// Claims the scheme for this executable. Runs on every start, so the handler
+// follows the app when it's moved or updated in place.
+pub fn register(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        if let Err(e) = platform::register(&app) {
+            tracing::warn!("Failed to register {}:// links: {}", SCHEME, e);
+        }
+    });
+}
+
+#[cfg(windows)]
+mod platform {
+    use tauri::AppHandle;
+
+    use super::SCHEME;
+    use crate::util::{launcher, set_registry_string};
+
+    pub fn register(app: &AppHandle) -> Result<(), String> {
+        let program = launcher()?.display().to_string();
+        let key = format!(r"Software\Classes\{}", SCHEME);
+        let name = format!("URL:{}", app.package_info().name);
+        set_registry_string(&key, None, &name)?;
+        set_registry_string(&key, Some("URL Protocol"), "")?;
+        set_registry_string(
+            &format!(r"{}\DefaultIcon", key),
+            None,
+            &format!("\"{}\",0", program),
+        )?;
+        set_registry_string(
+            &format!(r"{}\shell\open\command", key),
+            None,
+            &format!("\"{}\" \"%1\"", program),
+        )
+    }
+}
+
+// Declared in the bundle's Info.plist; Launch Services does the rest
+#[cfg(target_os = "macos")]
+mod platform {
+    use tauri::AppHandle;
+
+    pub fn register(_app: &AppHandle) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+// A hidden desktop entry of our own, since the one the bundle installs (if
+// any) doesn't list the scheme
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use std::process::Command;
+
+    use tauri::AppHandle;
+
+    use super::SCHEME;
+    use crate::util::{exec_quote, launcher, write_atomic};
+
+    pub fn register(app: &AppHandle) -> Result<(), String> {
+        let program = launcher()?;
+        let file = format!("{}-url-handler.desktop", app.package_info().crate_name);
+        let dir = tauri::api::path::data_dir()
+            .ok_or("No data directory")?
+            .join("applications");
+        let path = dir.join(&file);
+        let mime = format!("x-scheme-handler/{}", SCHEME);
+        let contents = format!(
+            "[Desktop Entry]\nType=Application\nName={}\nExec={} %u\nTerminal=false\nNoDisplay=true\nMimeType={};\n",
+            app.package_info().name,
+            exec_quote(&program.to_string_lossy()),
+            mime
+        );
+        write_atomic(&path, contents.as_bytes())
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        let status = Command::new("xdg-mime")
+            .args(["default", &file, &mime])
+            .status()
+            .map_err(|e| format!("Failed to run xdg-mime: {}", e))?;
+        if !status.success() {
+            return Err(format!("xdg-mime exited with {}", status));
+        }
+        // Not every desktop rescans on its own; missing is fine
+        let _ = Command::new("update-desktop-database").arg(dir).status();
+        Ok(())
+    }
+}
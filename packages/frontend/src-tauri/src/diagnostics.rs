@@ -0,0 +1,204 @@
+// One zip for bug reports: the last few days of the shell's log files and the
+// backend's recent output, the settings with secrets redacted, what we know
+// about the backend (version, health, resources), the webview's errors, how
+// long commands take and the machine it runs on.
+// Like `export_data`, it's written next to the target and renamed into place
+// once complete.
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::backend::compat;
+use crate::backend::metrics::BackendMetrics;
+use crate::health::HealthSnapshot;
+use crate::logging;
+use crate::logging::commands::{self, CommandMetrics};
+use crate::logging::frontend::FrontendErrorStats;
+use crate::settings::secret;
+use crate::state::AppState;
+use crate::system_info::{self, SystemInfo};
+use crate::util::now_millis;
+use crate::workers::Priority;
+
+const LOG_FILES: usize = 3;
+const BACKEND_LOG_LINES: usize = 2000;
+// The backend may be the reason for the report; don't wait long on it
+const VERSION_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Serialize)]
+struct Summary {
+    app_version: String,
+    created_at: u64,
+    files: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct BackendInfo {
+    url: String,
+    attached: bool,
+    running: bool,
+    pid: Option<u32>,
+    version: Option<String>,
+    // Why there's no version
+    error: Option<String>,
+    health: HealthSnapshot,
+    resources: Option<BackendMetrics>,
+}
+
+#[derive(Serialize)]
+pub struct DiagnosticsResult {
+    pub path: PathBuf,
+    pub bytes: u64,
+    pub files: Vec<String>,
+}
+
+// Everything that needs the backend or app state, gathered before the slow part
+struct Contents {
+    settings: Value,
+    backend: BackendInfo,
+    backend_log: String,
+    log_files: Vec<PathBuf>,
+    frontend_errors: FrontendErrorStats,
+    commands: Vec<CommandMetrics>,
+    system: SystemInfo,
+}
+
+async fn gather(app: &AppHandle) -> Result<Contents, String> {
+    let state = app.state::<AppState>();
+    let mut settings = serde_json::to_value(state.settings.get()).map_err(|e| e.to_string())?;
+    secret::redact(&mut settings);
+    let version = match state.backend.ensure_available() {
+        Ok(()) => tokio::time::timeout(VERSION_TIMEOUT, compat::backend_version(app))
+            .await
+            .unwrap_or_else(|_| Err("Timed out".to_string())),
+        Err(e) => Err(e),
+    };
+    let backend = BackendInfo {
+        url: state.backend.url(),
+        attached: state.backend.is_attached(),
+        running: state.backend.is_running(),
+        pid: state.backend.pid(),
+        error: version.as_ref().err().cloned(),
+        version: version.ok(),
+        health: state.health.snapshot(),
+        resources: state
+            .backend
+            .pid()
+            .and_then(|pid| state.resources.latest(pid)),
+    };
+    let mut backend_log = String::new();
+    for line in state.logs.tail(BACKEND_LOG_LINES) {
+        backend_log.push_str(&serde_json::to_string(&line).map_err(|e| e.to_string())?);
+        backend_log.push('\n');
+    }
+    let log_files = logging::log_dir(&app.config())
+        .map(|dir| logging::query::log_files(&dir))
+        .unwrap_or_default()
+        .into_iter()
+        .take(LOG_FILES)
+        .collect();
+    let handle = app.clone();
+    let system = tauri::async_runtime::spawn_blocking(move || system_info::collect(&handle))
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(Contents {
+        settings,
+        backend,
+        backend_log,
+        log_files,
+        frontend_errors: state.frontend_errors.stats(),
+        commands: commands::snapshot(),
+        system,
+    })
+}
+
+fn json<T: Serialize>(value: &T) -> Result<Vec<u8>, String> {
+    serde_json::to_vec_pretty(value).map_err(|e| e.to_string())
+}
+
+fn write_zip(contents: Contents, path: &Path) -> Result<Vec<String>, String> {
+    let mut entries: Vec<(String, Vec<u8>)> = vec![
+        ("settings.json".to_string(), json(&contents.settings)?),
+        ("backend.json".to_string(), json(&contents.backend)?),
+        ("system.json".to_string(), json(&contents.system)?),
+        (
+            "frontend_errors.json".to_string(),
+            json(&contents.frontend_errors)?,
+        ),
+        ("commands.json".to_string(), json(&contents.commands)?),
+        (
+            "logs/backend.ndjson".to_string(),
+            contents.backend_log.into_bytes(),
+        ),
+    ];
+    for file in &contents.log_files {
+        let Some(name) = file.file_name() else {
+            continue;
+        };
+        match fs::read(file) {
+            Ok(bytes) => entries.push((format!("logs/{}", name.to_string_lossy()), bytes)),
+            Err(e) => tracing::warn!("Leaving {} out of diagnostics: {}", file.display(), e),
+        }
+    }
+    let mut files: Vec<String> = entries.iter().map(|(name, _)| name.clone()).collect();
+    files.insert(0, "summary.json".to_string());
+    let summary = Summary {
+        app_version: contents.system.app_version.clone(),
+        created_at: now_millis(),
+        files: files.clone(),
+    };
+    entries.insert(0, ("summary.json".to_string(), json(&summary)?));
+
+    let file = File::create(path).map_err(|e| e.to_string())?;
+    let mut zip = ZipWriter::new(file);
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (name, bytes) in entries {
+        zip.start_file(name, options).map_err(|e| e.to_string())?;
+        zip.write_all(&bytes).map_err(|e| e.to_string())?;
+    }
+    zip.finish().map_err(|e| e.to_string())?;
+    Ok(files)
+}
+
+// `path` usually comes from `save_file_dialog`, e.g. "aetherframe-diagnostics.zip"
+#[tauri::command]
+pub async fn export_diagnostics(
+    app: AppHandle,
+    path: PathBuf,
+) -> Result<DiagnosticsResult, String> {
+    if !path.is_absolute() || path.parent().map_or(true, |dir| !dir.is_dir()) {
+        return Err(format!(
+            "{} is not a file in an existing folder",
+            path.display()
+        ));
+    }
+    let contents = gather(&app).await?;
+    let target = path.clone();
+    let workers = &app.state::<AppState>().workers;
+    let files = workers
+        .run(Priority::Low, "diagnostics bundle", move || {
+            let mut tmp = target.as_os_str().to_owned();
+            tmp.push(".tmp");
+            let tmp = PathBuf::from(tmp);
+            let written = write_zip(contents, &tmp);
+            let files = written.and_then(|files| {
+                fs::rename(&tmp, &target).map_err(|e| e.to_string())?;
+                Ok(files)
+            });
+            if files.is_err() {
+                let _ = fs::remove_file(&tmp);
+            }
+            files.map_err(|e| format!("Failed to write {}: {}", target.display(), e))
+        })
+        .await?;
+    let bytes = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    Ok(DiagnosticsResult { path, bytes, files })
+}
@@ -0,0 +1,142 @@
+// Native open/save dialogs, so the frontend gets paths the user actually chose
+// instead of needing fs access of its own. Dialogs are modal to the window
+// that asked. Cancelling resolves to None (or an empty list); what comes back
+// is checked first: picked files and folders must exist, and a save target
+// must be in an existing folder.
+
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use tauri::api::dialog::FileDialogBuilder;
+use tauri::Window;
+use tokio::sync::oneshot;
+
+#[derive(Deserialize)]
+pub struct Filter {
+    pub name: String,
+    // Without the dot, e.g. ["png", "jpg"]
+    pub extensions: Vec<String>,
+}
+
+fn builder(window: &Window, filters: &[Filter]) -> FileDialogBuilder {
+    let mut builder = FileDialogBuilder::new().set_parent(window);
+    for filter in filters {
+        let extensions: Vec<&str> = filter
+            .extensions
+            .iter()
+            .map(|ext| ext.trim_start_matches('.'))
+            .collect();
+        builder = builder.add_filter(&filter.name, &extensions);
+    }
+    builder
+}
+
+// The dialog calls back on its own thread once it's closed
+async fn show<T: Send + 'static>(
+    open: impl FnOnce(Box<dyn FnOnce(T) + Send>),
+) -> Result<T, String> {
+    let (tx, rx) = oneshot::channel();
+    open(Box::new(move |picked| {
+        let _ = tx.send(picked);
+    }));
+    rx.await
+        .map_err(|_| "The dialog closed unexpectedly".to_string())
+}
+
+fn existing(path: PathBuf, folder: bool) -> Result<PathBuf, String> {
+    let path = path
+        .canonicalize()
+        .map_err(|e| format!("Can't use {}: {}", path.display(), e))?;
+    match (folder, path.is_dir()) {
+        (true, false) => Err(format!("{} is not a folder", path.display())),
+        (false, true) => Err(format!("{} is a folder", path.display())),
+        _ => Ok(path),
+    }
+}
+
+// Some platforms don't add the filter's extension to a bare name themselves
+fn save_target(path: PathBuf, filters: &[Filter]) -> Result<PathBuf, String> {
+    let name = path
+        .file_name()
+        .filter(|name| !name.is_empty())
+        .ok_or("No file name given")?
+        .to_os_string();
+    let folder = path
+        .parent()
+        .filter(|dir| dir.is_dir())
+        .ok_or_else(|| format!("{} is not in an existing folder", path.display()))?;
+    let mut target = folder.canonicalize().map_err(|e| e.to_string())?.join(name);
+    let default_extension = filters.first().and_then(|f| f.extensions.first());
+    if let (None, Some(ext)) = (target.extension(), default_extension) {
+        target.set_extension(ext.trim_start_matches('.'));
+    }
+    if target.is_dir() {
+        return Err(format!("{} is a folder", target.display()));
+    }
+    Ok(target)
+}
+
+#[tauri::command]
+pub async fn pick_file(
+    window: Window,
+    filters: Option<Vec<Filter>>,
+) -> Result<Option<PathBuf>, String> {
+    let filters = filters.unwrap_or_default();
+    let dialog = builder(&window, &filters);
+    let picked = show(|done| dialog.pick_file(done)).await?;
+    picked.map(|path| existing(path, false)).transpose()
+}
+
+#[tauri::command]
+pub async fn pick_files(
+    window: Window,
+    filters: Option<Vec<Filter>>,
+) -> Result<Vec<PathBuf>, String> {
+    let filters = filters.unwrap_or_default();
+    let dialog = builder(&window, &filters);
+    let picked = show(|done| dialog.pick_files(done)).await?;
+    picked
+        .unwrap_or_default()
+        .into_iter()
+        .map(|path| existing(path, false))
+        .collect()
+}
+
+#[tauri::command]
+pub async fn pick_folder(window: Window) -> Result<Option<PathBuf>, String> {
+    let dialog = builder(&window, &[]);
+    let picked = show(|done| dialog.pick_folder(done)).await?;
+    picked.map(|path| existing(path, true)).transpose()
+}
+
+// Starts in Downloads with `default_name` filled in. Doesn't create the file;
+// the OS dialog has already asked about overwriting one
+#[tauri::command]
+pub async fn save_file_dialog(
+    window: Window,
+    default_name: Option<String>,
+    filters: Option<Vec<Filter>>,
+) -> Result<Option<PathBuf>, String> {
+    let filters = filters.unwrap_or_default();
+    let mut dialog = builder(&window, &filters);
+    if let Some(dir) = tauri::api::path::download_dir().filter(|dir| dir.is_dir()) {
+        dialog = dialog.set_directory(dir);
+    }
+    if let Some(name) = default_name
+        .as_deref()
+        .map(file_name)
+        .filter(|n| !n.is_empty())
+    {
+        dialog = dialog.set_file_name(name);
+    }
+    let picked = show(|done| dialog.save_file(done)).await?;
+    picked.map(|path| save_target(path, &filters)).transpose()
+}
+
+// Only the last component, so a default name can't point somewhere else
+fn file_name(name: &str) -> &str {
+    Path::new(name)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default()
+}
@@ -0,0 +1,203 @@
+// Project files (.aef). The extension is registered with the OS at startup
+// (Info.plist on macOS), so double-clicking one launches the app, or hands it
+// to the running instance, and the frontend gets `open-file` with its path.
+// Files that arrive before the main window's page has loaded are queued and
+// delivered, in order, once it has.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Window};
+
+use crate::state::AppState;
+use crate::window;
+
+pub const EXTENSION: &str = "aef";
+
+#[derive(Clone, Serialize)]
+struct OpenFile {
+    path: PathBuf,
+}
+
+#[derive(Default)]
+pub struct Documents {
+    inbox: Mutex<Inbox>,
+}
+
+#[derive(Default)]
+struct Inbox {
+    loaded: bool,
+    pending: Vec<PathBuf>,
+}
+
+pub fn is_project(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(EXTENSION))
+}
+
+pub fn open(app: &AppHandle, path: &Path) {
+    if !path.is_file() {
+        tracing::warn!("Not opening {}: no such file", path.display());
+        return;
+    }
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    let mut inbox = state.documents.inbox.lock().unwrap();
+    if !inbox.loaded {
+        inbox.pending.push(path);
+        return;
+    }
+    drop(inbox);
+    if let Some(main) = window::activate(app) {
+        let _ = main.emit("open-file", OpenFile { path });
+    }
+}
+
+pub fn on_page_load(window: &Window) {
+    if window.label() != window::MAIN {
+        return;
+    }
+    let Some(state) = window.try_state::<AppState>() else {
+        return;
+    };
+    let mut inbox = state.documents.inbox.lock().unwrap();
+    inbox.loaded = true;
+    for path in inbox.pending.drain(..) {
+        let _ = window.emit("open-file", OpenFile { path });
+    }
+}
+
+// Makes this executable the handler for .aef files; like the URL scheme,
+// redone on every start so it follows the app around
+pub fn register(app: &AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let type_name = crate::i18n::text(&app, "documents.type_name");
+        if let Err(e) = platform::register(&app, &type_name) {
+            tracing::warn!("Failed to register .{} files: {}", EXTENSION, e);
+        }
+    });
+}
+
+#[cfg(windows)]
+mod platform {
+    use tauri::AppHandle;
+    use windows::Win32::UI::Shell::{SHChangeNotify, SHCNE_ASSOCCHANGED, SHCNF_IDLIST};
+
+    use super::EXTENSION;
+    use crate::util::{launcher, set_registry_string};
+
+    const PROG_ID: &str = "AetherFrame.Project";
+
+    pub fn register(_app: &AppHandle, type_name: &str) -> Result<(), String> {
+        let program = launcher()?.display().to_string();
+        let classes = r"Software\Classes";
+        set_registry_string(&format!(r"{}\.{}", classes, EXTENSION), None, PROG_ID)?;
+        let key = format!(r"{}\{}", classes, PROG_ID);
+        set_registry_string(&key, None, type_name)?;
+        set_registry_string(
+            &format!(r"{}\DefaultIcon", key),
+            None,
+            &format!("\"{}\",0", program),
+        )?;
+        set_registry_string(
+            &format!(r"{}\shell\open\command", key),
+            None,
+            &format!("\"{}\" \"%1\"", program),
+        )?;
+        // Explorer caches icons and handlers until told otherwise
+        unsafe {
+            SHChangeNotify(
+                SHCNE_ASSOCCHANGED,
+                SHCNF_IDLIST,
+                std::ptr::null(),
+                std::ptr::null(),
+            )
+        };
+        Ok(())
+    }
+}
+
+// CFBundleDocumentTypes in the bundle's Info.plist
+#[cfg(target_os = "macos")]
+mod platform {
+    use tauri::AppHandle;
+
+    pub fn register(_app: &AppHandle, _type_name: &str) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+// A MIME type for the extension, and a hidden desktop entry that handles it
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use std::process::Command;
+
+    use tauri::AppHandle;
+
+    use super::EXTENSION;
+    use crate::util::{exec_quote, launcher, write_atomic};
+
+    const MIME_TYPE: &str = "application/x-aetherframe-project";
+
+    fn run(program: &str, args: &[&str]) -> Result<(), String> {
+        let status = Command::new(program)
+            .args(args)
+            .status()
+            .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+        if !status.success() {
+            return Err(format!("{} exited with {}", program, status));
+        }
+        Ok(())
+    }
+
+    fn escape(value: &str) -> String {
+        value
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    }
+
+    pub fn register(app: &AppHandle, type_name: &str) -> Result<(), String> {
+        let data = tauri::api::path::data_dir().ok_or("No data directory")?;
+        let mime_dir = data.join("mime");
+        let package = mime_dir
+            .join("packages")
+            .join(format!("{}.xml", app.package_info().crate_name));
+        let mime_info = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <mime-info xmlns=\"http://www.freedesktop.org/standards/shared-mime-info\">\n\
+             \x20 <mime-type type=\"{}\">\n\
+             \x20   <comment>{}</comment>\n\
+             \x20   <glob pattern=\"*.{}\"/>\n\
+             \x20 </mime-type>\n\
+             </mime-info>\n",
+            MIME_TYPE,
+            escape(type_name),
+            EXTENSION
+        );
+        write_atomic(&package, mime_info.as_bytes())
+            .map_err(|e| format!("Failed to write {}: {}", package.display(), e))?;
+        run("update-mime-database", &[&mime_dir.to_string_lossy()])?;
+
+        let program = launcher()?;
+        let file = format!("{}-file-handler.desktop", app.package_info().crate_name);
+        let apps = data.join("applications");
+        let path = apps.join(&file);
+        let contents = format!(
+            "[Desktop Entry]\nType=Application\nName={}\nExec={} %f\nTerminal=false\nNoDisplay=true\nMimeType={};\n",
+            app.package_info().name,
+            exec_quote(&program.to_string_lossy()),
+            MIME_TYPE
+        );
+        write_atomic(&path, contents.as_bytes())
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        run("xdg-mime", &["default", &file, MIME_TYPE])?;
+        // Not every desktop rescans on its own; missing is fine
+        let _ = Command::new("update-desktop-database").arg(apps).status();
+        Ok(())
+    }
+}
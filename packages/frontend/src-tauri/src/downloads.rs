@@ -0,0 +1,380 @@
+// Download manager for large files from the backend (models, exports) or
+// elsewhere. A few items run at once and the rest wait their turn; each can be
+// paused, resumed or cancelled, and the list survives restarts. The bytes go
+// through `http::transfer::download`, so a paused item picks up from its .part
+// file and progress arrives as `transfer-progress` under the download's id.
+// Every status change is broadcast as `download-changed` with the whole item.
+// Downloads still running when the app quit come back paused.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use sysinfo::Disks;
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::Semaphore;
+
+use crate::http::transfer;
+use crate::state::AppState;
+use crate::storage::blocking;
+use crate::system_info;
+use crate::util::now_millis;
+
+const MAX_CONCURRENT: usize = 3;
+// Left free on top of what a download needs, so it can't fill the disk
+const DISK_HEADROOM: u64 = 256 * 1024 * 1024;
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Queued,
+    Downloading,
+    Paused,
+    Completed,
+    Failed,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Download {
+    pub id: String,
+    pub url: String,
+    pub dest: PathBuf,
+    pub sha256: Option<String>,
+    pub status: Status,
+    // On disk so far; the .part file's size until it completes
+    pub bytes: u64,
+    pub total: Option<u64>,
+    pub error: Option<String>,
+    pub created_at: u64,
+    pub updated_at: u64,
+    // Bumped each time a run starts, so a run that was paused and outlived by
+    // the next one can tell its result is stale
+    #[serde(skip)]
+    pub run: u64,
+}
+
+pub struct Downloads {
+    items: Mutex<HashMap<String, Download>>,
+    slots: Arc<Semaphore>,
+}
+
+impl Default for Downloads {
+    fn default() -> Self {
+        Self {
+            items: Mutex::new(HashMap::new()),
+            slots: Arc::new(Semaphore::new(MAX_CONCURRENT)),
+        }
+    }
+}
+
+impl Downloads {
+    fn list(&self) -> Vec<Download> {
+        let mut items: Vec<_> = self.items.lock().unwrap().values().cloned().collect();
+        items.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+        items
+    }
+}
+
+fn new_id() -> String {
+    format!("{:016x}", rand::random::<u64>())
+}
+
+fn on_disk(dest: &Path) -> u64 {
+    std::fs::metadata(transfer::partial_path(dest))
+        .map(|m| m.len())
+        .unwrap_or(0)
+}
+
+async fn save(app: &AppHandle, item: &Download) {
+    let (id, created_at) = (item.id.clone(), item.created_at);
+    let saved = match serde_json::to_value(item) {
+        Ok(record) => {
+            blocking(app.clone(), move |state| {
+                state.db.save_download(&id, record, created_at)
+            })
+            .await
+        }
+        Err(e) => Err(e.to_string()),
+    };
+    if let Err(e) = saved {
+        tracing::warn!("Failed to save download {}: {}", item.id, e);
+    }
+}
+
+// Applies `change` if it returns true, then saves and announces the item
+async fn update(
+    app: &AppHandle,
+    id: &str,
+    change: impl FnOnce(&mut Download) -> bool,
+) -> Option<Download> {
+    let item = {
+        let state = app.state::<AppState>();
+        let mut items = state.downloads.items.lock().unwrap();
+        let item = items.get_mut(id)?;
+        if !change(item) {
+            return None;
+        }
+        item.updated_at = now_millis();
+        item.clone()
+    };
+    save(app, &item).await;
+    let _ = app.emit_all("download-changed", &item);
+    Some(item)
+}
+
+// Asks for the size first; servers that won't say get no check
+async fn preflight(app: &AppHandle, item: &Download) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let head = transfer::request(app, &state, Method::HEAD, &item.url).await?;
+    let total = match head.send().await {
+        Ok(response) if response.status().is_success() => response.content_length(),
+        _ => None,
+    };
+    let Some(total) = total else {
+        return Ok(());
+    };
+    update(app, &item.id, |d| {
+        d.total = Some(total);
+        true
+    })
+    .await;
+    let needed = total.saturating_sub(on_disk(&item.dest)) + DISK_HEADROOM;
+    match system_info::available_space(&Disks::new_with_refreshed_list(), &item.dest) {
+        Some(available) if available < needed => Err(format!(
+            "Not enough disk space for {}: {} MB needed, {} MB free",
+            item.dest.display(),
+            needed / (1024 * 1024),
+            available / (1024 * 1024)
+        )),
+        _ => Ok(()),
+    }
+}
+
+async fn fetch(app: &AppHandle, item: &Download) -> Result<u64, String> {
+    preflight(app, item).await?;
+    let state = app.state::<AppState>();
+    let request = transfer::request(app, &state, Method::GET, &item.url).await?;
+    let result = transfer::download(
+        app,
+        request,
+        item.dest.clone(),
+        item.sha256.as_deref(),
+        Some(item.id.clone()),
+    )
+    .await?;
+    Ok(result.bytes)
+}
+
+// Waits for a free slot, unless the item was paused or cancelled meanwhile
+fn run(app: &AppHandle, id: String) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let slots = app.state::<AppState>().downloads.slots.clone();
+        let Ok(_slot) = slots.acquire_owned().await else {
+            return;
+        };
+        let started = update(&app, &id, |d| {
+            if d.status != Status::Queued {
+                return false;
+            }
+            d.status = Status::Downloading;
+            d.error = None;
+            d.run += 1;
+            true
+        })
+        .await;
+        let Some(item) = started else {
+            return;
+        };
+        let result = fetch(&app, &item).await;
+        let mut stale = false;
+        let finished = update(&app, &id, |d| {
+            // Paused and resumed while running: the newer run reports for it
+            if d.run != item.run {
+                stale = true;
+                return false;
+            }
+            // Paused while running: only the size is news
+            if d.status != Status::Downloading {
+                d.bytes = on_disk(&d.dest);
+                return true;
+            }
+            match &result {
+                Ok(bytes) => {
+                    d.status = Status::Completed;
+                    d.bytes = *bytes;
+                }
+                Err(e) => {
+                    d.status = Status::Failed;
+                    d.bytes = on_disk(&d.dest);
+                    d.error = Some(e.clone());
+                }
+            }
+            true
+        })
+        .await;
+        // Cancelled while running; the transfer has let go of the file now
+        if finished.is_none() && !stale {
+            transfer::discard_partial(&item.dest).await;
+        }
+    });
+}
+
+// Loads the list saved by the last session
+pub fn start(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let records = match blocking(app.clone(), |state| state.db.downloads()).await {
+            Ok(records) => records,
+            Err(e) => {
+                tracing::warn!("Failed to load downloads: {}", e);
+                return;
+            }
+        };
+        let mut interrupted = Vec::new();
+        {
+            let state = app.state::<AppState>();
+            let mut items = state.downloads.items.lock().unwrap();
+            for record in records {
+                let Ok(mut item) = serde_json::from_value::<Download>(record) else {
+                    continue;
+                };
+                if matches!(item.status, Status::Queued | Status::Downloading) {
+                    item.status = Status::Paused;
+                    item.bytes = on_disk(&item.dest);
+                    interrupted.push(item.clone());
+                }
+                items.insert(item.id.clone(), item);
+            }
+        }
+        for item in interrupted {
+            save(&app, &item).await;
+        }
+    });
+}
+
+// `dest` is where the finished file goes; it's written as `<dest>.part` until
+// then, and only moved into place once the optional `sha256` matches
+#[tauri::command]
+pub async fn start_download(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    url: String,
+    dest: PathBuf,
+    sha256: Option<String>,
+) -> Result<Download, String> {
+    if !dest.is_absolute() || dest.file_name().is_none() {
+        return Err(format!(
+            "Download destination must be an absolute file path: {}",
+            dest.display()
+        ));
+    }
+    let now = now_millis();
+    let item = Download {
+        id: new_id(),
+        url,
+        bytes: on_disk(&dest),
+        dest,
+        sha256,
+        status: Status::Queued,
+        total: None,
+        error: None,
+        created_at: now,
+        updated_at: now,
+        run: 0,
+    };
+    {
+        let mut items = state.downloads.items.lock().unwrap();
+        // Two downloads appending to one .part file would corrupt both
+        let busy = items.values().any(|other| {
+            other.dest == item.dest && !matches!(other.status, Status::Completed | Status::Failed)
+        });
+        if busy {
+            return Err(format!(
+                "{} is already being downloaded",
+                item.dest.display()
+            ));
+        }
+        items.insert(item.id.clone(), item.clone());
+    }
+    save(&app, &item).await;
+    let _ = app.emit_all("download-changed", &item);
+    run(&app, item.id.clone());
+    Ok(item)
+}
+
+// Keeps the .part file for `resume_download`
+#[tauri::command]
+pub async fn pause_download(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<(), String> {
+    let paused = update(&app, &id, |d| {
+        if !matches!(d.status, Status::Queued | Status::Downloading) {
+            return false;
+        }
+        d.status = Status::Paused;
+        true
+    })
+    .await;
+    if paused.is_none() {
+        return Err(format!("Download {} is not running", id));
+    }
+    state.http.inflight.cancel(&id);
+    Ok(())
+}
+
+// Also retries a failed download, from where it stopped
+#[tauri::command]
+pub async fn resume_download(app: AppHandle, id: String) -> Result<(), String> {
+    let resumed = update(&app, &id, |d| {
+        if !matches!(d.status, Status::Paused | Status::Failed) {
+            return false;
+        }
+        d.status = Status::Queued;
+        d.error = None;
+        true
+    })
+    .await;
+    if resumed.is_none() {
+        return Err(format!("Download {} is not paused", id));
+    }
+    run(&app, id);
+    Ok(())
+}
+
+// Stops the download and deletes what it had so far. A completed file stays,
+// it's just taken off the list
+#[tauri::command]
+pub async fn cancel_download(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<(), String> {
+    let item = state
+        .downloads
+        .items
+        .lock()
+        .unwrap()
+        .remove(&id)
+        .ok_or_else(|| format!("No such download: {}", id))?;
+    // A running one deletes its own .part file once the transfer has stopped
+    if item.status == Status::Downloading {
+        state.http.inflight.cancel(&id);
+    } else if item.status != Status::Completed {
+        transfer::discard_partial(&item.dest).await;
+    }
+    let removed = id.clone();
+    blocking(app.clone(), move |state| state.db.remove_download(&removed)).await?;
+    let _ = app.emit_all("download-removed", id);
+    Ok(())
+}
+
+// Oldest first
+#[tauri::command]
+pub async fn list_downloads(state: State<'_, AppState>) -> Result<Vec<Download>, String> {
+    Ok(state.downloads.list())
+}
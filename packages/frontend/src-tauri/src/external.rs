@@ -0,0 +1,82 @@
+// The one way to hand a URL to the OS. Only web and mail links are opened,
+// and web links only to the backend itself or hosts on the allowlist
+// (`security.external_hosts`), so the webview can't launch local files,
+// custom protocol handlers or arbitrary sites.
+
+use reqwest::Url;
+use tauri::{AppHandle, Manager};
+
+use crate::state::AppState;
+
+const SCHEMES: [&str; 3] = ["https", "http", "mailto"];
+
+// "example.com" matches only that host, "*.example.com" any subdomain of it
+fn host_allowed(host: &str, pattern: &str) -> bool {
+    let host = host.to_ascii_lowercase();
+    let pattern = pattern.trim().to_ascii_lowercase();
+    match pattern.strip_prefix("*.") {
+        Some(domain) => host.ends_with(&format!(".{}", domain)),
+        None => host == pattern,
+    }
+}
+
+pub fn validate(app: &AppHandle, raw: &str) -> Result<Url, String> {
+    let url = Url::parse(raw.trim()).map_err(|e| format!("Invalid URL {:?}: {}", raw, e))?;
+    if !SCHEMES.contains(&url.scheme()) {
+        return Err(format!("Refusing to open {} URLs", url.scheme()));
+    }
+    if url.scheme() == "mailto" {
+        return Ok(url);
+    }
+    // Credentials in a link are almost always a spoofing attempt
+    if !url.username().is_empty() || url.password().is_some() {
+        return Err("Refusing to open a URL with embedded credentials".to_string());
+    }
+    let host = url.host_str().ok_or("URL has no host")?;
+    let state = app.state::<AppState>();
+    let backend = Url::parse(&state.backend.url()).ok();
+    let is_backend = backend.as_ref().and_then(|b| b.host_str()) == Some(host)
+        && backend.as_ref().and_then(|b| b.port_or_known_default()) == url.port_or_known_default();
+    let listed = state
+        .settings
+        .get()
+        .security
+        .external_hosts
+        .iter()
+        .any(|pattern| host_allowed(host, pattern));
+    if !is_backend && !listed {
+        return Err(format!("{} is not on the list of allowed hosts", host));
+    }
+    Ok(url)
+}
+
+pub async fn open(app: &AppHandle, url: &str) -> Result<(), String> {
+    launch(validate(app, url)?.to_string()).await
+}
+
+// Skips the checks; only for URLs the shell itself trusts (e.g. a sign-in
+// page from the user's own profile settings)
+pub async fn launch(url: String) -> Result<(), String> {
+    let target = url.clone();
+    // Launching the browser can block (xdg-open waits on Linux), keep it off the runtime
+    tauri::async_runtime::spawn_blocking(move || open::that(target))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("Failed to open {}: {}", url, e))
+}
+
+// The backend's API docs, from menus that can't wait for the result
+pub fn open_docs(app: &AppHandle) {
+    let url = format!("{}/docs", app.state::<AppState>().backend.url());
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = open(&app, &url).await {
+            tracing::warn!("{}", e);
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn open_external(app: AppHandle, url: String) -> Result<(), String> {
+    open(&app, &url).await
+}
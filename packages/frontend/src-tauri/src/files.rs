@@ -0,0 +1,162 @@
+// Opening local files for the user: showing one in the file manager, or
+// handing it to its default app. Paths are resolved (symlinks and `..`
+// included) and must end up under an allowed directory: the app's own data,
+// the backend's working directory, Downloads, or one listed in
+// `security.file_dirs`. Only folders and files of known document and result
+// types are opened; anything else (programs, scripts, disk images, and
+// whatever else an OS might run) is shown in its folder instead.
+
+use std::path::{Path, PathBuf};
+
+use tauri::{AppHandle, Manager};
+
+use crate::state::AppState;
+
+// Safe to hand to their default app; the backend writes analysis artifacts
+// to Downloads, so anything not listed here is only revealed
+const OPENABLE: &[&str] = &[
+    "txt", "log", "md", "json", "jsonl", "csv", "tsv", "xml", "yaml", "yml", "toml", "pdf", "rtf",
+    "docx", "xlsx", "pptx", "odt", "ods", "odp", "png", "jpg", "jpeg", "gif", "bmp", "webp",
+];
+
+fn allowed_dirs(app: &AppHandle) -> Vec<PathBuf> {
+    let resolver = app.path_resolver();
+    let settings = app.state::<AppState>().settings.get();
+    let mut dirs: Vec<PathBuf> = [
+        resolver.app_data_dir(),
+        resolver.app_local_data_dir(),
+        tauri::api::path::download_dir(),
+        settings.backend.working_dir,
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    dirs.extend(settings.security.file_dirs);
+    // Ones that don't exist can't contain anything
+    dirs.iter()
+        .filter_map(|dir| dir.canonicalize().ok())
+        .collect()
+}
+
+// The real location of `path`, if it's somewhere we may open
+pub fn resolve(app: &AppHandle, path: &Path) -> Result<PathBuf, String> {
+    let resolved = path
+        .canonicalize()
+        .map_err(|e| format!("Can't open {}: {}", path.display(), e))?;
+    if !allowed_dirs(app)
+        .iter()
+        .any(|dir| resolved.starts_with(dir))
+    {
+        return Err(format!(
+            "{} is outside the folders the app may open",
+            path.display()
+        ));
+    }
+    Ok(without_verbatim_prefix(resolved))
+}
+
+// canonicalize gives \\?\C:\... paths, which Explorer (and most else)
+// doesn't understand
+#[cfg(windows)]
+pub fn without_verbatim_prefix(path: PathBuf) -> PathBuf {
+    let raw = path.to_string_lossy();
+    if let Some(share) = raw.strip_prefix(r"\\?\UNC\") {
+        return PathBuf::from(format!(r"\\{}", share));
+    }
+    match raw.strip_prefix(r"\\?\") {
+        Some(local) => PathBuf::from(local),
+        None => path.clone(),
+    }
+}
+
+#[cfg(not(windows))]
+pub fn without_verbatim_prefix(path: PathBuf) -> PathBuf {
+    path
+}
+
+fn may_open(path: &Path) -> bool {
+    if path.is_dir() {
+        return true;
+    }
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+        .is_some_and(|ext| OPENABLE.contains(&ext.as_str()))
+}
+
+// Explorer exits with 1 even when it worked, so only spawning is checked
+#[cfg(windows)]
+fn reveal(path: &Path) -> Result<(), String> {
+    let mut select = std::ffi::OsString::from("/select,");
+    select.push(path);
+    std::process::Command::new("explorer")
+        .arg(select)
+        .spawn()
+        .map(drop)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn reveal(path: &Path) -> Result<(), String> {
+    let status = std::process::Command::new("open")
+        .arg("-R")
+        .arg(path)
+        .status()
+        .map_err(|e| e.to_string())?;
+    if !status.success() {
+        return Err(format!("open exited with {}", status));
+    }
+    Ok(())
+}
+
+// Most file managers implement FileManager1 and select the item; without
+// one, open the folder that contains it
+#[cfg(all(unix, not(target_os = "macos")))]
+fn reveal(path: &Path) -> Result<(), String> {
+    let shown = (|| -> zbus::Result<()> {
+        let uri = reqwest::Url::from_file_path(path)
+            .map_err(|_| zbus::Error::Failure("not an absolute path".to_string()))?;
+        let connection = zbus::blocking::Connection::session()?;
+        let proxy = zbus::blocking::Proxy::new(
+            &connection,
+            "org.freedesktop.FileManager1",
+            "/org/freedesktop/FileManager1",
+            "org.freedesktop.FileManager1",
+        )?;
+        proxy.call::<_, _, ()>("ShowItems", &(vec![uri.as_str()], ""))
+    })();
+    if shown.is_ok() {
+        return Ok(());
+    }
+    let folder = if path.is_dir() {
+        path
+    } else {
+        path.parent().unwrap_or(path)
+    };
+    open::that(folder).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn reveal_in_folder(app: AppHandle, path: PathBuf) -> Result<(), String> {
+    let path = resolve(&app, &path)?;
+    // File managers can take a while to answer
+    tauri::async_runtime::spawn_blocking(move || {
+        reveal(&path).map_err(|e| format!("Failed to show {}: {}", path.display(), e))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// Opens the file (or folder) with whatever the OS would use on a double-click;
+// a file of any other type is revealed in its folder instead
+#[tauri::command]
+pub async fn open_path(app: AppHandle, path: PathBuf) -> Result<(), String> {
+    let path = resolve(&app, &path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        if !may_open(&path) {
+            return reveal(&path).map_err(|e| format!("Failed to show {}: {}", path.display(), e));
+        }
+        open::that(&path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,280 @@
+// Background health monitor: polls /status, tracks up/degraded/down transitions
+// and broadcasts them to every window. It polls every `poll_interval_secs`
+// while the main window is in use and backs off to the slower intervals when
+// it's hidden or the machine is on battery; focusing the window again checks
+// at once if the last probe is older than the fast interval.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::Notify;
+
+use crate::backend::logs::{self, Level};
+use crate::http;
+use crate::notifications;
+use crate::state::AppState;
+use crate::tray;
+use crate::util::now_millis;
+use crate::window::MAIN;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HealthSettings {
+    // Read before every probe, so changes apply from the next one
+    pub poll_interval_secs: u64,
+    // While the main window is hidden or minimized (e.g. living in the tray)
+    pub hidden_interval_secs: u64,
+    // On battery, unless the main window has focus
+    pub battery_interval_secs: u64,
+}
+
+impl Default for HealthSettings {
+    fn default() -> Self {
+        Self {
+            poll_interval_secs: 5,
+            hidden_interval_secs: 30,
+            battery_interval_secs: 60,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthState {
+    Unknown,
+    Up,
+    // Reachable, but a dependency (e.g. the Celery broker) is unavailable
+    Degraded,
+    Down,
+}
+
+#[derive(Clone, Serialize)]
+pub struct HealthSnapshot {
+    pub state: HealthState,
+    pub checked_at: Option<u64>,
+    pub changed_at: Option<u64>,
+    pub latency_ms: Option<u64>,
+    pub consecutive_failures: u32,
+    pub error: Option<String>,
+    // Last /status body, passed through untouched
+    pub status: Option<serde_json::Value>,
+}
+
+impl Default for HealthSnapshot {
+    fn default() -> Self {
+        Self {
+            state: HealthState::Unknown,
+            checked_at: None,
+            changed_at: None,
+            latency_ms: None,
+            consecutive_failures: 0,
+            error: None,
+            status: None,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct HealthChanged {
+    previous: HealthState,
+    current: HealthSnapshot,
+}
+
+#[derive(Default)]
+pub struct HealthMonitor {
+    snapshot: Mutex<HealthSnapshot>,
+    // Cuts a slow poller's wait short when the main window gets focus
+    focused: Notify,
+}
+
+impl HealthMonitor {
+    pub fn snapshot(&self) -> HealthSnapshot {
+        self.snapshot.lock().unwrap().clone()
+    }
+
+    // Store a probe result; returns the previous state if this was a transition
+    fn record(&self, probe: Probe) -> Option<HealthState> {
+        let mut snapshot = self.snapshot.lock().unwrap();
+        let previous = snapshot.state;
+        let now = now_millis();
+
+        snapshot.state = probe.state;
+        snapshot.checked_at = Some(now);
+        snapshot.latency_ms = probe.latency_ms;
+        snapshot.error = probe.error;
+        if probe.state == HealthState::Down {
+            snapshot.consecutive_failures += 1;
+        } else {
+            snapshot.consecutive_failures = 0;
+            snapshot.status = probe.status;
+        }
+
+        if previous == probe.state {
+            return None;
+        }
+        snapshot.changed_at = Some(now);
+        Some(previous)
+    }
+}
+
+struct Probe {
+    state: HealthState,
+    latency_ms: Option<u64>,
+    error: Option<String>,
+    status: Option<serde_json::Value>,
+}
+
+async fn probe(client: &reqwest::Client, auth: HeaderMap, base_url: &str) -> Probe {
+    let started = Instant::now();
+    let request = client
+        .get(format!("{}/status", base_url))
+        .headers(auth)
+        .timeout(REQUEST_TIMEOUT);
+    let response = match request.send().await {
+        Ok(response) => response,
+        Err(e) => {
+            return Probe {
+                state: HealthState::Down,
+                latency_ms: None,
+                error: Some(e.to_string()),
+                status: None,
+            }
+        }
+    };
+    let latency_ms = Some(started.elapsed().as_millis() as u64);
+
+    if !response.status().is_success() {
+        return Probe {
+            state: HealthState::Degraded,
+            latency_ms,
+            error: Some(format!("/status returned {}", response.status())),
+            status: None,
+        };
+    }
+
+    let status: Option<serde_json::Value> = response.json().await.ok();
+    let broker_down = status
+        .as_ref()
+        .and_then(|s| s.get("celery"))
+        .and_then(|c| c.as_str())
+        == Some("down");
+    Probe {
+        state: if broker_down {
+            HealthState::Degraded
+        } else {
+            HealthState::Up
+        },
+        latency_ms,
+        error: broker_down.then(|| "task broker is down".to_string()),
+        status,
+    }
+}
+
+// The slowest interval that applies; a focused window always gets the fast one
+async fn interval(app: &AppHandle) -> Duration {
+    let state = app.state::<AppState>();
+    let settings = state.settings.get().health;
+    let fast = settings.poll_interval_secs.max(1);
+    let main = app.get_window(MAIN);
+    if main
+        .as_ref()
+        .is_some_and(|w| w.is_focused().unwrap_or(false))
+    {
+        return Duration::from_secs(fast);
+    }
+    let hidden = main.map_or(true, |w| {
+        !w.is_visible().unwrap_or(true) || w.is_minimized().unwrap_or(false)
+    });
+    let mut secs = fast;
+    if hidden {
+        secs = secs.max(settings.hidden_interval_secs);
+    }
+    // Asking the OS can mean running pmset, so not on a runtime thread
+    let handle = app.clone();
+    let on_battery = tauri::async_runtime::spawn_blocking(move || {
+        handle.state::<AppState>().power.battery.on_battery()
+    })
+    .await
+    .unwrap_or(false);
+    if on_battery {
+        secs = secs.max(settings.battery_interval_secs);
+    }
+    Duration::from_secs(secs)
+}
+
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if app.state::<AppState>().backend.is_stopping() {
+                break;
+            }
+            check_now(&app).await;
+            let interval = interval(&app).await;
+            let state = app.state::<AppState>();
+            tokio::select! {
+                _ = state.power.pause(interval) => {}
+                _ = state.health.focused.notified() => {}
+            }
+        }
+    });
+}
+
+// From the main window's focus event
+pub fn on_focus(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let fast = state.settings.get().health.poll_interval_secs.max(1) * 1000;
+    let stale = state
+        .health
+        .snapshot()
+        .checked_at
+        .map_or(true, |at| now_millis().saturating_sub(at) >= fast);
+    if stale {
+        state.health.focused.notify_one();
+    }
+}
+
+pub async fn check_now(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let auth = state.auth.headers(app).await;
+    let result = probe(&state.http.backend(), auth, &state.backend.url()).await;
+    let reachable = result.state != HealthState::Down;
+    if let Some(previous) = state.health.record(result) {
+        let current = state.health.snapshot();
+        let level = match current.state {
+            HealthState::Up => Level::Info,
+            _ => Level::Warning,
+        };
+        let detail = current.error.as_deref().unwrap_or("ok");
+        logs::internal(
+            app,
+            level,
+            format!(
+                "Backend health {:?} -> {:?} ({})",
+                previous, current.state, detail
+            ),
+        );
+        tray::icon::refresh(app);
+        notifications::health_changed(app, previous, state.health.snapshot().state);
+        let _ = app.emit_all(
+            "health-changed",
+            HealthChanged {
+                previous,
+                current: state.health.snapshot(),
+            },
+        );
+    }
+    // Anything queued while it was away goes out now
+    if reachable {
+        http::offline::resume(app);
+    }
+}
+
+#[tauri::command]
+pub async fn get_health_snapshot(state: State<'_, AppState>) -> Result<HealthSnapshot, String> {
+    Ok(state.health.snapshot())
+}
@@ -0,0 +1,101 @@
+// Conditional-request cache for GETs through `backend_request`: stores the last
+// response per URL and revalidates it with If-None-Match / If-Modified-Since
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use reqwest::header::{
+    HeaderMap, CACHE_CONTROL, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+use tauri::State;
+
+use super::request::BackendResponse;
+use crate::state::AppState;
+
+const MAX_ENTRIES: usize = 256;
+
+struct Entry {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    response: BackendResponse,
+    // Insertion order, used to evict the oldest entry when full
+    seq: u64,
+}
+
+#[derive(Default)]
+pub struct ResponseCache {
+    entries: Mutex<HashMap<String, Entry>>,
+    next_seq: AtomicU64,
+}
+
+impl ResponseCache {
+    // Validators to send for a cached URL, if we have any
+    pub fn conditional_headers(&self, url: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        let entries = self.entries.lock().unwrap();
+        let Some(entry) = entries.get(url) else {
+            return headers;
+        };
+        if let Some(value) = entry.etag.as_deref().and_then(|v| v.parse().ok()) {
+            headers.insert(IF_NONE_MATCH, value);
+        }
+        if let Some(value) = entry.last_modified.as_deref().and_then(|v| v.parse().ok()) {
+            headers.insert(IF_MODIFIED_SINCE, value);
+        }
+        headers
+    }
+
+    // Called with the 304 for a revalidated URL
+    pub fn cached(&self, url: &str) -> Option<BackendResponse> {
+        let entries = self.entries.lock().unwrap();
+        entries.get(url).map(|entry| entry.response.clone())
+    }
+
+    pub fn store(&self, url: &str, response: &BackendResponse) {
+        let header = |name: &str| response.headers.get(name).cloned();
+        let etag = header(ETAG.as_str());
+        let last_modified = header(LAST_MODIFIED.as_str());
+        let no_store = header(CACHE_CONTROL.as_str()).is_some_and(|v| v.contains("no-store"));
+        if no_store || (etag.is_none() && last_modified.is_none()) {
+            self.entries.lock().unwrap().remove(url);
+            return;
+        }
+
+        let seq = self.next_seq.fetch_add(1, Ordering::Relaxed);
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= MAX_ENTRIES && !entries.contains_key(url) {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, e)| e.seq)
+                .map(|(k, _)| k.clone());
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(
+            url.to_string(),
+            Entry {
+                etag,
+                last_modified,
+                response: response.clone(),
+                seq,
+            },
+        );
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+// Callers that set their own validators manage caching themselves
+pub fn has_validators(headers: &HeaderMap) -> bool {
+    headers.contains_key(IF_NONE_MATCH) || headers.contains_key(IF_MODIFIED_SINCE)
+}
+
+#[tauri::command]
+pub async fn clear_http_cache(state: State<'_, AppState>) -> Result<(), String> {
+    state.http.cache.clear();
+    Ok(())
+}
@@ -0,0 +1,67 @@
+// In-flight request registry so the UI can abort slow calls with `cancel_request`
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+
+use tauri::State;
+use tokio::sync::oneshot;
+
+use crate::state::AppState;
+
+#[derive(Default)]
+pub struct InFlight {
+    pending: Mutex<HashMap<String, oneshot::Sender<()>>>,
+}
+
+// Drops the registration however the request ends
+struct Registration<'a> {
+    inflight: &'a InFlight,
+    id: String,
+}
+
+impl Drop for Registration<'_> {
+    fn drop(&mut self) {
+        self.inflight.pending.lock().unwrap().remove(&self.id);
+    }
+}
+
+impl InFlight {
+    // Run `future` under a caller-chosen id; without an id it simply can't be cancelled.
+    // Dropping the future aborts the underlying reqwest call.
+    pub async fn run<T, F>(&self, id: Option<String>, future: F) -> Result<T, String>
+    where
+        F: Future<Output = Result<T, String>>,
+    {
+        let Some(id) = id else {
+            return future.await;
+        };
+        let (tx, rx) = oneshot::channel();
+        {
+            let mut pending = self.pending.lock().unwrap();
+            if pending.contains_key(&id) {
+                return Err(format!("Request id already in use: {}", id));
+            }
+            pending.insert(id.clone(), tx);
+        }
+        let _registration = Registration { inflight: self, id };
+
+        tokio::select! {
+            result = future => result,
+            _ = rx => Err("Request cancelled".to_string()),
+        }
+    }
+
+    pub fn cancel(&self, id: &str) -> bool {
+        match self.pending.lock().unwrap().remove(id) {
+            Some(tx) => tx.send(()).is_ok(),
+            None => false,
+        }
+    }
+}
+
+// Returns false when nothing with that id is running (already finished or unknown)
+#[tauri::command]
+pub async fn cancel_request(state: State<'_, AppState>, id: String) -> Result<bool, String> {
+    Ok(state.http.inflight.cancel(&id))
+}
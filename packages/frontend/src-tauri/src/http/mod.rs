@@ -0,0 +1,99 @@
+// Shared HTTP clients: one for the backend, with the active profile's TLS
+// options (a pinned CA, or invalid certificates accepted), and a strict one
+// for everything else (update feeds, sign-in, crash reports), which those
+// options must never loosen
+
+pub mod cache;
+pub mod cancel;
+pub mod offline;
+pub mod proxy;
+pub mod request;
+pub mod retry;
+pub mod tls;
+pub mod transfer;
+
+use std::sync::RwLock;
+use std::time::Duration;
+
+use reqwest::Client;
+use tauri::AppHandle;
+
+use self::cache::ResponseCache;
+use self::cancel::InFlight;
+use self::offline::OfflineQueue;
+use crate::profiles;
+use crate::settings::Settings;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+// Upper bound for any request; callers tighten it per request where it matters
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+const TCP_KEEPALIVE: Duration = Duration::from_secs(60);
+
+// `reqwest::Client` is a cheap handle onto a shared connection pool; hand out
+// clones instead of building new clients. The lock is only taken for writing when
+// proxy/TLS settings change and the client has to be rebuilt.
+pub struct HttpClient {
+    client: RwLock<Client>,
+    backend: RwLock<Client>,
+    pub cache: ResponseCache,
+    pub inflight: InFlight,
+    pub offline: OfflineQueue,
+}
+
+impl HttpClient {
+    // Bad network settings shouldn't keep the app from starting; fall back to defaults
+    pub fn new(app: &AppHandle, settings: &Settings) -> Self {
+        let (client, backend) = build(app, settings).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring network settings: {}", e);
+            build(app, &Settings::default()).expect("failed to build HTTP client")
+        });
+        Self {
+            client: RwLock::new(client),
+            backend: RwLock::new(backend),
+            cache: ResponseCache::default(),
+            inflight: InFlight::default(),
+            offline: OfflineQueue::default(),
+        }
+    }
+
+    // For anything that isn't the backend
+    pub fn client(&self) -> Client {
+        self.client.read().unwrap().clone()
+    }
+
+    pub fn backend(&self) -> Client {
+        self.backend.read().unwrap().clone()
+    }
+
+    pub fn reconfigure(&self, app: &AppHandle, settings: &Settings) -> Result<(), String> {
+        let (client, backend) = build(app, settings)?;
+        *self.client.write().unwrap() = client;
+        *self.backend.write().unwrap() = backend;
+        // Cached bodies may belong to a different backend or identity now
+        self.cache.clear();
+        Ok(())
+    }
+}
+
+// The default client, then the backend one
+fn build(app: &AppHandle, settings: &Settings) -> Result<(Client, Client), String> {
+    let builder = || {
+        let builder = Client::builder()
+            .user_agent(format!("AetherFrame/{}", app.package_info().version))
+            .connect_timeout(CONNECT_TIMEOUT)
+            .timeout(REQUEST_TIMEOUT)
+            .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+            .tcp_keepalive(TCP_KEEPALIVE);
+        settings.proxy.apply(builder)
+    };
+    let client = builder()?
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+    let backend = profiles::active(settings)
+        .tls
+        .apply(builder()?)?
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+    Ok((client, backend))
+}
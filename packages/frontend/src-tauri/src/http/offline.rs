@@ -0,0 +1,312 @@
+// Mutating requests that can't reach the backend wait in the database and are
+// replayed, oldest first, once health probes see it again. Until the queue has
+// drained, new requests line up behind it so the backend still gets them in
+// order. Each replayed request reports back as `offline-request` with its
+// outcome: "sent", "conflict" (409/412, the data changed in the meantime) or
+// "failed". A request the backend still can't take stays queued for the next
+// probe. Each request belongs to the profile active when it was queued and is
+// only replayed while that profile is active again; the others wait, listed as
+// blocked.
+
+use std::collections::HashMap;
+
+use reqwest::header::{HeaderName, HeaderValue, AUTHORIZATION};
+use reqwest::{Method, StatusCode};
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::Mutex;
+
+use super::request::{check_path, into_response, parse_headers, parse_method, BackendResponse};
+use crate::health::HealthState;
+use crate::profiles;
+use crate::state::AppState;
+use crate::storage::blocking;
+use crate::storage::db::QueuedRequest;
+use crate::util::now_millis;
+
+// Lets the backend drop a replay whose first attempt did get through
+const IDEMPOTENCY_KEY: HeaderName = HeaderName::from_static("idempotency-key");
+// Never written to disk with a queued request
+const CREDENTIAL_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "x-api-key",
+    "x-auth-token",
+    "x-access-token",
+    "x-csrf-token",
+    "x-xsrf-token",
+];
+// Or anything named like one
+const CREDENTIAL_WORDS: &[&str] = &[
+    "token", "secret", "password", "session", "api-key", "apikey",
+];
+
+#[derive(Default)]
+pub struct OfflineQueue {
+    // Held while replaying, so probes that overlap don't send items twice
+    replaying: Mutex<()>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "outcome", rename_all = "lowercase")]
+pub enum QueueOutcome {
+    Sent {
+        response: BackendResponse,
+    },
+    Queued {
+        id: String,
+        // Requests waiting ahead of this one
+        position: usize,
+    },
+}
+
+#[derive(Serialize)]
+pub struct QueuedItem {
+    #[serde(flatten)]
+    request: QueuedRequest,
+    // Queued under another profile (or before profiles were recorded), so it
+    // won't be replayed until that profile is active
+    blocked: bool,
+}
+
+#[derive(Clone, Serialize)]
+struct ReplayEvent {
+    id: String,
+    method: String,
+    path: String,
+    outcome: &'static str,
+    response: Option<BackendResponse>,
+    error: Option<String>,
+}
+
+fn is_credential(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    CREDENTIAL_HEADERS.contains(&name.as_str())
+        || CREDENTIAL_WORDS.iter().any(|word| name.contains(word))
+}
+
+fn active_profile(app: &AppHandle) -> String {
+    profiles::active_name(&app.state::<AppState>().settings.get())
+}
+
+// Queued for the active profile, oldest first
+async fn waiting(app: &AppHandle) -> Result<Vec<QueuedRequest>, String> {
+    let profile = active_profile(app);
+    let queued = blocking(app.clone(), |state| state.db.queued()).await?;
+    Ok(queued
+        .into_iter()
+        .filter(|request| request.profile.as_deref() == Some(profile.as_str()))
+        .collect())
+}
+
+fn new_id() -> String {
+    format!("{:016x}", rand::random::<u64>())
+}
+
+enum Attempt {
+    Answered(BackendResponse),
+    // Never reached the backend, or it's too busy to take anything; says why
+    Unreachable(String),
+}
+
+fn is_busy(status: u16) -> bool {
+    matches!(
+        StatusCode::from_u16(status),
+        Ok(StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT)
+    )
+}
+
+// One try, no retries: a mutating request must not be sent twice
+async fn attempt(app: &AppHandle, request: &QueuedRequest) -> Result<Attempt, String> {
+    let state = app.state::<AppState>();
+    let method = parse_method(&request.method)?;
+    let mut headers = parse_headers(request.headers.clone())?;
+    // e.g. a token refresh that needs the backend too; try again later
+    match state.auth.header(app).await {
+        Ok(Some(value)) => {
+            headers.entry(AUTHORIZATION).or_insert(value);
+        }
+        Ok(None) => {}
+        Err(e) => return Ok(Attempt::Unreachable(e)),
+    }
+    if let Ok(value) = HeaderValue::from_str(&request.id) {
+        headers.insert(IDEMPOTENCY_KEY, value);
+    }
+    let policy = state.settings.get().http.policy("queue_or_send");
+    let mut builder = state
+        .http
+        .backend()
+        .request(method, format!("{}{}", state.backend.url(), request.path))
+        .headers(headers)
+        .timeout(policy.timeout);
+    if let Some(body) = &request.body {
+        builder = builder.json(body);
+    }
+    match builder.send().await {
+        Ok(response) => {
+            let response = into_response(response).await?;
+            if is_busy(response.status) {
+                return Ok(Attempt::Unreachable(format!(
+                    "Backend returned {}",
+                    response.status
+                )));
+            }
+            Ok(Attempt::Answered(response))
+        }
+        Err(e) if e.is_connect() || e.is_timeout() => Ok(Attempt::Unreachable(e.to_string())),
+        Err(e) => Err(format!("Backend request failed: {}", e)),
+    }
+}
+
+// Credentials are dropped first; a replay goes out with the session current then
+async fn enqueue(app: &AppHandle, mut request: QueuedRequest) -> Result<QueueOutcome, String> {
+    request.headers.retain(|name, _| !is_credential(name));
+    let id = request.id.clone();
+    let position = blocking(app.clone(), move |state| state.db.enqueue(&request)).await?;
+    let _ = app.emit_all("offline-queue-changed", position + 1);
+    Ok(QueueOutcome::Queued { id, position })
+}
+
+// For requests that change something on the backend; reads should go through
+// `backend_request`, since there's nothing to replay them to
+#[tauri::command]
+pub async fn queue_or_send(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    method: String,
+    path: String,
+    body: Option<Value>,
+    headers: Option<HashMap<String, String>>,
+) -> Result<QueueOutcome, String> {
+    check_path(&path)?;
+    let parsed = parse_method(&method)?;
+    if matches!(parsed, Method::GET | Method::HEAD | Method::OPTIONS) {
+        return Err(format!("{} requests can't be queued", parsed));
+    }
+    let headers = headers.unwrap_or_default();
+    parse_headers(headers.clone())?;
+    let request = QueuedRequest {
+        id: new_id(),
+        method: parsed.to_string(),
+        path,
+        body,
+        headers,
+        queued_at: now_millis(),
+        profile: Some(active_profile(&app)),
+    };
+
+    let down = state.health.snapshot().state == HealthState::Down;
+    let waiting = waiting(&app).await?;
+    if down || !waiting.is_empty() || state.backend.ensure_available().is_err() {
+        return enqueue(&app, request).await;
+    }
+    match attempt(&app, &request).await? {
+        Attempt::Answered(response) => Ok(QueueOutcome::Sent { response }),
+        Attempt::Unreachable(_) => enqueue(&app, request).await,
+    }
+}
+
+// Called after every health probe that reached the backend
+pub fn resume(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let Ok(_replaying) = state.http.offline.replaying.try_lock() else {
+            return;
+        };
+        if let Err(e) = replay(&app).await {
+            tracing::warn!("Failed to replay queued requests: {}", e);
+        }
+    });
+}
+
+async fn replay(app: &AppHandle) -> Result<(), String> {
+    let queued = waiting(app).await?;
+    if queued.is_empty() {
+        return Ok(());
+    }
+    let mut remaining = queued.len();
+    for request in queued {
+        let (outcome, response, error) = match attempt(app, &request).await {
+            // Leave it, and everything after it, for the next probe
+            Ok(Attempt::Unreachable(reason)) => {
+                tracing::warn!("Holding {} queued requests: {}", remaining, reason);
+                break;
+            }
+            Ok(Attempt::Answered(response)) => {
+                let outcome = match StatusCode::from_u16(response.status) {
+                    Ok(status) if status.is_success() => "sent",
+                    Ok(StatusCode::CONFLICT | StatusCode::PRECONDITION_FAILED) => "conflict",
+                    _ => "failed",
+                };
+                (outcome, Some(response), None)
+            }
+            // Malformed requests won't get any better by waiting
+            Err(e) => ("failed", None, Some(e)),
+        };
+        let id = request.id.clone();
+        blocking(app.clone(), move |state| state.db.dequeue(&id)).await?;
+        remaining -= 1;
+        let event = ReplayEvent {
+            id: request.id,
+            method: request.method,
+            path: request.path,
+            outcome,
+            response,
+            error,
+        };
+        let _ = app.emit_all("offline-request", event);
+        let _ = app.emit_all("offline-queue-changed", remaining);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_offline_queue(app: AppHandle) -> Result<Vec<QueuedItem>, String> {
+    let profile = active_profile(&app);
+    let queued = blocking(app, |state| state.db.queued()).await?;
+    Ok(queued
+        .into_iter()
+        .map(|request| QueuedItem {
+            blocked: request.profile.as_deref() != Some(profile.as_str()),
+            request,
+        })
+        .collect())
+}
+
+// Drops a queued request without sending it
+#[tauri::command]
+pub async fn discard_queued_request(app: AppHandle, id: String) -> Result<bool, String> {
+    let removed = blocking(app.clone(), move |state| state.db.dequeue(&id)).await?;
+    if removed {
+        let remaining = waiting(&app).await?.len();
+        let _ = app.emit_all("offline-queue-changed", remaining);
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_credential;
+
+    #[test]
+    fn credential_headers_are_recognised() {
+        for name in [
+            "Authorization",
+            "Cookie",
+            "X-Api-Key",
+            "X-Session-Id",
+            "X-Refresh-Token",
+        ] {
+            assert!(is_credential(name), "{}", name);
+        }
+        for name in ["Content-Type", "Accept", "If-Match", "X-Request-Id"] {
+            assert!(!is_credential(name), "{}", name);
+        }
+    }
+}
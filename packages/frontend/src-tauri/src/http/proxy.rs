@@ -0,0 +1,138 @@
+// Proxy configuration for the shared HTTP client
+
+use std::time::{Duration, Instant};
+
+use reqwest::{ClientBuilder, NoProxy, Proxy};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State};
+
+use crate::state::AppState;
+
+// Never send traffic for the local sidecar through a proxy
+const LOOPBACK: &str = "localhost,127.0.0.1,::1";
+const DEFAULT_TEST_URL: &str = "https://github.com";
+const TEST_TIMEOUT: Duration = Duration::from_secs(10);
+const PROXY_ENV_VARS: [&str; 4] = ["HTTPS_PROXY", "HTTP_PROXY", "ALL_PROXY", "NO_PROXY"];
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyMode {
+    // Whatever the environment/OS provides (HTTP(S)_PROXY, NO_PROXY, ...)
+    #[default]
+    System,
+    Manual,
+    None,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProxySettings {
+    pub mode: ProxyMode,
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    // Hosts/domains that bypass the proxy, same syntax as NO_PROXY
+    pub bypass: Vec<String>,
+}
+
+impl ProxySettings {
+    pub fn apply(&self, builder: ClientBuilder) -> Result<ClientBuilder, String> {
+        match self.mode {
+            ProxyMode::System => Ok(builder),
+            ProxyMode::None => Ok(builder.no_proxy()),
+            ProxyMode::Manual => {
+                if self.host.trim().is_empty() || self.port == 0 {
+                    return Err("Manual proxy needs a host and port".into());
+                }
+                let url = if self.host.contains("://") {
+                    format!("{}:{}", self.host, self.port)
+                } else {
+                    format!("http://{}:{}", self.host, self.port)
+                };
+                let mut proxy =
+                    Proxy::all(&url).map_err(|e| format!("Invalid proxy {}: {}", url, e))?;
+                if let Some(username) = &self.username {
+                    proxy = proxy.basic_auth(username, self.password.as_deref().unwrap_or(""));
+                }
+                let mut bypass = self.bypass.clone();
+                bypass.push(LOOPBACK.to_string());
+                proxy = proxy.no_proxy(NoProxy::from_string(&bypass.join(",")));
+                Ok(builder.proxy(proxy))
+            }
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct DetectedProxy {
+    pub variable: String,
+    pub value: String,
+}
+
+#[derive(Serialize)]
+pub struct ProxyTestResult {
+    pub ok: bool,
+    pub status: Option<u16>,
+    pub latency_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+// Proxy variables visible to the app, upper- or lowercase
+#[tauri::command]
+pub async fn detect_system_proxy() -> Vec<DetectedProxy> {
+    PROXY_ENV_VARS
+        .iter()
+        .flat_map(|name| [name.to_string(), name.to_lowercase()])
+        .filter_map(|variable| {
+            std::env::var(&variable)
+                .ok()
+                .map(|value| DetectedProxy { variable, value })
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub async fn set_proxy_settings(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    proxy: ProxySettings,
+) -> Result<(), String> {
+    // Validate before persisting so a typo can't leave the app without a client
+    let _ = proxy.apply(reqwest::Client::builder())?;
+    state.settings.update(|s| s.proxy = proxy)?;
+    state.http.reconfigure(&app, &state.settings.get())
+}
+
+// Try a request through the given (or currently saved) proxy without applying it
+#[tauri::command]
+pub async fn test_proxy(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    proxy: Option<ProxySettings>,
+    url: Option<String>,
+) -> Result<ProxyTestResult, String> {
+    let mut settings = state.settings.get();
+    if let Some(proxy) = proxy {
+        settings.proxy = proxy;
+    }
+    let (client, _) = super::build(&app, &settings)?;
+
+    let started = Instant::now();
+    let url = url.unwrap_or_else(|| DEFAULT_TEST_URL.to_string());
+    let result = match client.get(&url).timeout(TEST_TIMEOUT).send().await {
+        Ok(response) => ProxyTestResult {
+            ok: response.status().is_success() || response.status().is_redirection(),
+            status: Some(response.status().as_u16()),
+            latency_ms: Some(started.elapsed().as_millis() as u64),
+            error: None,
+        },
+        Err(e) => ProxyTestResult {
+            ok: false,
+            status: None,
+            latency_ms: None,
+            error: Some(e.to_string()),
+        },
+    };
+    Ok(result)
+}
@@ -0,0 +1,139 @@
+// Generic passthrough so the frontend can reach the backend without CORS
+// exceptions; base URL, auth and TLS all come from the shared client
+
+use std::collections::HashMap;
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use reqwest::{Method, RequestBuilder, Response, StatusCode};
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, State};
+
+use super::cache;
+use super::retry::send_with_retry;
+use crate::state::AppState;
+
+#[derive(Clone, Serialize)]
+pub struct BackendResponse {
+    pub status: u16,
+    pub headers: HashMap<String, String>,
+    // Parsed JSON when the backend says so, otherwise the raw text
+    pub body: Value,
+}
+
+pub(super) fn parse_method(method: &str) -> Result<Method, String> {
+    Method::from_bytes(method.to_uppercase().as_bytes())
+        .map_err(|_| format!("Invalid HTTP method: {}", method))
+}
+
+pub(super) fn parse_headers(headers: HashMap<String, String>) -> Result<HeaderMap, String> {
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| format!("Invalid header name: {}", name))?;
+        let value = HeaderValue::from_str(&value)
+            .map_err(|_| format!("Invalid value for header {}", name))?;
+        map.insert(name, value);
+    }
+    Ok(map)
+}
+
+// Only paths are accepted so the command can't be used to reach arbitrary hosts
+pub(super) fn check_path(path: &str) -> Result<(), String> {
+    if !path.starts_with('/') || path.starts_with("//") {
+        return Err(format!(
+            "Backend path must start with a single '/': {}",
+            path
+        ));
+    }
+    Ok(())
+}
+
+pub(super) async fn into_response(response: Response) -> Result<BackendResponse, String> {
+    let status = response.status().as_u16();
+    let headers = response
+        .headers()
+        .iter()
+        .filter_map(|(k, v)| Some((k.to_string(), v.to_str().ok()?.to_string())))
+        .collect::<HashMap<_, _>>();
+    let is_json = headers
+        .get(CONTENT_TYPE.as_str())
+        .is_some_and(|t| t.contains("json"));
+    let text = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read backend response: {}", e))?;
+    let body = if text.is_empty() {
+        Value::Null
+    } else if is_json {
+        serde_json::from_str(&text).unwrap_or(Value::String(text))
+    } else {
+        Value::String(text)
+    };
+    Ok(BackendResponse {
+        status,
+        headers,
+        body,
+    })
+}
+
+// Pass `request_id` to be able to abort the call with `cancel_request`
+#[tauri::command]
+pub async fn backend_request(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    method: String,
+    path: String,
+    body: Option<Value>,
+    headers: Option<HashMap<String, String>>,
+    request_id: Option<String>,
+) -> Result<BackendResponse, String> {
+    state.backend.ensure_available()?;
+    check_path(&path)?;
+    let method = parse_method(&method)?;
+    let mut headers = parse_headers(headers.unwrap_or_default())?;
+
+    // An explicit Authorization header from the caller wins
+    if let Some(value) = state.auth.header(&app).await? {
+        headers.entry(AUTHORIZATION).or_insert(value);
+    }
+
+    let client = state.http.backend();
+    let url = format!("{}{}", state.backend.url(), path);
+    let cache = &state.http.cache;
+    let cacheable = method == Method::GET && !cache::has_validators(&headers);
+    if cacheable {
+        headers.extend(cache.conditional_headers(&url));
+    }
+    let build = || -> RequestBuilder {
+        let request = client
+            .request(method.clone(), &url)
+            .headers(headers.clone());
+        match &body {
+            Some(body) => request.json(body),
+            None => request,
+        }
+    };
+
+    let mut policy = state.settings.get().http.policy("backend_request");
+    // Replaying a POST/PATCH could duplicate work on the backend
+    if !method.is_idempotent() {
+        policy.max_attempts = 1;
+    }
+    let send = async {
+        let response = send_with_retry(policy, build)
+            .await
+            .map_err(|e| format!("Backend request failed: {}", e))?;
+        if cacheable && response.status() == StatusCode::NOT_MODIFIED {
+            if let Some(cached) = cache.cached(&url) {
+                return Ok(cached);
+            }
+        }
+        into_response(response).await
+    };
+    let response = state.http.inflight.run(request_id, send).await?;
+    if cacheable && response.status == StatusCode::OK.as_u16() {
+        cache.store(&url, &response);
+    }
+    Ok(response)
+}
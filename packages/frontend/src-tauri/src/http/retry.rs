@@ -0,0 +1,103 @@
+// Timeouts and retries for idempotent backend calls
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use rand::Rng;
+use reqwest::{RequestBuilder, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetrySettings {
+    pub timeout_ms: u64,
+    // Total tries including the first one
+    pub max_attempts: u32,
+    pub base_delay_ms: u64,
+    pub max_delay_ms: u64,
+    // Per-command overrides, keyed by command name
+    pub commands: HashMap<String, CommandOverride>,
+}
+
+impl Default for RetrySettings {
+    fn default() -> Self {
+        Self {
+            timeout_ms: 10_000,
+            max_attempts: 3,
+            base_delay_ms: 200,
+            max_delay_ms: 5_000,
+            commands: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CommandOverride {
+    pub timeout_ms: Option<u64>,
+    pub max_attempts: Option<u32>,
+}
+
+#[derive(Clone, Copy)]
+pub struct RetryPolicy {
+    pub timeout: Duration,
+    pub max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+}
+
+impl RetrySettings {
+    pub fn policy(&self, command: &str) -> RetryPolicy {
+        let overrides = self.commands.get(command).cloned().unwrap_or_default();
+        RetryPolicy {
+            timeout: Duration::from_millis(overrides.timeout_ms.unwrap_or(self.timeout_ms)),
+            max_attempts: overrides.max_attempts.unwrap_or(self.max_attempts).max(1),
+            base_delay: Duration::from_millis(self.base_delay_ms),
+            max_delay: Duration::from_millis(self.max_delay_ms),
+        }
+    }
+}
+
+impl RetryPolicy {
+    // "Full jitter": anywhere between zero and the exponential ceiling, so callers
+    // that failed together don't retry in lockstep
+    fn delay(&self, retry: u32) -> Duration {
+        let ceiling = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_delay);
+        let millis = ceiling.as_millis() as u64;
+        Duration::from_millis(rand::thread_rng().gen_range(0..=millis))
+    }
+}
+
+// Statuses that mean "busy, try again" rather than "your request is wrong"
+fn is_transient(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+// Only use for idempotent requests: `build` is called again for every attempt
+pub async fn send_with_retry<F>(policy: RetryPolicy, build: F) -> Result<Response, reqwest::Error>
+where
+    F: Fn() -> RequestBuilder,
+{
+    let mut attempt = 1;
+    loop {
+        let result = build().timeout(policy.timeout).send().await;
+        let retryable = match &result {
+            Ok(response) => is_transient(response.status()),
+            Err(e) => e.is_timeout() || e.is_connect(),
+        };
+        if !retryable || attempt >= policy.max_attempts {
+            return result;
+        }
+        tokio::time::sleep(policy.delay(attempt - 1)).await;
+        attempt += 1;
+    }
+}
@@ -0,0 +1,148 @@
+// Custom root CAs and certificate pinning for HTTPS backends
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use reqwest::{Certificate, ClientBuilder};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, State};
+
+use crate::profiles::{self, LOCAL_PROFILE};
+use crate::state::AppState;
+
+const CERTS_DIR: &str = "certs";
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TlsOptions {
+    // PEM bundle trusted in addition to the system roots
+    pub ca_cert: Option<PathBuf>,
+    // SHA-256 of a certificate in `ca_cert`; when set, only that bundle is trusted
+    pub pinned_sha256: Option<String>,
+    pub accept_invalid_certs: bool,
+}
+
+impl TlsOptions {
+    pub fn apply(&self, mut builder: ClientBuilder) -> Result<ClientBuilder, String> {
+        if let Some(path) = &self.ca_cert {
+            let certs = load_pem(path)?;
+            if let Some(pin) = &self.pinned_sha256 {
+                let pin = normalize_fingerprint(pin);
+                if !certs.iter().any(|c| c.sha256 == pin) {
+                    return Err(format!(
+                        "No certificate in {} matches the pinned fingerprint",
+                        path.display()
+                    ));
+                }
+                builder = builder.tls_built_in_root_certs(false);
+            }
+            for cert in certs {
+                builder = builder.add_root_certificate(cert.certificate);
+            }
+        } else if self.pinned_sha256.is_some() {
+            return Err("Certificate pinning needs a CA certificate file".into());
+        }
+        Ok(builder.danger_accept_invalid_certs(self.accept_invalid_certs))
+    }
+}
+
+struct LoadedCert {
+    certificate: Certificate,
+    sha256: String,
+}
+
+fn load_pem(path: &Path) -> Result<Vec<LoadedCert>, String> {
+    let raw = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let blocks =
+        pem::parse_many(&raw).map_err(|e| format!("Invalid PEM in {}: {}", path.display(), e))?;
+    let certs: Vec<LoadedCert> = blocks
+        .iter()
+        .filter(|block| block.tag() == "CERTIFICATE")
+        .map(|block| {
+            let certificate = Certificate::from_der(block.contents())
+                .map_err(|e| format!("Invalid certificate in {}: {}", path.display(), e))?;
+            Ok(LoadedCert {
+                certificate,
+                sha256: fingerprint(block.contents()),
+            })
+        })
+        .collect::<Result<_, String>>()?;
+    if certs.is_empty() {
+        return Err(format!("No certificates found in {}", path.display()));
+    }
+    Ok(certs)
+}
+
+// Lowercase hex without separators, matching what `normalize_fingerprint` yields
+fn fingerprint(der: &[u8]) -> String {
+    Sha256::digest(der)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+// Accept "AB:CD:..." as printed by openssl as well as bare hex
+fn normalize_fingerprint(pin: &str) -> String {
+    pin.chars()
+        .filter(|c| c.is_ascii_hexdigit())
+        .collect::<String>()
+        .to_lowercase()
+}
+
+#[derive(Serialize)]
+pub struct ImportedCertificate {
+    pub profile: String,
+    pub path: PathBuf,
+    pub sha256: Vec<String>,
+}
+
+// Copy a PEM into the config dir and trust it for a profile (the active one by default)
+#[tauri::command]
+pub async fn import_ca_certificate(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    path: PathBuf,
+    profile: Option<String>,
+) -> Result<ImportedCertificate, String> {
+    let certs = load_pem(&path)?;
+    let settings = state.settings.get();
+    let profile = profile.unwrap_or_else(|| profiles::active_name(&settings));
+    if !profiles::all(&settings).iter().any(|p| p.name == profile) {
+        return Err(format!("Unknown profile: {}", profile));
+    }
+
+    let dir = app
+        .path_resolver()
+        .app_config_dir()
+        .ok_or("no app config directory")?
+        .join(CERTS_DIR);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let file_name: String = profile
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let dest = dir.join(format!("{}.pem", file_name));
+    fs::copy(&path, &dest).map_err(|e| format!("Failed to import certificate: {}", e))?;
+
+    state.settings.update(|s| {
+        if profile == LOCAL_PROFILE {
+            s.tls.ca_cert = Some(dest.clone());
+        } else if let Some(p) = s.profiles.iter_mut().find(|p| p.name == profile) {
+            p.tls.ca_cert = Some(dest.clone());
+        }
+    })?;
+    state.http.reconfigure(&app, &state.settings.get())?;
+
+    Ok(ImportedCertificate {
+        profile,
+        path: dest,
+        sha256: certs.into_iter().map(|c| c.sha256).collect(),
+    })
+}
@@ -0,0 +1,437 @@
+// Translations for strings rendered natively (tray, notifications, splash).
+// The webview localises itself; this only covers what Rust draws.
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+
+use crate::menu;
+use crate::quick_actions;
+use crate::settings::Settings;
+use crate::state::AppState;
+use crate::tray;
+
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+type Catalog = &'static [(&'static str, &'static str)];
+
+const EN: Catalog = &[
+    ("tray.show", "Show"),
+    ("tray.hide", "Hide"),
+    ("tray.always_on_top", "Always on Top"),
+    ("tray.widget", "Mini Widget"),
+    ("tray.console", "Log Console"),
+    ("tray.update", "Update available: {}"),
+    ("tray.quit", "Quit"),
+    ("tray.docs", "API Docs"),
+    ("tray.status", "System Status"),
+    ("tray.restart", "Restart Backend"),
+    ("tray.profile", "Profile: {}"),
+    ("tray.recent", "Recent"),
+    ("tray.recent_empty", "No recent items"),
+    ("status.not_connected", "Backend: not connected"),
+    ("status.backend", "Backend: {}"),
+    ("status.starting", "starting"),
+    ("status.running", "running"),
+    ("status.degraded", "degraded"),
+    ("status.down", "down"),
+    ("status.job_one", "{} job"),
+    ("status.job_many", "{} jobs"),
+    ("status.memory", "{} MB RAM"),
+    ("status.version", "Backend {}"),
+    ("status.version_uptime", "Backend {} · up {}"),
+    ("status.unknown_version", "unknown version"),
+    ("notify.backend_down", "Backend is down"),
+    (
+        "notify.backend_down_body",
+        "AetherFrame lost its connection to the analysis backend.",
+    ),
+    ("notify.backend_recovered", "Backend recovered"),
+    (
+        "notify.backend_recovered_body",
+        "The analysis backend is reachable again.",
+    ),
+    ("notify.job_finished", "Job finished"),
+    ("notify.job_failed", "Job failed"),
+    ("notify.job_body", "Job #{} ({}) is {}."),
+    ("splash.starting", "Starting analysis backend…"),
+    (
+        "splash.timeout",
+        "The backend did not become ready in time.",
+    ),
+    ("close.title", "Close AetherFrame"),
+    ("close.message", "Keep AetherFrame running in the tray when the window is closed? Choose No to quit instead. You can change this later in the settings."),
+    ("login.success", "You are signed in to AetherFrame. You can close this tab."),
+    ("login.failed", "Sign-in failed. Return to AetherFrame for details."),
+    ("tray.update_ready", "Update ready — Restart to apply"),
+    ("notify.update_ready", "Update ready"),
+    ("notify.update_ready_body", "AetherFrame {} has been downloaded and will be installed the next time it restarts."),
+    ("update.restart_title", "Restart to update"),
+    ("update.restart_message", "{} jobs are still running and will be interrupted. Restart and install the update now?"),
+    ("crash.title", "AetherFrame closed unexpectedly"),
+    ("crash.message_one", "AetherFrame didn't shut down properly last time. Send the crash report to help fix the problem? Your user name, computer name and other personal details are removed first."),
+    ("crash.message_many", "AetherFrame didn't shut down properly {} times. Send the crash reports to help fix the problem? Your user name, computer name and other personal details are removed first."),
+    ("crash.fatal_title", "AetherFrame has to close"),
+    ("crash.fatal_message", "AetherFrame ran into a problem it can't recover from and will close now: {}\n\nCopy the details to include them in a bug report, or open the logs folder."),
+    ("crash.copy_details", "Copy details"),
+    ("crash.open_logs", "Open logs folder"),
+    ("notify.view_result", "View result"),
+    ("notify.actions", "Actions"),
+    ("badge.description", "{} new"),
+    ("tray.autostart", "Start at login"),
+    ("documents.type_name", "AetherFrame project"),
+    ("power.jobs_running", "Backend jobs are running"),
+    ("actions.new_job", "New job"),
+    ("actions.show_status", "Show status"),
+    ("actions.recent", "Recent"),
+    ("menu.file", "File"),
+    ("menu.edit", "Edit"),
+    ("menu.view", "View"),
+    ("menu.window", "Window"),
+    ("menu.help", "Help"),
+    ("menu.new_job", "New Job"),
+    ("menu.settings", "Settings…"),
+    ("menu.zoom_in", "Zoom In"),
+    ("menu.zoom_out", "Zoom Out"),
+    ("menu.zoom_reset", "Actual Size"),
+    ("menu.fullscreen", "Toggle Full Screen"),
+    ("menu.check_updates", "Check for Updates…"),
+];
+
+const ES: Catalog = &[
+    ("tray.show", "Mostrar"),
+    ("tray.hide", "Ocultar"),
+    ("tray.always_on_top", "Siempre visible"),
+    ("tray.widget", "Mini widget"),
+    ("tray.console", "Consola de registros"),
+    ("tray.update", "Actualización disponible: {}"),
+    ("tray.quit", "Salir"),
+    ("tray.docs", "Documentación de la API"),
+    ("tray.status", "Estado del sistema"),
+    ("tray.restart", "Reiniciar backend"),
+    ("tray.profile", "Perfil: {}"),
+    ("tray.recent", "Recientes"),
+    ("tray.recent_empty", "Sin elementos recientes"),
+    ("status.not_connected", "Backend: sin conexión"),
+    ("status.backend", "Backend: {}"),
+    ("status.starting", "iniciando"),
+    ("status.running", "en marcha"),
+    ("status.degraded", "degradado"),
+    ("status.down", "caído"),
+    ("status.job_one", "{} tarea"),
+    ("status.job_many", "{} tareas"),
+    ("status.memory", "{} MB de RAM"),
+    ("status.version", "Backend {}"),
+    ("status.version_uptime", "Backend {} · activo {}"),
+    ("status.unknown_version", "versión desconocida"),
+    ("notify.backend_down", "El backend no responde"),
+    (
+        "notify.backend_down_body",
+        "AetherFrame perdió la conexión con el backend de análisis.",
+    ),
+    ("notify.backend_recovered", "Backend recuperado"),
+    (
+        "notify.backend_recovered_body",
+        "El backend de análisis vuelve a estar disponible.",
+    ),
+    ("notify.job_finished", "Tarea terminada"),
+    ("notify.job_failed", "Tarea fallida"),
+    ("notify.job_body", "La tarea #{} ({}) está {}."),
+    ("splash.starting", "Iniciando el backend de análisis…"),
+    ("splash.timeout", "El backend no estuvo listo a tiempo."),
+    ("close.title", "Cerrar AetherFrame"),
+    ("close.message", "¿Mantener AetherFrame en la bandeja del sistema al cerrar la ventana? Elija No para salir. Puede cambiarlo más tarde en la configuración."),
+    ("login.success", "Ha iniciado sesión en AetherFrame. Ya puede cerrar esta pestaña."),
+    ("login.failed", "No se pudo iniciar sesión. Vuelva a AetherFrame para ver los detalles."),
+    ("tray.update_ready", "Actualización lista — Reiniciar para aplicar"),
+    ("notify.update_ready", "Actualización lista"),
+    ("notify.update_ready_body", "AetherFrame {} se ha descargado y se instalará la próxima vez que se reinicie."),
+    ("update.restart_title", "Reiniciar para actualizar"),
+    ("update.restart_message", "Todavía hay {} tareas en curso que se interrumpirán. ¿Reiniciar e instalar la actualización ahora?"),
+    ("crash.title", "AetherFrame se cerró inesperadamente"),
+    ("crash.message_one", "AetherFrame no se cerró correctamente la última vez. ¿Enviar el informe de errores para ayudar a solucionarlo? Antes se eliminan tu nombre de usuario, el nombre del equipo y otros datos personales."),
+    ("crash.message_many", "AetherFrame no se cerró correctamente {} veces. ¿Enviar los informes de errores para ayudar a solucionarlo? Antes se eliminan tu nombre de usuario, el nombre del equipo y otros datos personales."),
+    ("crash.fatal_title", "AetherFrame tiene que cerrarse"),
+    ("crash.fatal_message", "AetherFrame encontró un problema del que no puede recuperarse y se cerrará ahora: {}\n\nCopia los detalles para incluirlos en un informe de errores o abre la carpeta de registros."),
+    ("crash.copy_details", "Copiar detalles"),
+    ("crash.open_logs", "Abrir carpeta de registros"),
+    ("notify.view_result", "Ver resultado"),
+    ("notify.actions", "Acciones"),
+    ("badge.description", "{} nuevos"),
+    ("tray.autostart", "Iniciar al iniciar sesión"),
+    ("documents.type_name", "Proyecto de AetherFrame"),
+    ("power.jobs_running", "Hay trabajos del backend en curso"),
+    ("actions.new_job", "Nuevo trabajo"),
+    ("actions.show_status", "Ver estado"),
+    ("actions.recent", "Recientes"),
+    ("menu.file", "Archivo"),
+    ("menu.edit", "Edición"),
+    ("menu.view", "Ver"),
+    ("menu.window", "Ventana"),
+    ("menu.help", "Ayuda"),
+    ("menu.new_job", "Nuevo trabajo"),
+    ("menu.settings", "Ajustes…"),
+    ("menu.zoom_in", "Acercar"),
+    ("menu.zoom_out", "Alejar"),
+    ("menu.zoom_reset", "Tamaño real"),
+    ("menu.fullscreen", "Pantalla completa"),
+    ("menu.check_updates", "Buscar actualizaciones…"),
+];
+
+const DE: Catalog = &[
+    ("tray.show", "Anzeigen"),
+    ("tray.hide", "Ausblenden"),
+    ("tray.always_on_top", "Immer im Vordergrund"),
+    ("tray.widget", "Mini-Widget"),
+    ("tray.console", "Log-Konsole"),
+    ("tray.update", "Update verfügbar: {}"),
+    ("tray.quit", "Beenden"),
+    ("tray.docs", "API-Dokumentation"),
+    ("tray.status", "Systemstatus"),
+    ("tray.restart", "Backend neu starten"),
+    ("tray.profile", "Profil: {}"),
+    ("tray.recent", "Zuletzt verwendet"),
+    ("tray.recent_empty", "Keine Einträge"),
+    ("status.not_connected", "Backend: nicht verbunden"),
+    ("status.backend", "Backend: {}"),
+    ("status.starting", "startet"),
+    ("status.running", "läuft"),
+    ("status.degraded", "eingeschränkt"),
+    ("status.down", "nicht erreichbar"),
+    ("status.job_one", "{} Auftrag"),
+    ("status.job_many", "{} Aufträge"),
+    ("status.memory", "{} MB RAM"),
+    ("status.version", "Backend {}"),
+    ("status.version_uptime", "Backend {} · läuft seit {}"),
+    ("status.unknown_version", "unbekannte Version"),
+    ("notify.backend_down", "Backend nicht erreichbar"),
+    (
+        "notify.backend_down_body",
+        "AetherFrame hat die Verbindung zum Analyse-Backend verloren.",
+    ),
+    ("notify.backend_recovered", "Backend wieder erreichbar"),
+    (
+        "notify.backend_recovered_body",
+        "Das Analyse-Backend ist wieder erreichbar.",
+    ),
+    ("notify.job_finished", "Auftrag abgeschlossen"),
+    ("notify.job_failed", "Auftrag fehlgeschlagen"),
+    ("notify.job_body", "Auftrag #{} ({}) ist {}."),
+    ("splash.starting", "Analyse-Backend wird gestartet…"),
+    (
+        "splash.timeout",
+        "Das Backend ist nicht rechtzeitig bereit geworden.",
+    ),
+    ("close.title", "AetherFrame schließen"),
+    ("close.message", "Soll AetherFrame beim Schließen des Fensters im Infobereich weiterlaufen? Wählen Sie Nein, um das Programm zu beenden. Dies lässt sich später in den Einstellungen ändern."),
+    ("login.success", "Sie sind bei AetherFrame angemeldet. Dieser Tab kann geschlossen werden."),
+    ("login.failed", "Die Anmeldung ist fehlgeschlagen. Details finden Sie in AetherFrame."),
+    ("tray.update_ready", "Update bereit – Zum Anwenden neu starten"),
+    ("notify.update_ready", "Update bereit"),
+    ("notify.update_ready_body", "AetherFrame {} wurde heruntergeladen und wird beim nächsten Neustart installiert."),
+    ("update.restart_title", "Neu starten zum Aktualisieren"),
+    ("update.restart_message", "Es laufen noch {} Aufträge, die abgebrochen werden. Jetzt neu starten und das Update installieren?"),
+    ("crash.title", "AetherFrame wurde unerwartet beendet"),
+    ("crash.message_one", "AetherFrame wurde beim letzten Mal nicht ordnungsgemäß beendet. Fehlerbericht senden, um das Problem zu beheben? Benutzername, Computername und andere persönliche Daten werden vorher entfernt."),
+    ("crash.message_many", "AetherFrame wurde {}-mal nicht ordnungsgemäß beendet. Fehlerberichte senden, um das Problem zu beheben? Benutzername, Computername und andere persönliche Daten werden vorher entfernt."),
+    ("crash.fatal_title", "AetherFrame muss beendet werden"),
+    ("crash.fatal_message", "AetherFrame ist auf ein Problem gestoßen, das nicht behoben werden kann, und wird jetzt beendet: {}\n\nKopiere die Details für einen Fehlerbericht oder öffne den Protokollordner."),
+    ("crash.copy_details", "Details kopieren"),
+    ("crash.open_logs", "Protokollordner öffnen"),
+    ("notify.view_result", "Ergebnis anzeigen"),
+    ("notify.actions", "Aktionen"),
+    ("badge.description", "{} neu"),
+    ("tray.autostart", "Bei Anmeldung starten"),
+    ("documents.type_name", "AetherFrame-Projekt"),
+    ("power.jobs_running", "Backend-Aufträge laufen"),
+    ("actions.new_job", "Neuer Auftrag"),
+    ("actions.show_status", "Status anzeigen"),
+    ("actions.recent", "Zuletzt verwendet"),
+    ("menu.file", "Ablage"),
+    ("menu.edit", "Bearbeiten"),
+    ("menu.view", "Darstellung"),
+    ("menu.window", "Fenster"),
+    ("menu.help", "Hilfe"),
+    ("menu.new_job", "Neuer Auftrag"),
+    ("menu.settings", "Einstellungen …"),
+    ("menu.zoom_in", "Vergrößern"),
+    ("menu.zoom_out", "Verkleinern"),
+    ("menu.zoom_reset", "Originalgröße"),
+    ("menu.fullscreen", "Vollbild umschalten"),
+    ("menu.check_updates", "Nach Updates suchen …"),
+];
+
+const FR: Catalog = &[
+    ("tray.show", "Afficher"),
+    ("tray.hide", "Masquer"),
+    ("tray.always_on_top", "Toujours au premier plan"),
+    ("tray.widget", "Mini-widget"),
+    ("tray.console", "Console des journaux"),
+    ("tray.update", "Mise à jour disponible : {}"),
+    ("tray.quit", "Quitter"),
+    ("tray.docs", "Documentation de l'API"),
+    ("tray.status", "État du système"),
+    ("tray.restart", "Redémarrer le backend"),
+    ("tray.profile", "Profil : {}"),
+    ("tray.recent", "Récents"),
+    ("tray.recent_empty", "Aucun élément récent"),
+    ("status.not_connected", "Backend : non connecté"),
+    ("status.backend", "Backend : {}"),
+    ("status.starting", "démarrage"),
+    ("status.running", "en cours"),
+    ("status.degraded", "dégradé"),
+    ("status.down", "hors ligne"),
+    ("status.job_one", "{} tâche"),
+    ("status.job_many", "{} tâches"),
+    ("status.memory", "{} Mo de RAM"),
+    ("status.version", "Backend {}"),
+    ("status.version_uptime", "Backend {} · actif depuis {}"),
+    ("status.unknown_version", "version inconnue"),
+    ("notify.backend_down", "Backend hors ligne"),
+    (
+        "notify.backend_down_body",
+        "AetherFrame a perdu la connexion au backend d'analyse.",
+    ),
+    ("notify.backend_recovered", "Backend rétabli"),
+    (
+        "notify.backend_recovered_body",
+        "Le backend d'analyse est de nouveau joignable.",
+    ),
+    ("notify.job_finished", "Tâche terminée"),
+    ("notify.job_failed", "Tâche échouée"),
+    ("notify.job_body", "La tâche #{} ({}) est {}."),
+    ("splash.starting", "Démarrage du backend d'analyse…"),
+    ("splash.timeout", "Le backend n'a pas été prêt à temps."),
+    ("close.title", "Fermer AetherFrame"),
+    ("close.message", "Garder AetherFrame dans la zone de notification à la fermeture de la fenêtre ? Choisissez Non pour quitter. Vous pourrez modifier ce choix plus tard dans les paramètres."),
+    ("login.success", "Vous êtes connecté à AetherFrame. Vous pouvez fermer cet onglet."),
+    ("login.failed", "La connexion a échoué. Revenez à AetherFrame pour plus de détails."),
+    ("tray.update_ready", "Mise à jour prête — Redémarrer pour appliquer"),
+    ("notify.update_ready", "Mise à jour prête"),
+    ("notify.update_ready_body", "AetherFrame {} a été téléchargé et sera installé au prochain redémarrage."),
+    ("update.restart_title", "Redémarrer pour mettre à jour"),
+    ("update.restart_message", "{} tâches sont encore en cours et seront interrompues. Redémarrer et installer la mise à jour maintenant ?"),
+    ("crash.title", "AetherFrame s'est fermé de manière inattendue"),
+    ("crash.message_one", "AetherFrame ne s'est pas fermé correctement la dernière fois. Envoyer le rapport de plantage pour aider à corriger le problème ? Votre nom d'utilisateur, le nom de l'ordinateur et les autres données personnelles sont supprimés avant l'envoi."),
+    ("crash.message_many", "AetherFrame ne s'est pas fermé correctement {} fois. Envoyer les rapports de plantage pour aider à corriger le problème ? Votre nom d'utilisateur, le nom de l'ordinateur et les autres données personnelles sont supprimés avant l'envoi."),
+    ("crash.fatal_title", "AetherFrame doit se fermer"),
+    ("crash.fatal_message", "AetherFrame a rencontré un problème irrécupérable et va se fermer : {}\n\nCopiez les détails pour les joindre à un rapport de bug, ou ouvrez le dossier des journaux."),
+    ("crash.copy_details", "Copier les détails"),
+    ("crash.open_logs", "Ouvrir le dossier des journaux"),
+    ("notify.view_result", "Voir le résultat"),
+    ("notify.actions", "Actions"),
+    ("badge.description", "{} nouveaux"),
+    ("tray.autostart", "Lancer à l'ouverture de session"),
+    ("documents.type_name", "Projet AetherFrame"),
+    ("power.jobs_running", "Des tâches du backend sont en cours"),
+    ("actions.new_job", "Nouvelle tâche"),
+    ("actions.show_status", "Afficher l'état"),
+    ("actions.recent", "Récents"),
+    ("menu.file", "Fichier"),
+    ("menu.edit", "Édition"),
+    ("menu.view", "Présentation"),
+    ("menu.window", "Fenêtre"),
+    ("menu.help", "Aide"),
+    ("menu.new_job", "Nouvelle tâche"),
+    ("menu.settings", "Réglages…"),
+    ("menu.zoom_in", "Zoom avant"),
+    ("menu.zoom_out", "Zoom arrière"),
+    ("menu.zoom_reset", "Taille réelle"),
+    ("menu.fullscreen", "Plein écran"),
+    ("menu.check_updates", "Rechercher des mises à jour…"),
+];
+
+const CATALOGS: &[(&str, Catalog)] = &[("en", EN), ("es", ES), ("de", DE), ("fr", FR)];
+
+fn catalog(language: &str) -> Option<Catalog> {
+    CATALOGS
+        .iter()
+        .find(|(code, _)| *code == language)
+        .map(|(_, catalog)| *catalog)
+}
+
+// "es-MX" / "es_MX.UTF-8" -> "es", if we have it
+fn supported(locale: &str) -> Option<&'static str> {
+    let code = locale
+        .split(['-', '_', '.'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    CATALOGS.iter().map(|(c, _)| *c).find(|c| *c == code)
+}
+
+// The configured language, else the OS locale, else English
+pub fn language(settings: &Settings) -> &'static str {
+    settings
+        .language
+        .as_deref()
+        .and_then(supported)
+        .or_else(|| sys_locale::get_locale().as_deref().and_then(supported))
+        .unwrap_or(DEFAULT_LANGUAGE)
+}
+
+// Falls back to English, then to the key itself so a missing string is visible
+pub fn lookup(language: &str, key: &'static str) -> &'static str {
+    let find = |catalog: Catalog| {
+        catalog
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, text)| *text)
+    };
+    catalog(language)
+        .and_then(find)
+        .or_else(|| find(EN))
+        .unwrap_or(key)
+}
+
+// Substitutes each "{}" with the next argument
+pub fn format(language: &str, key: &'static str, args: &[&str]) -> String {
+    let mut text = lookup(language, key).to_string();
+    for arg in args {
+        text = text.replacen("{}", arg, 1);
+    }
+    text
+}
+
+pub fn text(app: &AppHandle, key: &'static str) -> String {
+    let settings = app.state::<AppState>().settings.get();
+    lookup(language(&settings), key).to_string()
+}
+
+#[derive(Serialize)]
+pub struct LanguageInfo {
+    // What's in effect right now
+    pub language: String,
+    // The saved preference; None follows the OS
+    pub configured: Option<String>,
+    pub available: Vec<String>,
+}
+
+#[tauri::command]
+pub async fn get_language(state: State<'_, AppState>) -> Result<LanguageInfo, String> {
+    let settings = state.settings.get();
+    Ok(LanguageInfo {
+        language: language(&settings).to_string(),
+        configured: settings.language.clone(),
+        available: CATALOGS.iter().map(|(c, _)| c.to_string()).collect(),
+    })
+}
+
+// Pass None to follow the OS locale again
+#[tauri::command]
+pub async fn set_language(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    language: Option<String>,
+) -> Result<(), String> {
+    if let Some(code) = &language {
+        if supported(code).is_none() {
+            return Err(format!("Unsupported language: {}", code));
+        }
+    }
+    state.settings.update(|s| s.language = language)?;
+    tray::rebuild(&app);
+    menu::refresh(&app);
+    quick_actions::refresh(&app);
+    let _ = app.emit_all("language-changed", self::language(&state.settings.get()));
+    Ok(())
+}
@@ -0,0 +1,229 @@
+// Idle lock for shared machines: after a configurable stretch without input
+// the windows are hidden, in-memory tokens and cached responses are dropped and
+// commands are refused until the user signs in again (or, for profiles without
+// a sign-in provider, passes the OS lock screen: asking to unlock only shows
+// it, and the app unlocks when the OS says the session was unlocked, see
+// `session`).
+
+mod session;
+
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::auth;
+use crate::backend::logs::{self, Level};
+use crate::profiles;
+use crate::state::AppState;
+use crate::window::{self, console, widget};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+// What the lock screen needs; everything else is refused while locked
+pub const WHILE_LOCKED: &[&str] = &[
+    "get_lock_state",
+    "unlock_session",
+    "report_activity",
+    "get_session",
+    "get_language",
+    "is_first_run",
+    "log_from_frontend",
+    "report_frontend_error",
+];
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IdleSettings {
+    // Minutes without input before locking; 0 turns the lock off
+    pub lock_after_mins: u64,
+    // Also lock the OS session when the app locks
+    pub lock_os: bool,
+}
+
+pub struct IdleLock {
+    last_activity: Mutex<Instant>,
+    locked: AtomicBool,
+}
+
+impl Default for IdleLock {
+    fn default() -> Self {
+        Self {
+            last_activity: Mutex::new(Instant::now()),
+            locked: AtomicBool::new(false),
+        }
+    }
+}
+
+impl IdleLock {
+    pub fn is_locked(&self) -> bool {
+        self.locked.load(Ordering::SeqCst)
+    }
+
+    pub fn touch(&self) {
+        *self.last_activity.lock().unwrap() = Instant::now();
+    }
+
+    fn idle_for(&self) -> Duration {
+        self.last_activity.lock().unwrap().elapsed()
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct LockState {
+    pub locked: bool,
+    // How unlocking works for the active profile: "login" or "os"
+    pub unlock: &'static str,
+}
+
+fn lock_state(app: &AppHandle) -> LockState {
+    let state = app.state::<AppState>();
+    let profile = profiles::active(&state.settings.get());
+    LockState {
+        locked: state.idle.is_locked(),
+        unlock: if profile.oauth.is_some() {
+            "login"
+        } else {
+            "os"
+        },
+    }
+}
+
+pub fn start(app: AppHandle) {
+    if let Err(e) = session::watch(&app) {
+        tracing::warn!("Not watching for OS session unlocks: {}", e);
+    }
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            let state = app.state::<AppState>();
+            let minutes = state.settings.get().idle.lock_after_mins;
+            if minutes == 0 || state.idle.is_locked() {
+                continue;
+            }
+            if state.idle.idle_for() >= Duration::from_secs(minutes * 60) {
+                lock(&app).await;
+            }
+        }
+    });
+}
+
+pub async fn lock(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    if state.idle.locked.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    if let Some(main) = app.get_window(window::MAIN) {
+        let _ = main.hide();
+    }
+    for label in [widget::LABEL, console::LABEL] {
+        if let Some(window) = app.get_window(label) {
+            let _ = window.close();
+        }
+    }
+    state.auth.clear().await;
+    state.http.cache.clear();
+    logs::internal(app, Level::Info, "Session locked".to_string());
+    let _ = app.emit_all("session-locked", lock_state(app));
+    if state.settings.get().idle.lock_os {
+        if let Err(e) = lock_os() {
+            tracing::warn!("{}", e);
+        }
+    }
+}
+
+// The OS lock screen was passed; only counts for profiles that unlock that way
+pub fn os_unlocked(app: &AppHandle) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    if state.idle.is_locked() && lock_state(app).unlock == "os" {
+        logs::internal(
+            app,
+            Level::Info,
+            "Unlocked at the OS lock screen".to_string(),
+        );
+        unlock(app);
+    }
+}
+
+fn unlock(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    state.idle.touch();
+    state.idle.locked.store(false, Ordering::SeqCst);
+    let _ = app.emit_all("session-unlocked", lock_state(app));
+}
+
+// Hand over to the OS lock screen; the user is back once they've passed it
+fn lock_os() -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = Command::new("rundll32.exe");
+        command.args(["user32.dll,LockWorkStation"]);
+        command
+    };
+    #[cfg(target_os = "macos")]
+    let mut command = {
+        // Locks when "require password after sleep" is on, the default
+        let mut command = Command::new("pmset");
+        command.arg("displaysleepnow");
+        command
+    };
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let mut command = {
+        let mut command = Command::new("loginctl");
+        command.arg("lock-session");
+        command
+    };
+    let status = command
+        .status()
+        .map_err(|e| format!("Failed to lock the session: {}", e))?;
+    if !status.success() {
+        return Err(format!("Failed to lock the session: {}", status));
+    }
+    Ok(())
+}
+
+// For the frontend to call on user input (throttled on its side); window
+// focus counts as activity on its own
+#[tauri::command]
+pub async fn report_activity(state: State<'_, AppState>) -> Result<(), String> {
+    state.idle.touch();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_lock_state(app: AppHandle) -> Result<LockState, String> {
+    Ok(lock_state(&app))
+}
+
+#[tauri::command]
+pub async fn lock_session(app: AppHandle) -> Result<(), String> {
+    lock(&app).await;
+    Ok(())
+}
+
+// Profiles with a sign-in provider go through it again. The others are sent
+// to the OS lock screen and stay locked until it has been passed, which
+// arrives as `session-unlocked` like any other unlock
+#[tauri::command]
+pub async fn unlock_session(app: AppHandle) -> Result<LockState, String> {
+    if !app.state::<AppState>().idle.is_locked() {
+        return Ok(lock_state(&app));
+    }
+    if lock_state(&app).unlock == "login" {
+        auth::login(app.clone(), None).await?;
+        unlock(&app);
+        return Ok(lock_state(&app));
+    }
+    if !session::watching() {
+        return Err("Can't tell when the OS lock screen is passed on this system".to_string());
+    }
+    tauri::async_runtime::spawn_blocking(lock_os)
+        .await
+        .map_err(|e| e.to_string())??;
+    Ok(lock_state(&app))
+}
@@ -0,0 +1,193 @@
+// When the OS session is unlocked, i.e. someone got past the OS lock screen:
+// WTS session notifications on Windows, the screenIsUnlocked distributed
+// notification on macOS, and logind's LockedHint going false on Linux (set by
+// the desktop's screen locker). Each calls `idle::os_unlocked`.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tauri::AppHandle;
+
+static WATCHING: AtomicBool = AtomicBool::new(false);
+
+// Without a watcher the OS lock screen can't unlock the app
+pub fn watching() -> bool {
+    WATCHING.load(Ordering::SeqCst)
+}
+
+pub fn watch(app: &AppHandle) -> Result<(), String> {
+    platform::watch(app)?;
+    WATCHING.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+// Session messages only go to windows, so one that's never shown gets them
+#[cfg(windows)]
+mod platform {
+    use std::sync::{mpsc, OnceLock};
+
+    use tauri::AppHandle;
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+    use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+    use windows::Win32::System::RemoteDesktop::{
+        WTSRegisterSessionNotification, NOTIFY_FOR_THIS_SESSION,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        CreateWindowExW, DefWindowProcW, DispatchMessageW, GetMessageW, RegisterClassW, HMENU,
+        HWND_MESSAGE, MSG, WINDOW_EX_STYLE, WINDOW_STYLE, WM_WTSSESSION_CHANGE, WNDCLASSW,
+        WTS_SESSION_UNLOCK,
+    };
+
+    static APP: OnceLock<AppHandle> = OnceLock::new();
+
+    unsafe extern "system" fn window_proc(
+        hwnd: HWND,
+        message: u32,
+        wparam: WPARAM,
+        lparam: LPARAM,
+    ) -> LRESULT {
+        if message == WM_WTSSESSION_CHANGE && wparam.0 as u32 == WTS_SESSION_UNLOCK {
+            if let Some(app) = APP.get() {
+                crate::idle::os_unlocked(app);
+            }
+        }
+        DefWindowProcW(hwnd, message, wparam, lparam)
+    }
+
+    // The window has to live on a thread that pumps its messages
+    fn run(ready: mpsc::Sender<Result<(), String>>) {
+        let class: Vec<u16> = "AetherFrameSessionWatcher\0".encode_utf16().collect();
+        let hwnd = unsafe {
+            let instance = GetModuleHandleW(PCWSTR::null()).unwrap_or_default();
+            let window_class = WNDCLASSW {
+                lpfnWndProc: Some(window_proc),
+                hInstance: instance,
+                lpszClassName: PCWSTR(class.as_ptr()),
+                ..Default::default()
+            };
+            RegisterClassW(&window_class);
+            CreateWindowExW(
+                WINDOW_EX_STYLE::default(),
+                PCWSTR(class.as_ptr()),
+                PCWSTR::null(),
+                WINDOW_STYLE::default(),
+                0,
+                0,
+                0,
+                0,
+                HWND_MESSAGE,
+                HMENU::default(),
+                instance,
+                std::ptr::null(),
+            )
+        };
+        if hwnd.0 == 0 {
+            let _ = ready.send(Err("Failed to create the session window".to_string()));
+            return;
+        }
+        if !unsafe { WTSRegisterSessionNotification(hwnd, NOTIFY_FOR_THIS_SESSION) }.as_bool() {
+            let _ = ready.send(Err("Failed to register for session changes".to_string()));
+            return;
+        }
+        let _ = ready.send(Ok(()));
+        let mut message = MSG::default();
+        // 0 is WM_QUIT, -1 an error
+        while unsafe { GetMessageW(&mut message, HWND::default(), 0, 0) }.0 > 0 {
+            unsafe { DispatchMessageW(&message) };
+        }
+    }
+
+    pub fn watch(app: &AppHandle) -> Result<(), String> {
+        if APP.set(app.clone()).is_err() {
+            return Ok(());
+        }
+        let (ready, started) = mpsc::channel();
+        std::thread::Builder::new()
+            .name("session-watcher".to_string())
+            .spawn(move || run(ready))
+            .map_err(|e| e.to_string())?;
+        started.recv().map_err(|e| e.to_string())?
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::ffi::CString;
+    use std::sync::OnceLock;
+
+    use objc::declare::ClassDecl;
+    use objc::runtime::{Object, Sel};
+    use objc::{class, msg_send, sel, sel_impl};
+    use tauri::AppHandle;
+
+    static APP: OnceLock<AppHandle> = OnceLock::new();
+
+    extern "C" fn unlocked(_this: &Object, _cmd: Sel, _note: *mut Object) {
+        if let Some(app) = APP.get() {
+            crate::idle::os_unlocked(app);
+        }
+    }
+
+    pub fn watch(app: &AppHandle) -> Result<(), String> {
+        if APP.set(app.clone()).is_err() {
+            return Ok(());
+        }
+        let mut decl = ClassDecl::new("AetherFrameSessionObserver", class!(NSObject))
+            .ok_or("observer class already exists")?;
+        type Handler = extern "C" fn(&Object, Sel, *mut Object);
+        unsafe {
+            decl.add_method(sel!(unlocked:), unlocked as Handler);
+            let observer: *mut Object = msg_send![decl.register(), new];
+            let center: *mut Object =
+                msg_send![class!(NSDistributedNotificationCenter), defaultCenter];
+            let name = CString::new("com.apple.screenIsUnlocked").unwrap();
+            let name: *mut Object =
+                msg_send![class!(NSString), stringWithUTF8String: name.as_ptr()];
+            let nil: *mut Object = std::ptr::null_mut();
+            let _: () = msg_send![center,
+                addObserver: observer
+                selector: sel!(unlocked:)
+                name: name
+                object: nil];
+        }
+        Ok(())
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use tauri::AppHandle;
+    use zbus::blocking::{Connection, Proxy};
+    use zbus::zvariant::OwnedObjectPath;
+
+    pub fn watch(app: &AppHandle) -> Result<(), String> {
+        let connection = Connection::system().map_err(|e| e.to_string())?;
+        // Signals come from the session's own object, not the "auto" alias
+        let session = (|| -> zbus::Result<Proxy<'static>> {
+            let manager = Proxy::new(
+                &connection,
+                "org.freedesktop.login1",
+                "/org/freedesktop/login1",
+                "org.freedesktop.login1.Manager",
+            )?;
+            let path: OwnedObjectPath = manager.call("GetSession", &("auto",))?;
+            Proxy::new(
+                &connection,
+                "org.freedesktop.login1",
+                path,
+                "org.freedesktop.login1.Session",
+            )
+        })()
+        .map_err(|e| e.to_string())?;
+        let app = app.clone();
+        std::thread::spawn(move || {
+            for changed in session.receive_property_changed::<bool>("LockedHint") {
+                if changed.get().is_ok_and(|locked| !locked) {
+                    crate::idle::os_unlocked(&app);
+                }
+            }
+            tracing::warn!("Stopped watching for OS session unlocks");
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,207 @@
+// Files dragged onto a window. The drop is handled here rather than in the
+// webview: each file is checked against `ingest` settings (extension, size),
+// optionally hashed, then either streamed to the backend's upload endpoint
+// or described to the frontend for it to deal with.
+//
+// Events, all to the window the files were dropped on:
+// - `file-drop-hover` with the paths while something is dragged over it,
+//   `file-drop-cancelled` when it leaves
+// - `files-dropped` with the batch and every file's metadata, rejected ones
+//   included with the reason
+// - `file-ingested` per uploaded file; upload progress is the usual
+//   `transfer-progress`, with the file's id as the request id
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, FileDropEvent, Manager, Window};
+
+use crate::http::transfer::{self, TransferResult};
+use crate::state::AppState;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IngestSettings {
+    // Without the dot, any case; empty accepts everything
+    pub extensions: Vec<String>,
+    // 0 means no limit
+    pub max_size_mb: u64,
+    // SHA-256 of each file before it's handed on
+    pub hash: bool,
+    // Backend path to upload dropped files to; None leaves them to the frontend
+    pub upload_endpoint: Option<String>,
+}
+
+impl Default for IngestSettings {
+    fn default() -> Self {
+        Self {
+            extensions: Vec::new(),
+            max_size_mb: 512,
+            hash: true,
+            upload_endpoint: None,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct DroppedFile {
+    id: String,
+    path: PathBuf,
+    name: String,
+    extension: Option<String>,
+    size: u64,
+    sha256: Option<String>,
+    accepted: bool,
+    // Why it was rejected
+    error: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+struct Dropped {
+    batch: String,
+    files: Vec<DroppedFile>,
+    // Whether accepted files are on their way to the backend
+    uploading: bool,
+}
+
+#[derive(Clone, Serialize)]
+struct Ingested {
+    id: String,
+    path: PathBuf,
+    result: Option<TransferResult>,
+    error: Option<String>,
+}
+
+fn new_id() -> String {
+    format!("{:016x}", rand::random::<u64>())
+}
+
+fn check(settings: &IngestSettings, path: &Path, size: u64) -> Result<(), String> {
+    if !settings.extensions.is_empty() {
+        let extension = path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+        let allowed = settings.extensions.iter().any(|allowed| {
+            allowed
+                .trim_start_matches('.')
+                .eq_ignore_ascii_case(&extension)
+        });
+        if !allowed {
+            return Err(format!("{} files are not accepted", extension));
+        }
+    }
+    let limit = settings.max_size_mb * 1024 * 1024;
+    if settings.max_size_mb > 0 && size > limit {
+        return Err(format!("Larger than the {} MB limit", settings.max_size_mb));
+    }
+    Ok(())
+}
+
+async fn describe(
+    app: &AppHandle,
+    settings: &IngestSettings,
+    batch: &str,
+    index: usize,
+    path: PathBuf,
+) -> DroppedFile {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut file = DroppedFile {
+        id: format!("{}-{}", batch, index),
+        extension: path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_ascii_lowercase()),
+        name,
+        size: 0,
+        sha256: None,
+        accepted: false,
+        error: None,
+        path,
+    };
+    let checked = match tokio::fs::metadata(&file.path).await {
+        Ok(meta) if meta.is_dir() => Err("Folders can't be dropped".to_string()),
+        Ok(meta) => {
+            file.size = meta.len();
+            check(settings, &file.path, file.size)
+        }
+        Err(e) => Err(format!("Can't read the file: {}", e)),
+    };
+    let hashed = match checked {
+        Ok(()) if settings.hash => transfer::sha256_file(app, &file.path).await.map(Some),
+        Ok(()) => Ok(None),
+        Err(e) => Err(e),
+    };
+    match hashed {
+        Ok(sha256) => {
+            file.sha256 = sha256;
+            file.accepted = true;
+        }
+        Err(e) => file.error = Some(e),
+    }
+    file
+}
+
+async fn ingest(window: Window, paths: Vec<PathBuf>) {
+    let settings = window.state::<AppState>().settings.get().ingest;
+    let batch = new_id();
+    let mut files = Vec::with_capacity(paths.len());
+    for (index, path) in paths.into_iter().enumerate() {
+        files.push(describe(&window.app_handle(), &settings, &batch, index, path).await);
+    }
+    let endpoint = settings.upload_endpoint.filter(|e| !e.is_empty());
+    let _ = window.emit(
+        "files-dropped",
+        Dropped {
+            batch,
+            files: files.clone(),
+            uploading: endpoint.is_some(),
+        },
+    );
+    let Some(endpoint) = endpoint else {
+        return;
+    };
+    let app = window.app_handle();
+    // One at a time, so a large batch doesn't saturate the backend
+    for file in files.into_iter().filter(|file| file.accepted) {
+        let uploaded = transfer::upload(
+            &app,
+            file.path.clone(),
+            &endpoint,
+            file.sha256.as_deref(),
+            Some(file.id.clone()),
+        )
+        .await;
+        let (result, error) = match uploaded {
+            Ok(result) => (Some(result), None),
+            Err(e) => (None, Some(e)),
+        };
+        let _ = window.emit(
+            "file-ingested",
+            Ingested {
+                id: file.id,
+                path: file.path,
+                result,
+                error,
+            },
+        );
+    }
+}
+
+pub fn on_file_drop(window: &Window, event: &FileDropEvent) {
+    match event {
+        FileDropEvent::Hovered(paths) => {
+            let _ = window.emit("file-drop-hover", paths);
+        }
+        FileDropEvent::Dropped(paths) => {
+            let (window, paths) = (window.clone(), paths.clone());
+            tauri::async_runtime::spawn(ingest(window, paths));
+        }
+        FileDropEvent::Cancelled => {
+            let _ = window.emit("file-drop-cancelled", ());
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,210 @@
+// One running instance per user. The first launch listens on a local socket
+// (a named pipe on Windows); later launches hand it their arguments and exit
+// instead of starting a second tray and backend. The running instance opens
+// a forwarded deep link or project files, or just comes to the front, and
+// tells the frontend with `second-instance`.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+
+use crate::cli::LaunchArgs;
+use crate::window;
+use crate::{deeplink, documents};
+
+pub use platform::Listener;
+
+// One JSON line per launch; anything longer isn't ours
+const MAX_MESSAGE: u64 = 64 * 1024;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Forwarded {
+    pub args: Vec<String>,
+    pub cwd: Option<String>,
+}
+
+pub enum Claim {
+    // We're the instance; keep the listener for `listen`
+    Primary(Listener),
+    // Another instance has our arguments, so this one should exit
+    Forwarded,
+}
+
+// Runs before Tauri starts, so there's nothing to tear down on the way out
+pub fn claim(identifier: &str, args: &[String]) -> Result<Claim, String> {
+    let message = Forwarded {
+        args: args.to_vec(),
+        cwd: std::env::current_dir()
+            .ok()
+            .map(|dir| dir.display().to_string()),
+    };
+    tauri::async_runtime::block_on(async {
+        if let Some(listener) = platform::bind(identifier)
+            .await
+            .map_err(|e| format!("Failed to claim the single instance lock: {}", e))?
+        {
+            return Ok(Claim::Primary(listener));
+        }
+        forward(identifier, &message)
+            .await
+            .map_err(|e| format!("Failed to reach the running instance: {}", e))?;
+        Ok(Claim::Forwarded)
+    })
+}
+
+async fn forward(identifier: &str, message: &Forwarded) -> std::io::Result<()> {
+    let mut stream = platform::connect(identifier).await?;
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    stream.write_all(&line).await?;
+    stream.flush().await
+}
+
+pub fn listen(app: AppHandle, mut listener: Listener) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let stream = match listener.accept().await {
+                Ok(stream) => stream,
+                Err(e) => {
+                    tracing::warn!("Single instance listener stopped: {}", e);
+                    return;
+                }
+            };
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let mut line = String::new();
+                let mut reader = BufReader::new(stream.take(MAX_MESSAGE));
+                if reader.read_line(&mut line).await.is_err() {
+                    return;
+                }
+                match serde_json::from_str::<Forwarded>(&line) {
+                    Ok(message) => forwarded(&app, message),
+                    Err(e) => {
+                        tracing::warn!("Ignoring a bad message from a second instance: {}", e)
+                    }
+                }
+            });
+        }
+    });
+}
+
+fn forwarded(app: &AppHandle, message: Forwarded) {
+    let args = LaunchArgs::parse(message.args.clone());
+    let _ = app.emit_all("second-instance", &message);
+    if let Some(link) = &args.link {
+        deeplink::open(app, link);
+    } else if !args.files.is_empty() {
+        // Relative paths are relative to where the second launch ran
+        let cwd = message.cwd.as_deref().map(Path::new);
+        for file in &args.files {
+            documents::open(app, &cwd.map_or(file.clone(), |cwd| cwd.join(file)));
+        }
+    } else if !args.minimized {
+        // e.g. the autostart entry firing while we already run
+        window::activate(app);
+    }
+}
+
+#[cfg(unix)]
+mod platform {
+    use std::io;
+    use std::path::PathBuf;
+
+    use tokio::net::{UnixListener, UnixStream};
+
+    pub struct Listener(UnixListener);
+
+    impl Listener {
+        pub async fn accept(&mut self) -> io::Result<UnixStream> {
+            Ok(self.0.accept().await?.0)
+        }
+    }
+
+    // Inside the per-user data directory, so other users can't reach it
+    fn path(identifier: &str) -> io::Result<PathBuf> {
+        let dir = tauri::api::path::local_data_dir()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no local data directory"))?
+            .join(identifier);
+        std::fs::create_dir_all(&dir)?;
+        Ok(dir.join("instance.sock"))
+    }
+
+    pub async fn bind(identifier: &str) -> io::Result<Option<Listener>> {
+        let path = path(identifier)?;
+        match UnixListener::bind(&path) {
+            Ok(listener) => return Ok(Some(Listener(listener))),
+            Err(e) if e.kind() != io::ErrorKind::AddrInUse => return Err(e),
+            Err(_) => {}
+        }
+        if UnixStream::connect(&path).await.is_ok() {
+            return Ok(None);
+        }
+        // Left behind by an instance that crashed
+        std::fs::remove_file(&path)?;
+        UnixListener::bind(&path).map(|listener| Some(Listener(listener)))
+    }
+
+    pub async fn connect(identifier: &str) -> io::Result<UnixStream> {
+        UnixStream::connect(path(identifier)?).await
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::io;
+    use std::time::Duration;
+
+    use tokio::net::windows::named_pipe::{
+        ClientOptions, NamedPipeClient, NamedPipeServer, ServerOptions,
+    };
+
+    // windows::Win32::Foundation::ERROR_PIPE_BUSY
+    const PIPE_BUSY: i32 = 231;
+
+    pub struct Listener {
+        name: String,
+        server: NamedPipeServer,
+    }
+
+    impl Listener {
+        pub async fn accept(&mut self) -> io::Result<NamedPipeServer> {
+            self.server.connect().await?;
+            // Open the next instance before handing this one off, so a launch
+            // never finds the pipe missing
+            let next = ServerOptions::new().create(&self.name)?;
+            Ok(std::mem::replace(&mut self.server, next))
+        }
+    }
+
+    // Pipes are machine-wide, so the name includes the user
+    fn name(identifier: &str) -> String {
+        let user = std::env::var("USERNAME").unwrap_or_default();
+        format!(r"\\.\pipe\{}-{}", identifier, user)
+    }
+
+    pub async fn bind(identifier: &str) -> io::Result<Option<Listener>> {
+        let name = name(identifier);
+        match ServerOptions::new().first_pipe_instance(true).create(&name) {
+            Ok(server) => Ok(Some(Listener { name, server })),
+            // Someone else created it first
+            Err(e) if e.kind() == io::ErrorKind::PermissionDenied => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub async fn connect(identifier: &str) -> io::Result<NamedPipeClient> {
+        let name = name(identifier);
+        let mut attempts = 0;
+        loop {
+            match ClientOptions::new().open(&name) {
+                Err(e) if e.raw_os_error() == Some(PIPE_BUSY) && attempts < 20 => {
+                    attempts += 1;
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                }
+                result => return result,
+            }
+        }
+    }
+}
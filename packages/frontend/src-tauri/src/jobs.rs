@@ -0,0 +1,143 @@
+// Tracks running backend jobs so progress can be shown outside the window
+// (tray icon/tooltip) and completion can be announced
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::notifications;
+use crate::power::inhibit;
+use crate::state::AppState;
+use crate::tray;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct JobProgress {
+    pub id: u64,
+    pub target: String,
+    pub status: String,
+    #[serde(default)]
+    pub progress: u32,
+}
+
+#[derive(Clone, Serialize)]
+struct ProgressPayload {
+    jobs: Vec<JobProgress>,
+    overall: Option<f64>,
+}
+
+#[derive(Default)]
+pub struct JobTracker {
+    running: Mutex<HashMap<u64, JobProgress>>,
+}
+
+impl JobTracker {
+    pub fn running(&self) -> Vec<JobProgress> {
+        let mut jobs: Vec<_> = self.running.lock().unwrap().values().cloned().collect();
+        jobs.sort_by_key(|j| j.id);
+        jobs
+    }
+
+    // Mean progress across running jobs, 0-100
+    pub fn overall(&self) -> Option<f64> {
+        let running = self.running.lock().unwrap();
+        if running.is_empty() {
+            return None;
+        }
+        let sum: u32 = running.values().map(|j| j.progress.min(100)).sum();
+        Some(sum as f64 / running.len() as f64)
+    }
+
+    // Replace the running set; returns the jobs that are no longer running
+    fn replace(&self, jobs: Vec<JobProgress>) -> Vec<JobProgress> {
+        let mut running = self.running.lock().unwrap();
+        let next: HashMap<_, _> = jobs.into_iter().map(|j| (j.id, j)).collect();
+        let finished = running
+            .values()
+            .filter(|j| !next.contains_key(&j.id))
+            .cloned()
+            .collect();
+        *running = next;
+        finished
+    }
+}
+
+// The health monitor's /status tells us whether polling /jobs is worth it
+fn backend_reports_running(app: &AppHandle) -> bool {
+    let health = app.state::<AppState>().health.snapshot();
+    health
+        .status
+        .as_ref()
+        .and_then(|s| s.pointer("/metrics/jobs_by_status/running"))
+        .and_then(|v| v.as_u64())
+        .is_some_and(|n| n > 0)
+}
+
+async fn fetch<T: for<'de> Deserialize<'de>>(app: &AppHandle, path: &str) -> Result<T, String> {
+    let state = app.state::<AppState>();
+    state
+        .http
+        .backend()
+        .get(format!("{}{}", state.backend.url(), path))
+        .headers(state.auth.headers(app).await)
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to fetch {}: {}", path, e))?
+        .json()
+        .await
+        .map_err(|e| format!("Unexpected {} response: {}", path, e))
+}
+
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            app.state::<AppState>().power.pause(POLL_INTERVAL).await;
+            let state = app.state::<AppState>();
+            if state.backend.is_stopping() {
+                break;
+            }
+            let tracking = !state.jobs.running.lock().unwrap().is_empty();
+            if !tracking && !backend_reports_running(&app) {
+                continue;
+            }
+            match fetch::<Vec<JobProgress>>(&app, "/jobs?status=running").await {
+                Ok(jobs) => update(&app, jobs).await,
+                Err(e) => tracing::warn!("{}", e),
+            }
+        }
+    });
+}
+
+async fn update(app: &AppHandle, jobs: Vec<JobProgress>) {
+    let state = app.state::<AppState>();
+    let finished = state.jobs.replace(jobs);
+    inhibit::jobs_changed(app, !state.jobs.running().is_empty()).await;
+    let _ = app.emit_all(
+        "job-progress",
+        ProgressPayload {
+            jobs: state.jobs.running(),
+            overall: state.jobs.overall(),
+        },
+    );
+    for job in finished {
+        // Look up the final status; fall back to what we last saw
+        let job = fetch::<JobProgress>(app, &format!("/jobs/{}", job.id))
+            .await
+            .unwrap_or(job);
+        let _ = app.emit_all("job-finished", &job);
+        notifications::job_finished(app, &job);
+    }
+    tray::icon::refresh(app);
+}
+
+#[tauri::command]
+pub async fn get_running_jobs(state: State<'_, AppState>) -> Result<Vec<JobProgress>, String> {
+    Ok(state.jobs.running())
+}
@@ -0,0 +1,189 @@
+// Timing for every IPC command, from Tauri's own request spans (its `tracing`
+// feature): a command starts when the span around dispatching its message
+// opens and ends when the response for it is built, whether that happens right
+// away or after an async command's future completes. Each command keeps a
+// count, an error count and the last SAMPLES durations for percentiles, for
+// `get_command_metrics`. Commands refused by the permission gate are answered
+// with an error, so they count as failures.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Metadata, Subscriber};
+use tracing_subscriber::filter::{filter_fn, FilterFn};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+// Entered around `Window::on_message`; carries `cmd`. (The command macro's own
+// `ipc::request::handler` span is closed before the command body even runs,
+// so it can't be used.)
+const HANDLE_SPAN: &str = "ipc::request::handle";
+// Sits between the two for async commands, whose response comes from a task
+// spawned while the handle span was current
+const RESPOND_SPAN: &str = "ipc::request::respond";
+// Opened once the result is in; carries `response`
+const RESPONSE_SPAN: &str = "ipc::request::response";
+const SAMPLES: usize = 512;
+
+#[derive(Default)]
+struct Stats {
+    count: u64,
+    errors: u64,
+    total: Duration,
+    max: Duration,
+    recent: VecDeque<Duration>,
+}
+
+static STATS: Mutex<BTreeMap<String, Stats>> = Mutex::new(BTreeMap::new());
+
+#[derive(Serialize)]
+pub struct CommandMetrics {
+    pub command: String,
+    pub count: u64,
+    pub errors: u64,
+    pub error_rate: f64,
+    // Milliseconds; percentiles are over the last SAMPLES calls
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+    pub total_ms: f64,
+}
+
+fn record(command: String, elapsed: Duration, failed: bool) {
+    let mut stats = STATS.lock().unwrap();
+    let stats = stats.entry(command).or_default();
+    stats.count += 1;
+    stats.errors += u64::from(failed);
+    stats.total += elapsed;
+    stats.max = stats.max.max(elapsed);
+    if stats.recent.len() == SAMPLES {
+        stats.recent.pop_front();
+    }
+    stats.recent.push_back(elapsed);
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+// Nearest rank over sorted samples
+fn percentile(sorted: &[Duration], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    millis(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+// Slowest overall first
+pub fn snapshot() -> Vec<CommandMetrics> {
+    let stats = STATS.lock().unwrap();
+    let mut metrics: Vec<CommandMetrics> = stats
+        .iter()
+        .map(|(command, stats)| {
+            let mut sorted: Vec<Duration> = stats.recent.iter().copied().collect();
+            sorted.sort();
+            CommandMetrics {
+                command: command.clone(),
+                count: stats.count,
+                errors: stats.errors,
+                error_rate: stats.errors as f64 / stats.count.max(1) as f64,
+                mean_ms: millis(stats.total) / stats.count.max(1) as f64,
+                p50_ms: percentile(&sorted, 50.0),
+                p90_ms: percentile(&sorted, 90.0),
+                p99_ms: percentile(&sorted, 99.0),
+                max_ms: millis(stats.max),
+                total_ms: millis(stats.total),
+            }
+        })
+        .collect();
+    metrics.sort_by(|a, b| b.total_ms.total_cmp(&a.total_ms));
+    metrics
+}
+
+// Kept on a handle span until its response shows up
+struct Started {
+    command: String,
+    at: Instant,
+}
+
+// Picks one field out of a span's attributes
+struct FieldVisitor {
+    name: &'static str,
+    value: Option<String>,
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == self.name {
+            self.value = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == self.name {
+            self.value = Some(format!("{:?}", value));
+        }
+    }
+}
+
+fn field(attrs: &Attributes<'_>, name: &'static str) -> Option<String> {
+    let mut visitor = FieldVisitor { name, value: None };
+    attrs.record(&mut visitor);
+    visitor.value
+}
+
+pub struct CommandLayer;
+
+impl<S> Layer<S> for CommandLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let name = attrs.metadata().name();
+        if name == HANDLE_SPAN {
+            let (Some(command), Some(span)) = (field(attrs, "cmd"), ctx.span(id)) else {
+                return;
+            };
+            span.extensions_mut().insert(Started {
+                command,
+                at: Instant::now(),
+            });
+        } else if name == RESPONSE_SPAN {
+            let Some(span) = ctx.span(id) else {
+                return;
+            };
+            // Debug of the InvokeResponse: "Ok(...)" or "Err(...)"
+            let failed = field(attrs, "response").is_some_and(|r| r.starts_with("Err"));
+            for ancestor in span.scope().skip(1) {
+                if let Some(started) = ancestor.extensions_mut().remove::<Started>() {
+                    record(started.command, started.at.elapsed(), failed);
+                    return;
+                }
+            }
+        }
+    }
+}
+
+// Only the spans above are looked at, so the rest of Tauri's tracing stays off
+pub fn layer<S>() -> impl Layer<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    let spans: FilterFn<fn(&Metadata<'_>) -> bool> = filter_fn(|meta| {
+        meta.is_span() && [HANDLE_SPAN, RESPOND_SPAN, RESPONSE_SPAN].contains(&meta.name())
+    });
+    CommandLayer.with_filter(spans)
+}
+
+#[tauri::command]
+pub async fn get_command_metrics() -> Result<Vec<CommandMetrics>, String> {
+    Ok(snapshot())
+}
@@ -0,0 +1,272 @@
+// Uncaught errors and rejections from the webview, so they end up in the log
+// files next to the shell's own events instead of a console nobody opens.
+// The same error thrown in a render loop would drown everything else out, so
+// repeats are grouped by fingerprint (message plus where it was thrown) and
+// logged again at most once per DEDUP_WINDOW with the number folded in, and
+// no more than RATE_LIMIT errors a minute are logged overall. Everything is
+// still counted, for the diagnostics page (`get_frontend_error_stats`).
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, State, Window};
+
+use super::{truncate, WEBVIEW};
+use crate::state::AppState;
+use crate::util::now_millis;
+
+const DEDUP_WINDOW: Duration = Duration::from_secs(60);
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+const RATE_LIMIT: usize = 20;
+// Oldest groups are forgotten past this
+const MAX_GROUPS: usize = 200;
+const STATS_GROUPS: usize = 50;
+
+#[derive(Deserialize)]
+pub struct FrontendError {
+    pub message: String,
+    // "error", "unhandledrejection", "console", ...
+    pub kind: Option<String>,
+    pub stack: Option<String>,
+    // Script the error came from, with line and column
+    pub source: Option<String>,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    // The app's route when it happened, e.g. "/projects/42"
+    pub route: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct ErrorGroup {
+    pub message: String,
+    pub kind: Option<String>,
+    pub location: Option<String>,
+    pub window: String,
+    pub count: u64,
+    pub first_seen: u64,
+    pub last_seen: u64,
+}
+
+struct Group {
+    info: ErrorGroup,
+    last_logged: Option<Instant>,
+    // Seen since it was last logged
+    unlogged: u64,
+}
+
+#[derive(Serialize)]
+pub struct FrontendErrorStats {
+    pub session: String,
+    // Everything reported this session
+    pub total: u64,
+    // Written to the log; the rest were repeats or over the rate limit
+    pub logged: u64,
+    pub rate_limited: u64,
+    pub distinct: usize,
+    // Most recent first
+    pub groups: Vec<ErrorGroup>,
+}
+
+struct Inner {
+    groups: HashMap<u64, Group>,
+    // When the last few errors were logged, for the rate limit
+    recent: VecDeque<Instant>,
+    total: u64,
+    logged: u64,
+    rate_limited: u64,
+    // Whether the rate limit warning for the current burst is out
+    limit_logged: bool,
+}
+
+pub struct FrontendErrors {
+    // Ties this run's webview errors together in the log
+    session: String,
+    started: Instant,
+    inner: Mutex<Inner>,
+}
+
+impl Default for FrontendErrors {
+    fn default() -> Self {
+        Self {
+            session: format!("{:016x}", rand::random::<u64>()),
+            started: Instant::now(),
+            inner: Mutex::new(Inner {
+                groups: HashMap::new(),
+                recent: VecDeque::new(),
+                total: 0,
+                logged: 0,
+                rate_limited: 0,
+                limit_logged: false,
+            }),
+        }
+    }
+}
+
+// What to log for one report, decided under the lock and logged outside it
+enum Verdict {
+    Log { repeats: u64 },
+    Repeat,
+    RateLimited { first: bool },
+}
+
+fn location(error: &FrontendError) -> Option<String> {
+    let source = error.source.as_deref()?;
+    Some(match (error.line, error.column) {
+        (Some(line), Some(column)) => format!("{}:{}:{}", source, line, column),
+        (Some(line), None) => format!("{}:{}", source, line),
+        _ => source.to_string(),
+    })
+}
+
+// Without a source, the first stack frame says where it came from
+fn fingerprint(error: &FrontendError, location: Option<&str>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    error.message.hash(&mut hasher);
+    match location {
+        Some(location) => location.hash(&mut hasher),
+        None => error
+            .stack
+            .as_deref()
+            .and_then(|stack| stack.lines().map(str::trim).find(|l| l.starts_with("at ")))
+            .hash(&mut hasher),
+    }
+    hasher.finish()
+}
+
+impl FrontendErrors {
+    fn record(&self, error: &FrontendError, location: Option<String>, window: &str) -> Verdict {
+        let now = Instant::now();
+        let key = fingerprint(error, location.as_deref());
+        let mut inner = self.inner.lock().unwrap();
+        inner.total += 1;
+
+        let seen = now_millis();
+        if !inner.groups.contains_key(&key) && inner.groups.len() >= MAX_GROUPS {
+            let oldest = inner
+                .groups
+                .iter()
+                .min_by_key(|(_, group)| group.info.last_seen)
+                .map(|(key, _)| *key);
+            if let Some(oldest) = oldest {
+                inner.groups.remove(&oldest);
+            }
+        }
+        let group = inner.groups.entry(key).or_insert_with(|| Group {
+            info: ErrorGroup {
+                message: error.message.clone(),
+                kind: error.kind.clone(),
+                location,
+                window: window.to_string(),
+                count: 0,
+                first_seen: seen,
+                last_seen: seen,
+            },
+            last_logged: None,
+            unlogged: 0,
+        });
+        group.info.count += 1;
+        group.info.last_seen = seen;
+        group.unlogged += 1;
+        let logged_lately = group
+            .last_logged
+            .is_some_and(|at| now.duration_since(at) < DEDUP_WINDOW);
+        if logged_lately {
+            return Verdict::Repeat;
+        }
+
+        while inner
+            .recent
+            .front()
+            .is_some_and(|at| now.duration_since(*at) >= RATE_WINDOW)
+        {
+            inner.recent.pop_front();
+        }
+        if inner.recent.len() >= RATE_LIMIT {
+            inner.rate_limited += 1;
+            let first = !inner.limit_logged;
+            inner.limit_logged = true;
+            return Verdict::RateLimited { first };
+        }
+        inner.recent.push_back(now);
+        inner.limit_logged = false;
+        inner.logged += 1;
+        let group = inner.groups.get_mut(&key).unwrap();
+        let repeats = group.unlogged - 1;
+        group.unlogged = 0;
+        group.last_logged = Some(now);
+        Verdict::Log { repeats }
+    }
+
+    pub fn stats(&self) -> FrontendErrorStats {
+        let inner = self.inner.lock().unwrap();
+        let mut groups: Vec<ErrorGroup> = inner.groups.values().map(|g| g.info.clone()).collect();
+        groups.sort_by_key(|group| std::cmp::Reverse(group.last_seen));
+        groups.truncate(STATS_GROUPS);
+        FrontendErrorStats {
+            session: self.session.clone(),
+            total: inner.total,
+            logged: inner.logged,
+            rate_limited: inner.rate_limited,
+            distinct: inner.groups.len(),
+            groups,
+        }
+    }
+}
+
+// For `window.onerror` and `unhandledrejection` handlers; never fails on a
+// well-formed payload, so the handler can't throw in turn
+#[tauri::command]
+pub async fn report_frontend_error(
+    app: AppHandle,
+    window: Window,
+    state: State<'_, AppState>,
+    mut payload: FrontendError,
+) -> Result<(), String> {
+    payload.message = truncate(payload.message);
+    let errors = &state.frontend_errors;
+    let location = location(&payload);
+    match errors.record(&payload, location.clone(), window.label()) {
+        Verdict::Repeat => {}
+        Verdict::RateLimited { first } => {
+            if first {
+                tracing::warn!(
+                    target: WEBVIEW,
+                    session = %errors.session,
+                    "More than {} frontend errors a minute, the rest of this burst is only counted",
+                    RATE_LIMIT
+                );
+            }
+        }
+        Verdict::Log { repeats } => {
+            let url = window.url().to_string();
+            let stack = payload.stack.map(truncate);
+            tracing::error!(
+                target: WEBVIEW,
+                session = %errors.session,
+                uptime_secs = errors.started.elapsed().as_secs(),
+                app_version = %app.package_info().version,
+                window = window.label(),
+                url = %url,
+                route = payload.route.as_deref(),
+                kind = payload.kind.as_deref(),
+                location = location.as_deref(),
+                stack = stack.as_deref(),
+                repeats,
+                "{}",
+                payload.message
+            );
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_frontend_error_stats(
+    state: State<'_, AppState>,
+) -> Result<FrontendErrorStats, String> {
+    Ok(state.frontend_errors.stats())
+}
@@ -0,0 +1,159 @@
+// The shell's own log: `tracing` events from every module, written to daily
+// files in the app log dir (the last KEEP_FILES days are kept) and to stderr.
+// The files hold one JSON object per line, so `query_logs` can filter them.
+// Levels come from `logging.level`, with per-module overrides in
+// `logging.modules` ("http" or "aetherframe::http", "webview" for the
+// frontend); RUST_LOG, when set, wins over both. Webview logs arrive through
+// `log_from_frontend` and go to the same files, uncaught errors through
+// `report_frontend_error` (see `frontend`). Command timings are kept apart
+// from all this, see `commands`.
+
+pub mod commands;
+pub mod frontend;
+pub mod query;
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::Window;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
+use tracing_subscriber::{fmt, reload, EnvFilter, Registry};
+
+const FILE_PREFIX: &str = "aetherframe";
+const KEEP_FILES: usize = 14;
+const CRATE: &str = env!("CARGO_CRATE_NAME");
+// Frontend events are logged under this target
+const WEBVIEW: &str = "webview";
+// A runaway frontend shouldn't fill the disk one line at a time
+const MAX_MESSAGE_LEN: usize = 8 * 1024;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LoggingSettings {
+    // "error", "warn", "info", "debug" or "trace"
+    pub level: String,
+    // Module -> level
+    pub modules: BTreeMap<String, String>,
+}
+
+impl Default for LoggingSettings {
+    fn default() -> Self {
+        Self {
+            level: "info".to_string(),
+            modules: BTreeMap::new(),
+        }
+    }
+}
+
+static FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+pub fn log_dir(config: &tauri::Config) -> Option<PathBuf> {
+    tauri::api::path::app_log_dir(config)
+}
+
+// "http" is short for "aetherframe::http"
+fn target(module: &str) -> String {
+    if module == WEBVIEW || module.starts_with(CRATE) {
+        module.to_string()
+    } else {
+        format!("{}::{}", CRATE, module)
+    }
+}
+
+fn filter(settings: &LoggingSettings) -> Result<EnvFilter, String> {
+    if let Ok(filter) = EnvFilter::try_from_default_env() {
+        return Ok(filter);
+    }
+    let mut directives = vec![settings.level.clone()];
+    for (module, level) in &settings.modules {
+        directives.push(format!("{}={}", target(module), level));
+    }
+    EnvFilter::builder()
+        .parse(directives.join(","))
+        .map_err(|e| format!("Invalid log level: {}", e))
+}
+
+// Called first thing, before settings are loaded; `reload` applies them
+pub fn init(config: &tauri::Config) {
+    let filter = filter(&LoggingSettings::default()).unwrap_or_else(|_| EnvFilter::new("info"));
+    let (filter, handle) = reload::Layer::new(filter);
+    let appender = log_dir(config).and_then(|dir| {
+        RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix(FILE_PREFIX)
+            .filename_suffix("log")
+            .max_log_files(KEEP_FILES)
+            .build(&dir)
+            .map_err(|e| eprintln!("Not logging to {}: {}", dir.display(), e))
+            .ok()
+    });
+    let file = appender.map(|appender| fmt::layer().json().with_writer(appender));
+    let stderr = fmt::layer().with_writer(std::io::stderr);
+    // The level filter is only for the log output; command metrics see their own spans
+    let output = Layer::and_then(file, stderr).with_filter(filter);
+    if tracing_subscriber::registry()
+        .with(output)
+        .with(commands::layer())
+        .try_init()
+        .is_ok()
+    {
+        let _ = FILTER.set(handle);
+    }
+}
+
+pub fn reload(settings: &LoggingSettings) -> Result<(), String> {
+    let filter = filter(settings)?;
+    match FILTER.get() {
+        Some(handle) => handle.reload(filter).map_err(|e| e.to_string()),
+        None => Ok(()),
+    }
+}
+
+fn truncate(mut msg: String) -> String {
+    if msg.len() > MAX_MESSAGE_LEN {
+        let mut end = MAX_MESSAGE_LEN;
+        while !msg.is_char_boundary(end) {
+            end -= 1;
+        }
+        msg.truncate(end);
+        msg.push('…');
+    }
+    msg
+}
+
+// `ctx` is any extra JSON worth keeping with the line (a component, an error's
+// stack, ...)
+#[tauri::command]
+pub async fn log_from_frontend(
+    window: Window,
+    level: String,
+    msg: String,
+    ctx: Option<Value>,
+) -> Result<(), String> {
+    let msg = truncate(msg);
+    let ctx = ctx.map(|ctx| truncate(ctx.to_string()));
+    let label = window.label();
+    macro_rules! log {
+        ($level:ident) => {
+            match &ctx {
+                Some(ctx) => tracing::$level!(target: WEBVIEW, window = label, ctx = %ctx, "{}", msg),
+                None => tracing::$level!(target: WEBVIEW, window = label, "{}", msg),
+            }
+        };
+    }
+    match level.to_ascii_lowercase().as_str() {
+        "trace" => log!(trace),
+        "debug" => log!(debug),
+        "info" | "log" => log!(info),
+        "warn" | "warning" => log!(warn),
+        "error" => log!(error),
+        _ => return Err(format!("Unknown log level: {}", level)),
+    }
+    Ok(())
+}
@@ -0,0 +1,240 @@
+// Filtered, paged reads of the log files for the diagnostics page, newest
+// first. Files are read on a blocking thread, newest file first, and the scan
+// stops as soon as the page is full, so recent pages stay cheap however much
+// history there is. Lines that aren't ours (or were cut short by a crash) are
+// skipped.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::AppHandle;
+
+use super::{log_dir, target, FILE_PREFIX};
+
+const DEFAULT_PAGE_SIZE: usize = 100;
+const MAX_PAGE_SIZE: usize = 500;
+const LEVELS: &[&str] = &["error", "warn", "info", "debug", "trace"];
+
+// Millis since the epoch; either end may be left open
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct TimeRange {
+    pub from: Option<u64>,
+    pub to: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct LogEntry {
+    pub timestamp: u64,
+    pub level: String,
+    pub module: String,
+    pub message: String,
+    // Everything logged with the message besides the text itself
+    pub fields: Map<String, Value>,
+}
+
+#[derive(Serialize)]
+pub struct LogPage {
+    pub entries: Vec<LogEntry>,
+    pub page: usize,
+    pub page_size: usize,
+    pub has_more: bool,
+}
+
+struct Filter {
+    // Index into LEVELS; this level and anything more severe
+    level: usize,
+    module: Option<String>,
+    range: TimeRange,
+    text: Option<String>,
+}
+
+impl Filter {
+    fn matches(&self, entry: &LogEntry) -> bool {
+        let level = LEVELS.iter().position(|l| *l == entry.level);
+        if level.map_or(true, |level| level > self.level) {
+            return false;
+        }
+        if let Some(module) = &self.module {
+            let under = entry
+                .module
+                .strip_prefix(module.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"));
+            if !under {
+                return false;
+            }
+        }
+        if self.range.from.is_some_and(|from| entry.timestamp < from)
+            || self.range.to.is_some_and(|to| entry.timestamp > to)
+        {
+            return false;
+        }
+        match &self.text {
+            Some(text) => {
+                entry.message.to_lowercase().contains(text)
+                    || Value::Object(entry.fields.clone())
+                        .to_string()
+                        .to_lowercase()
+                        .contains(text)
+            }
+            None => true,
+        }
+    }
+}
+
+fn parse_line(line: &str) -> Option<LogEntry> {
+    let Value::Object(mut line) = serde_json::from_str(line).ok()? else {
+        return None;
+    };
+    let timestamp = chrono::DateTime::parse_from_rfc3339(line.get("timestamp")?.as_str()?)
+        .ok()?
+        .timestamp_millis()
+        .max(0) as u64;
+    let level = line.get("level")?.as_str()?.to_ascii_lowercase();
+    let module = line.get("target")?.as_str()?.to_string();
+    let mut fields = match line.remove("fields") {
+        Some(Value::Object(fields)) => fields,
+        _ => Map::new(),
+    };
+    let message = match fields.remove("message") {
+        Some(Value::String(message)) => message,
+        Some(other) => other.to_string(),
+        None => String::new(),
+    };
+    Some(LogEntry {
+        timestamp,
+        level,
+        module,
+        message,
+        fields,
+    })
+}
+
+fn modified_millis(path: &Path) -> u64 {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map_or(u64::MAX, |d| d.as_millis() as u64)
+}
+
+// Newest first: the date in "aetherframe.YYYY-MM-DD.log" sorts as text
+pub fn log_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().starts_with(FILE_PREFIX))
+                .unwrap_or(false)
+        })
+        .collect();
+    files.sort_by(|a, b| b.cmp(a));
+    files
+}
+
+// The last `limit` raw lines written before `before`, oldest first
+pub fn recent_lines(dir: &Path, limit: usize, before: u64) -> Vec<String> {
+    let mut lines = Vec::new();
+    for file in log_files(dir) {
+        let Ok(contents) = fs::read_to_string(&file) else {
+            continue;
+        };
+        for line in contents.lines().rev() {
+            if parse_line(line).is_some_and(|entry| entry.timestamp < before) {
+                lines.push(line.to_string());
+                if lines.len() == limit {
+                    lines.reverse();
+                    return lines;
+                }
+            }
+        }
+    }
+    lines.reverse();
+    lines
+}
+
+fn query(dir: &Path, filter: &Filter, page: usize, page_size: usize) -> LogPage {
+    let skip = page * page_size;
+    let mut matched = 0;
+    let mut entries = Vec::new();
+    let mut has_more = false;
+    'files: for file in log_files(dir) {
+        // Last written before the range starts; so is everything older
+        if filter
+            .range
+            .from
+            .is_some_and(|from| modified_millis(&file) < from)
+        {
+            break;
+        }
+        let Ok(contents) = fs::read_to_string(&file) else {
+            continue;
+        };
+        for entry in contents.lines().rev().filter_map(parse_line) {
+            if !filter.matches(&entry) {
+                continue;
+            }
+            matched += 1;
+            if matched <= skip {
+                continue;
+            }
+            if entries.len() == page_size {
+                has_more = true;
+                break 'files;
+            }
+            entries.push(entry);
+        }
+    }
+    LogPage {
+        entries,
+        page,
+        page_size,
+        has_more,
+    }
+}
+
+// `level` is the least severe level to include ("warn" gets warnings and
+// errors); `module` takes the same names as `logging.modules`; `text` is
+// matched case-insensitively against the message and its fields. `page`
+// counts from 0, newest first
+#[tauri::command]
+pub async fn query_logs(
+    app: AppHandle,
+    level: Option<String>,
+    module: Option<String>,
+    time_range: Option<TimeRange>,
+    text: Option<String>,
+    page: Option<usize>,
+    page_size: Option<usize>,
+) -> Result<LogPage, String> {
+    let level = match level.map(|l| l.to_ascii_lowercase()) {
+        None => LEVELS.len() - 1,
+        Some(l) if l == "warning" => 1,
+        Some(l) => LEVELS
+            .iter()
+            .position(|known| *known == l)
+            .ok_or_else(|| format!("Unknown log level: {}", l))?,
+    };
+    let module = module.filter(|m| !m.is_empty()).map(|m| target(&m));
+    let filter = Filter {
+        level,
+        module,
+        range: time_range.unwrap_or_default(),
+        text: text.filter(|t| !t.is_empty()).map(|t| t.to_lowercase()),
+    };
+    let page = page.unwrap_or(0);
+    let page_size = page_size
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let dir = log_dir(&app.config()).ok_or("No log directory")?;
+    tauri::async_runtime::spawn_blocking(move || query(&dir, &filter, page, page_size))
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -4,96 +4,359 @@
     windows_subsystem = "windows"
 )]
 
-use tauri::{
-    CustomMenuItem, Manager, SystemTray, SystemTrayEvent, SystemTrayMenu, SystemTrayMenuItem,
-};
+#[cfg(target_os = "macos")]
+mod apple_events;
+mod auth;
+mod autostart;
+mod backend;
+mod cli;
+mod clipboard;
+mod crash;
+mod deeplink;
+mod diagnostics;
+mod dialog;
+mod documents;
+mod downloads;
+mod external;
+mod files;
+mod health;
+mod http;
+mod i18n;
+mod idle;
+mod ingest;
+mod instance;
+mod jobs;
+mod logging;
+mod menu;
+mod notifications;
+mod onboarding;
+mod permissions;
+mod power;
+mod profiles;
+mod quick_actions;
+mod recent;
+mod secrets;
+mod settings;
+mod shortcuts;
+mod splash;
+mod state;
+mod storage;
+mod system_info;
+mod theme;
+mod tray;
+mod updater;
+mod util;
+mod window;
+mod workers;
+
+use cli::LaunchArgs;
+use health::HealthState;
+use state::AppState;
+use tauri::{AppHandle, Manager, RunEvent, State};
 
 // Commands that can be called from JavaScript
 #[tauri::command]
-fn check_backend_status() -> Result<String, String> {
-    // Check if backend is running
-    match reqwest::blocking::get("http://localhost:8000/status") {
-        Ok(_) => Ok("Backend is running".to_string()),
-        Err(_) => Err("Backend is not running".to_string()),
+async fn check_backend_status(state: State<'_, AppState>) -> Result<String, String> {
+    state.backend.ensure_available()?;
+    // Answered from the health monitor's last probe; see `get_health_snapshot` for details
+    match state.health.snapshot().state {
+        HealthState::Up | HealthState::Degraded => Ok("Backend is running".to_string()),
+        HealthState::Down | HealthState::Unknown => Err("Backend is not running".to_string()),
     }
 }
 
 #[tauri::command]
-fn open_backend_docs() {
-    let _ = open::that("http://localhost:8000/docs");
+async fn open_backend_docs(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    external::open(&app, &format!("{}/docs", state.backend.url())).await
+}
+
+#[tauri::command]
+async fn get_backend_url(state: State<'_, AppState>) -> Result<String, String> {
+    Ok(state.backend.url())
 }
 
 fn main() {
-    // System tray menu
-    let quit = CustomMenuItem::new("quit".to_string(), "Quit");
-    let hide = CustomMenuItem::new("hide".to_string(), "Hide");
-    let show = CustomMenuItem::new("show".to_string(), "Show");
-    let docs = CustomMenuItem::new("docs".to_string(), "API Docs");
-    let status = CustomMenuItem::new("status".to_string(), "System Status");
+    let raw_args: Vec<String> = std::env::args().skip(1).collect();
+    let args = LaunchArgs::parse(raw_args.clone());
+    let context = tauri::generate_context!();
+    logging::init(context.config());
+    let instance = match instance::claim(&context.config().tauri.bundle.identifier, &raw_args) {
+        Ok(instance::Claim::Primary(listener)) => Some(listener),
+        Ok(instance::Claim::Forwarded) => return,
+        // Better a second instance than none at all
+        Err(e) => {
+            tracing::warn!("{}", e);
+            None
+        }
+    };
+    // Only the instance that stays, or a forwarded launch would see the
+    // primary's marker as an unclean exit
+    crash::install(
+        context.config(),
+        &context.package_info().version.to_string(),
+    );
+    let port = backend::port::pick_free_port().expect("error while allocating backend port");
 
-    let tray_menu = SystemTrayMenu::new()
-        .add_item(show)
-        .add_item(hide)
-        .add_native_item(SystemTrayMenuItem::Separator)
-        .add_item(status)
-        .add_item(docs)
-        .add_native_item(SystemTrayMenuItem::Separator)
-        .add_item(quit);
+    let tray_available = tray::platform::available();
+    let mut builder = tauri::Builder::default()
+        .menu(menu::build(&context.package_info().name))
+        .on_menu_event(menu::on_event);
+    if tray_available {
+        builder = builder
+            .system_tray(tray::system_tray())
+            .on_system_tray_event(tray::on_event);
+    }
+
+    // Everything goes through the permission gate first
+    let handler: fn(tauri::Invoke) = tauri::generate_handler![
+        check_backend_status,
+        open_backend_docs,
+        external::open_external,
+        files::reveal_in_folder,
+        files::open_path,
+        dialog::pick_file,
+        dialog::pick_files,
+        dialog::pick_folder,
+        dialog::save_file_dialog,
+        get_backend_url,
+        logging::log_from_frontend,
+        logging::query::query_logs,
+        logging::frontend::report_frontend_error,
+        logging::frontend::get_frontend_error_stats,
+        logging::commands::get_command_metrics,
+        crash::list_crash_reports,
+        crash::submit_crash_reports,
+        crash::delete_crash_reports,
+        diagnostics::export_diagnostics,
+        system_info::get_system_info,
+        workers::get_worker_status,
+        backend::logs::get_backend_logs,
+        settings::get_all_settings,
+        settings::get_setting,
+        settings::set_setting,
+        settings::reset_setting,
+        secrets::store_secret,
+        secrets::get_secret,
+        secrets::delete_secret,
+        onboarding::is_first_run,
+        onboarding::complete_onboarding,
+        settings::bundle::export_settings,
+        settings::bundle::import_settings,
+        backend::relaunch_backend_with_args,
+        backend::restart_backend,
+        backend::compat::check_compatibility,
+        backend::metrics::backend_resources,
+        health::get_health_snapshot,
+        idle::report_activity,
+        idle::get_lock_state,
+        idle::lock_session,
+        idle::unlock_session,
+        state::get_app_state,
+        state::get_config,
+        i18n::get_language,
+        i18n::set_language,
+        jobs::get_running_jobs,
+        recent::get_recent_items,
+        recent::push_recent_item,
+        recent::clear_recent_items,
+        storage::cache_put,
+        storage::cache_get,
+        storage::cache_delete,
+        storage::cache_list,
+        storage::cache_clear,
+        storage::db::record_job,
+        storage::db::list_job_history,
+        storage::db::delete_job_history,
+        storage::db::add_favorite,
+        storage::db::remove_favorite,
+        storage::db::list_favorites,
+        storage::db::save_backend_result,
+        storage::db::get_backend_result,
+        storage::db::clear_backend_results,
+        storage::search::search_local,
+        storage::export::export_data,
+        storage::backup::backup_now,
+        storage::backup::list_backups,
+        storage::backup::restore_backup,
+        storage::file_cache::fetch_cached_file,
+        storage::file_cache::get_cache_stats,
+        storage::file_cache::clear_cache,
+        tray::custom::set_tray_menu,
+        updater::check_for_updates,
+        updater::get_available_update,
+        updater::download_update,
+        updater::install_update,
+        updater::get_release_notes,
+        notifications::notify,
+        notifications::badge::set_badge_count,
+        notifications::history::list_notifications,
+        notifications::history::mark_read,
+        notifications::history::clear_notifications,
+        backend::update::update_backend,
+        window::set_always_on_top,
+        window::focus_main_window,
+        window::list_monitors,
+        window::move_to_monitor,
+        window::set_close_action,
+        window::set_start_minimized,
+        autostart::enable_autostart,
+        autostart::disable_autostart,
+        autostart::is_autostart_enabled,
+        shortcuts::register_global_shortcut,
+        shortcuts::unregister_global_shortcut,
+        shortcuts::list_global_shortcuts,
+        clipboard::clipboard_write_text,
+        clipboard::clipboard_read_text,
+        clipboard::clipboard_write_image,
+        clipboard::clipboard_read_image,
+        theme::get_system_theme,
+        power::inhibit::inhibit_sleep,
+        power::inhibit::release_sleep_inhibit,
+        window::effects::set_window_effect,
+        window::kiosk::enter_kiosk_mode,
+        window::kiosk::exit_kiosk_mode,
+        window::open::open_window,
+        window::widget::toggle_widget,
+        window::console::open_log_console,
+        window::console::console_set_paused,
+        window::console::console_set_scroll_lock,
+        window::console::console_clear,
+        window::widget::widget_start_drag,
+        window::widget::widget_close,
+        window::open::close_window,
+        window::open::list_windows,
+        window::open::emit_to_window,
+        auth::set_auth_token,
+        auth::clear_auth_token,
+        auth::login,
+        auth::logout,
+        auth::get_session,
+        http::cache::clear_http_cache,
+        http::cancel::cancel_request,
+        http::proxy::detect_system_proxy,
+        http::proxy::set_proxy_settings,
+        http::proxy::test_proxy,
+        http::request::backend_request,
+        http::offline::queue_or_send,
+        http::offline::list_offline_queue,
+        http::offline::discard_queued_request,
+        http::tls::import_ca_certificate,
+        http::transfer::download_file,
+        http::transfer::upload_file,
+        downloads::start_download,
+        downloads::pause_download,
+        downloads::resume_download,
+        downloads::cancel_download,
+        downloads::list_downloads,
+        splash::splash_retry,
+        splash::splash_continue,
+        profiles::list_profiles,
+        profiles::switch_profile,
+        profiles::save_profile,
+        profiles::delete_profile
+    ];
 
-    let system_tray = SystemTray::new().with_menu(tray_menu);
+    builder
+        .setup(move |app| {
+            let state = AppState::new(&app.handle(), port)?;
+            let settings = state.settings.get();
+            if let Err(e) = logging::reload(&settings.logging) {
+                tracing::warn!("{}", e);
+            }
+            let mut profile = profiles::active(&settings);
+            // Starting hidden only makes sense if the tray can bring the window back
+            let first_run = state.onboarding.is_pending();
+            // A link means someone wants to see something now
+            let minimized = tray_available
+                && !first_run
+                && args.link.is_none()
+                && args.files.is_empty()
+                && (args.minimized || settings.window.start_minimized);
+            if let Some(main) = app.get_window(window::MAIN) {
+                window::geometry::restore(&main, &state.windows);
+            }
+            app.manage(state);
+            window::apply_settings(&app.handle());
+            theme::start(&app.handle());
+            tray::rebuild(&app.handle());
+            menu::refresh(&app.handle());
+            if let Some(splash) = app.get_window("splash") {
+                menu::detach(&splash);
+            }
+            tray::icon::refresh(&app.handle());
+            shortcuts::reload(&app.handle());
+            if let Some(listener) = instance {
+                instance::listen(app.handle(), listener);
+            }
+            #[cfg(target_os = "macos")]
+            apple_events::listen(&app.handle());
+            deeplink::register(&app.handle());
+            documents::register(&app.handle());
+            quick_actions::start(&app.handle());
+            downloads::start(&app.handle());
+            if let Some(link) = &args.link {
+                deeplink::open(&app.handle(), link);
+            }
+            for file in &args.files {
+                documents::open(&app.handle(), file);
+            }
 
-    tauri::Builder::default()
-        .system_tray(system_tray)
-        .on_system_tray_event(|app, event| match event {
-            SystemTrayEvent::LeftClick {
-                position: _,
-                size: _,
-                ..
-            } => {
-                let window = app.get_window("main").unwrap();
-                window.show().unwrap();
-                window.set_focus().unwrap();
+            // --attach wins over whatever the active profile says
+            if args.attach.is_some() {
+                profile.url = args.attach.clone();
             }
-            SystemTrayEvent::MenuItemClick { id, .. } => {
-                match id.as_str() {
-                    "quit" => {
-                        std::process::exit(0);
-                    }
-                    "hide" => {
-                        let window = app.get_window("main").unwrap();
-                        window.hide().unwrap();
-                    }
-                    "show" => {
-                        let window = app.get_window("main").unwrap();
-                        window.show().unwrap();
-                        window.set_focus().unwrap();
-                    }
-                    "docs" => {
-                        let _ = open::that("http://localhost:8000/docs");
-                    }
-                    "status" => {
-                        let window = app.get_window("main").unwrap();
-                        window.show().unwrap();
-                        window.set_focus().unwrap();
-                        // Navigate to status page if available
-                    }
-                    _ => {}
+            // Either start the bundled backend, or attach to one someone else owns
+            // (e.g. a dev container) and leave its lifecycle alone. On first run
+            // the setup wizard decides which, see `onboarding`.
+            if !first_run {
+                profiles::connect(&app.handle(), &profile);
+                backend::supervisor::start(app.handle());
+            }
+            backend::metrics::start(app.handle());
+            health::start(app.handle());
+            jobs::start(app.handle());
+            idle::start(app.handle());
+            power::start(&app.handle());
+            updater::start(app.handle());
+            storage::backup::start(app.handle());
+            crash::start(&app.handle());
+            if first_run {
+                // Nothing to wait for yet; go straight to the wizard
+                if let Some(splash) = app.get_window("splash") {
+                    let _ = splash.close();
+                }
+                window::activate(&app.handle());
+            } else if minimized {
+                // The main window stays hidden until it's opened from the tray
+                if let Some(splash) = app.get_window("splash") {
+                    let _ = splash.close();
                 }
+            } else {
+                splash::start(app.handle());
             }
-            _ => {}
+            tray::status::start(app.handle());
+            Ok(())
         })
-        .on_window_event(|event| match event.event() {
-            tauri::WindowEvent::CloseRequested { api, .. } => {
-                // Don't close, just hide to tray
-                event.window().hide().unwrap();
-                api.prevent_close();
+        .on_window_event(move |event| window::on_event(event, tray_available))
+        .on_page_load(|window, _| {
+            storage::warm_up(&window.app_handle());
+            onboarding::on_page_load(&window);
+            deeplink::on_page_load(&window);
+            documents::on_page_load(&window);
+        })
+        .invoke_handler(move |invoke| {
+            if let Some(invoke) = permissions::check(invoke) {
+                handler(invoke)
             }
-            _ => {}
         })
-        .invoke_handler(tauri::generate_handler![
-            check_backend_status,
-            open_backend_docs
-        ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(context)
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let RunEvent::Exit = event {
+                let state = app.state::<AppState>();
+                state.windows.save();
+                tauri::async_runtime::block_on(state.backend.shutdown());
+                crash::clean_exit();
+            }
+        });
 }
@@ -0,0 +1,203 @@
+// The application menu: the menu bar on macOS, and the main window's menu
+// bar elsewhere (secondary windows hide theirs). Clipboard, undo and window
+// items are native, so they act on the webview as-is where the platform
+// supports them (Linux has none; the webview's own shortcuts still work).
+// Quit goes through the same shutdown as the tray, and Close Window through
+// the window's close behaviour. Every custom item also reaches the window it
+// was chosen in as `menu-action` with its id; zoom is left to the UI entirely.
+//
+// The menu is built before settings are loaded, so submenu titles follow the
+// OS language; item titles switch to the chosen one at startup and whenever
+// it changes.
+
+#[cfg(not(windows))]
+use tauri::AboutMetadata;
+use tauri::{AppHandle, CustomMenuItem, Manager, Menu, MenuItem, Submenu, Window, WindowMenuEvent};
+
+use crate::external;
+use crate::i18n;
+use crate::settings::Settings;
+use crate::state::AppState;
+use crate::updater;
+use crate::window;
+
+// Id, title and accelerator of every custom item
+const ITEMS: &[(&str, &str, Option<&str>)] = &[
+    ("new-job", "menu.new_job", Some("CmdOrCtrl+N")),
+    ("settings", "menu.settings", Some("CmdOrCtrl+,")),
+    ("quit", "tray.quit", Some("CmdOrCtrl+Q")),
+    ("zoom-in", "menu.zoom_in", Some("CmdOrCtrl+=")),
+    ("zoom-out", "menu.zoom_out", Some("CmdOrCtrl+-")),
+    ("zoom-reset", "menu.zoom_reset", Some("CmdOrCtrl+0")),
+    ("fullscreen", "menu.fullscreen", Some("F11")),
+    ("always-on-top", "tray.always_on_top", None),
+    ("widget", "tray.widget", None),
+    ("console", "tray.console", Some("CmdOrCtrl+Shift+L")),
+    ("status", "tray.status", None),
+    ("docs", "tray.docs", None),
+    ("check-updates", "menu.check_updates", None),
+];
+
+fn item(language: &str, id: &str) -> CustomMenuItem {
+    let (_, key, accelerator) = ITEMS.iter().find(|(item, ..)| *item == id).unwrap();
+    let item = CustomMenuItem::new(id, i18n::lookup(language, key));
+    match accelerator {
+        Some(accelerator) => item.accelerator(*accelerator),
+        None => item,
+    }
+}
+
+fn submenu(language: &str, key: &'static str, menu: Menu) -> Submenu {
+    Submenu::new(i18n::lookup(language, key), menu)
+}
+
+// Linux has no native editing items to offer
+#[cfg(not(target_os = "linux"))]
+fn edit_menu() -> Menu {
+    Menu::new()
+        .add_native_item(MenuItem::Undo)
+        .add_native_item(MenuItem::Redo)
+        .add_native_item(MenuItem::Separator)
+        .add_native_item(MenuItem::Cut)
+        .add_native_item(MenuItem::Copy)
+        .add_native_item(MenuItem::Paste)
+        .add_native_item(MenuItem::SelectAll)
+}
+
+// The app name is only shown where there's a native About item
+#[cfg_attr(windows, allow(unused_variables))]
+pub fn build(app_name: &str) -> Menu {
+    let settings = Settings::default();
+    let language = i18n::language(&settings);
+    let item = |id| item(language, id);
+
+    let file = Menu::new().add_item(item("new-job"));
+    // macOS keeps Settings and Quit in the app menu
+    #[cfg(not(target_os = "macos"))]
+    let file = file
+        .add_native_item(MenuItem::Separator)
+        .add_item(item("settings"))
+        .add_native_item(MenuItem::Separator)
+        .add_native_item(MenuItem::CloseWindow)
+        .add_item(item("quit"));
+    #[cfg(target_os = "macos")]
+    let file = file
+        .add_native_item(MenuItem::Separator)
+        .add_native_item(MenuItem::CloseWindow);
+
+    let view = Menu::new()
+        .add_item(item("zoom-in"))
+        .add_item(item("zoom-out"))
+        .add_item(item("zoom-reset"))
+        .add_native_item(MenuItem::Separator);
+    // AppKit's own item also handles the green button and Mission Control
+    #[cfg(target_os = "macos")]
+    let view = view.add_native_item(MenuItem::EnterFullScreen);
+    #[cfg(not(target_os = "macos"))]
+    let view = view.add_item(item("fullscreen"));
+
+    let mut on_top = item("always-on-top");
+    if settings.window.always_on_top {
+        on_top = on_top.selected();
+    }
+    let windows = Menu::new()
+        .add_native_item(MenuItem::Minimize)
+        .add_native_item(MenuItem::Zoom)
+        .add_native_item(MenuItem::Separator)
+        .add_item(on_top)
+        .add_item(item("widget"))
+        .add_item(item("console"));
+
+    let help = Menu::new()
+        .add_item(item("status"))
+        .add_item(item("docs"))
+        .add_item(item("check-updates"));
+    #[cfg(target_os = "linux")]
+    let help = help
+        .add_native_item(MenuItem::Separator)
+        .add_native_item(MenuItem::About(app_name.to_string(), AboutMetadata::new()));
+
+    let menu = Menu::new();
+    #[cfg(target_os = "macos")]
+    let menu = menu.add_submenu(Submenu::new(
+        app_name,
+        Menu::new()
+            .add_native_item(MenuItem::About(app_name.to_string(), AboutMetadata::new()))
+            .add_native_item(MenuItem::Separator)
+            .add_item(item("settings"))
+            .add_native_item(MenuItem::Separator)
+            .add_native_item(MenuItem::Services)
+            .add_native_item(MenuItem::Separator)
+            .add_native_item(MenuItem::Hide)
+            .add_native_item(MenuItem::HideOthers)
+            .add_native_item(MenuItem::ShowAll)
+            .add_native_item(MenuItem::Separator)
+            .add_item(item("quit")),
+    ));
+    let menu = menu.add_submenu(submenu(language, "menu.file", file));
+    #[cfg(not(target_os = "linux"))]
+    let menu = menu.add_submenu(submenu(language, "menu.edit", edit_menu()));
+    menu.add_submenu(submenu(language, "menu.view", view))
+        .add_submenu(submenu(language, "menu.window", windows))
+        .add_submenu(submenu(language, "menu.help", help))
+}
+
+// Only the main window carries the menu bar; macOS has just the one anyway
+pub fn detach(window: &Window) {
+    if window.label() != window::MAIN {
+        let _ = window.menu_handle().hide();
+    }
+}
+
+// Titles and the always-on-top check, after settings load or change
+pub fn refresh(app: &AppHandle) {
+    let settings = app.state::<AppState>().settings.get();
+    let language = i18n::language(&settings);
+    for window in app.windows().values() {
+        let handle = window.menu_handle();
+        // Not every item is on every platform's menu
+        for (id, key, _) in ITEMS {
+            if let Some(item) = handle.try_get_item(id) {
+                let _ = item.set_title(i18n::lookup(language, key));
+            }
+        }
+        if let Some(on_top) = handle.try_get_item("always-on-top") {
+            let _ = on_top.set_selected(settings.window.always_on_top);
+        }
+    }
+}
+
+fn log_error(result: Result<(), String>) {
+    if let Err(e) = result {
+        tracing::warn!("{}", e);
+    }
+}
+
+pub fn on_event(event: WindowMenuEvent) {
+    let window = event.window();
+    let app = window.app_handle();
+    let id = event.menu_item_id();
+    match id {
+        "quit" => window::quit(&app),
+        "new-job" => window::navigate(&app, "/jobs/new"),
+        "settings" => window::navigate(&app, "/settings"),
+        "status" => window::navigate(&app, "/status"),
+        "docs" => external::open_docs(&app),
+        "check-updates" => {
+            updater::check_in_background(&app);
+            window::navigate(&app, "/updates");
+        }
+        "always-on-top" => {
+            let on_top = app.state::<AppState>().settings.get().window.always_on_top;
+            log_error(window::pin(&app, !on_top));
+        }
+        "widget" => log_error(window::widget::toggle(&app)),
+        "console" => log_error(window::console::open(&app)),
+        "fullscreen" if !app.state::<AppState>().kiosk.is_active() => {
+            let fullscreen = window.is_fullscreen().unwrap_or(false);
+            let _ = window.set_fullscreen(!fullscreen);
+        }
+        _ => {}
+    }
+    let _ = window.emit("menu-action", id);
+}
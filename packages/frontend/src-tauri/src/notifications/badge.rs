@@ -0,0 +1,238 @@
+// Count badge on the app's dock/taskbar entry: the dock tile label on macOS,
+// an overlay icon on the Windows taskbar button and the Unity launcher count
+// (honoured by most Linux docks). Focusing the main window clears it.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(all(unix, not(target_os = "macos")))]
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager};
+
+use crate::state::AppState;
+
+#[derive(Default)]
+pub struct Badge {
+    count: AtomicU64,
+    // Docks drop the count when its sender leaves the bus, so keep one open
+    #[cfg(all(unix, not(target_os = "macos")))]
+    bus: Mutex<Option<zbus::blocking::Connection>>,
+}
+
+pub fn set(app: &AppHandle, count: u64) {
+    let previous = app
+        .state::<AppState>()
+        .badge
+        .count
+        .swap(count, Ordering::SeqCst);
+    if previous != count {
+        apply(app, count);
+    }
+}
+
+pub fn clear(app: &AppHandle) {
+    set(app, 0);
+}
+
+#[cfg(target_os = "macos")]
+fn apply(app: &AppHandle, count: u64) {
+    use objc::runtime::Object;
+    use objc::{class, msg_send, sel, sel_impl};
+
+    // AppKit only on the main thread; an empty label removes the badge
+    let result = app.run_on_main_thread(move || unsafe {
+        let label = if count == 0 {
+            String::new()
+        } else {
+            count.to_string()
+        };
+        let ns_app: *mut Object = msg_send![class!(NSApplication), sharedApplication];
+        let tile: *mut Object = msg_send![ns_app, dockTile];
+        let text: *mut Object = msg_send![class!(NSString), alloc];
+        // 4 = NSUTF8StringEncoding
+        let text: *mut Object =
+            msg_send![text, initWithBytes: label.as_ptr() length: label.len() encoding: 4usize];
+        let _: () = msg_send![tile, setBadgeLabel: text];
+        let _: () = msg_send![text, release];
+    });
+    if let Err(e) = result {
+        tracing::warn!("Failed to set the dock badge: {}", e);
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn apply(app: &AppHandle, count: u64) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        if let Err(e) = launcher_entry(&app, count) {
+            tracing::warn!("Failed to set the launcher count: {}", e);
+        }
+    });
+}
+
+// com.canonical.Unity.LauncherEntry, keyed by the .desktop file the bundle installs
+#[cfg(all(unix, not(target_os = "macos")))]
+fn launcher_entry(app: &AppHandle, count: u64) -> zbus::Result<()> {
+    use std::collections::HashMap;
+    use zbus::zvariant::Value;
+
+    let state = app.state::<AppState>();
+    let mut bus = state.badge.bus.lock().unwrap();
+    let connection = match bus.as_ref() {
+        Some(connection) => connection.clone(),
+        None => {
+            let connection = zbus::blocking::Connection::session()?;
+            *bus = Some(connection.clone());
+            connection
+        }
+    };
+    let name = app.package_info().crate_name;
+    let uri = format!("application://{}.desktop", name);
+    let path = format!(
+        "/com/canonical/unity/launcherentry/{}",
+        name.replace(|c: char| !c.is_ascii_alphanumeric(), "_")
+    );
+    let mut properties: HashMap<&str, Value> = HashMap::new();
+    properties.insert("count", Value::I64(count as i64));
+    properties.insert("count-visible", Value::Bool(count > 0));
+    connection.emit_signal(
+        None::<zbus::names::BusName>,
+        path.as_str(),
+        "com.canonical.Unity.LauncherEntry",
+        "Update",
+        &(uri, properties),
+    )
+}
+
+#[cfg(windows)]
+fn apply(app: &AppHandle, count: u64) {
+    let Some(main) = app.get_window(crate::window::MAIN) else {
+        return;
+    };
+    let description = crate::i18n::format(
+        crate::i18n::language(&app.state::<AppState>().settings.get()),
+        "badge.description",
+        &[&count.to_string()],
+    );
+    let window = main.clone();
+    // The taskbar's COM objects live on the UI thread
+    let result = main.run_on_main_thread(move || {
+        let Ok(hwnd) = window.hwnd() else {
+            return;
+        };
+        if let Err(e) = overlay::set(hwnd, count, &description) {
+            tracing::warn!("Failed to set the taskbar badge: {}", e);
+        }
+    });
+    if let Err(e) = result {
+        tracing::warn!("Failed to set the taskbar badge: {}", e);
+    }
+}
+
+#[cfg(windows)]
+mod overlay {
+    use windows::core::{HSTRING, PCWSTR};
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::Graphics::Gdi::{CreateBitmap, DeleteObject, HGDIOBJ};
+    use windows::Win32::System::Com::{CoCreateInstance, CLSCTX_INPROC_SERVER};
+    use windows::Win32::UI::Shell::{ITaskbarList3, TaskbarList};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        CreateIconIndirect, DestroyIcon, HICON, ICONINFO,
+    };
+
+    const SIZE: usize = 16;
+
+    // 3x5 glyphs, one row per entry, high bit on the left
+    const DIGITS: [[u8; 5]; 10] = [
+        [0b111, 0b101, 0b101, 0b101, 0b111],
+        [0b010, 0b110, 0b010, 0b010, 0b111],
+        [0b111, 0b001, 0b111, 0b100, 0b111],
+        [0b111, 0b001, 0b111, 0b001, 0b111],
+        [0b101, 0b101, 0b111, 0b001, 0b001],
+        [0b111, 0b100, 0b111, 0b001, 0b111],
+        [0b111, 0b100, 0b111, 0b101, 0b111],
+        [0b111, 0b001, 0b010, 0b010, 0b010],
+        [0b111, 0b101, 0b111, 0b101, 0b111],
+        [0b111, 0b101, 0b111, 0b001, 0b111],
+    ];
+    const PLUS: [u8; 5] = [0b000, 0b010, 0b111, 0b010, 0b000];
+
+    // A red dot with the count in white, "9+" past single digits; BGRA, top-down
+    fn pixels(count: u64) -> Vec<u8> {
+        let mut bgra = vec![0u8; SIZE * SIZE * 4];
+        let centre = (SIZE as f32 - 1.0) / 2.0;
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                let (dx, dy) = (x as f32 - centre, y as f32 - centre);
+                if dx * dx + dy * dy <= centre * centre + 1.0 {
+                    bgra[(y * SIZE + x) * 4..][..4].copy_from_slice(&[0x2b, 0x2b, 0xd9, 0xff]);
+                }
+            }
+        }
+        let glyphs: Vec<[u8; 5]> = if count > 9 {
+            vec![DIGITS[9], PLUS]
+        } else {
+            vec![DIGITS[count as usize]]
+        };
+        // Each glyph pixel is 2x2, with a 2px gap between glyphs
+        let width = glyphs.len() * 6 + (glyphs.len() - 1) * 2;
+        let (left, top) = ((SIZE - width) / 2, (SIZE - 10) / 2);
+        for (i, glyph) in glyphs.iter().enumerate() {
+            for (row, bits) in glyph.iter().enumerate() {
+                for col in 0..3 {
+                    if bits & (0b100 >> col) == 0 {
+                        continue;
+                    }
+                    for (sx, sy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                        let x = left + i * 8 + col * 2 + sx;
+                        let y = top + row * 2 + sy;
+                        bgra[(y * SIZE + x) * 4..][..4].copy_from_slice(&[0xff; 4]);
+                    }
+                }
+            }
+        }
+        bgra
+    }
+
+    fn icon(count: u64) -> windows::core::Result<HICON> {
+        let bgra = pixels(count);
+        let mask = [0u8; SIZE * SIZE / 8];
+        unsafe {
+            let color = CreateBitmap(SIZE as i32, SIZE as i32, 1, 32, bgra.as_ptr().cast());
+            let mask = CreateBitmap(SIZE as i32, SIZE as i32, 1, 1, mask.as_ptr().cast());
+            let info = ICONINFO {
+                fIcon: true.into(),
+                xHotspot: 0,
+                yHotspot: 0,
+                hbmMask: mask,
+                hbmColor: color,
+            };
+            let icon = CreateIconIndirect(&info);
+            DeleteObject(HGDIOBJ(color.0));
+            DeleteObject(HGDIOBJ(mask.0));
+            icon
+        }
+    }
+
+    pub fn set(hwnd: HWND, count: u64, description: &str) -> windows::core::Result<()> {
+        unsafe {
+            let taskbar: ITaskbarList3 =
+                CoCreateInstance(&TaskbarList, None, CLSCTX_INPROC_SERVER)?;
+            if count == 0 {
+                return taskbar.SetOverlayIcon(hwnd, HICON::default(), PCWSTR::null());
+            }
+            let icon = icon(count)?;
+            let description = HSTRING::from(description);
+            let result = taskbar.SetOverlayIcon(hwnd, icon, &description);
+            // The taskbar keeps its own copy
+            DestroyIcon(icon);
+            result
+        }
+    }
+}
+
+// Shown for pending results or failed jobs; 0 clears it
+#[tauri::command]
+pub async fn set_badge_count(app: AppHandle, n: u64) -> Result<(), String> {
+    set(&app, n);
+    Ok(())
+}
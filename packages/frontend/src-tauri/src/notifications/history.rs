@@ -0,0 +1,165 @@
+// Every notification shown is also kept in the local store, so someone who
+// was away can catch up on what happened. Clicking one marks it read.
+// Changes are announced as `notifications-changed` with the unread count.
+
+use std::cmp::Reverse;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+
+use super::native::{Action, Native};
+use crate::state::AppState;
+use crate::storage::blocking;
+use crate::util::now_millis;
+
+const NAMESPACE: &str = "notifications";
+// Oldest entries beyond this are dropped
+const MAX_ENTRIES: usize = 500;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Entry {
+    pub id: String,
+    // Which setting it falls under, or "app" for `notify`
+    pub kind: String,
+    pub title: String,
+    pub body: String,
+    pub actions: Vec<Action>,
+    pub data: Option<Value>,
+    pub created_at: u64,
+    pub read: bool,
+    // Held back by quiet hours, so the user never saw it pop up
+    #[serde(default)]
+    pub suppressed: bool,
+}
+
+#[derive(Clone, Serialize)]
+struct Changed {
+    unread: usize,
+}
+
+// Newest first
+pub fn entries(state: &AppState) -> Result<Vec<Entry>, String> {
+    let mut entries: Vec<Entry> = state
+        .store
+        .list(NAMESPACE)?
+        .into_iter()
+        .filter_map(|entry| serde_json::from_value(entry.value).ok())
+        .collect();
+    entries.sort_by_key(|entry| Reverse(entry.created_at));
+    Ok(entries)
+}
+
+fn save(state: &AppState, entry: &Entry) -> Result<(), String> {
+    let value = serde_json::to_value(entry).map_err(|e| e.to_string())?;
+    state.store.put(NAMESPACE, &entry.id, value, None)
+}
+
+fn changed(app: &AppHandle, state: &AppState) {
+    if let Ok(entries) = entries(state) {
+        let unread = entries.iter().filter(|e| !e.read).count();
+        let _ = app.emit_all("notifications-changed", Changed { unread });
+    }
+}
+
+pub fn record(app: &AppHandle, kind: &str, notification: &Native, suppressed: bool) {
+    let entry = Entry {
+        id: notification.id.clone(),
+        kind: kind.to_string(),
+        title: notification.title.clone(),
+        body: notification.body.clone(),
+        actions: notification.actions.clone(),
+        data: notification.data.clone(),
+        created_at: now_millis(),
+        read: false,
+        suppressed,
+    };
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let handle = app.clone();
+        let result = blocking(app, move |state| {
+            save(state, &entry)?;
+            for old in entries(state)?.iter().skip(MAX_ENTRIES) {
+                state.store.delete(NAMESPACE, &old.id)?;
+            }
+            changed(&handle, state);
+            Ok(())
+        })
+        .await;
+        if let Err(e) = result {
+            tracing::warn!("Failed to record notification: {}", e);
+        }
+    });
+}
+
+// None marks everything read; returns how many entries changed
+fn mark(app: &AppHandle, state: &AppState, ids: Option<&[String]>) -> Result<usize, String> {
+    let mut marked = 0;
+    for mut entry in entries(state)? {
+        let wanted = ids.map_or(true, |ids| ids.contains(&entry.id));
+        if wanted && !entry.read {
+            entry.read = true;
+            save(state, &entry)?;
+            marked += 1;
+        }
+    }
+    if marked > 0 {
+        changed(app, state);
+    }
+    Ok(marked)
+}
+
+pub async fn find(app: &AppHandle, id: &str) -> Option<Entry> {
+    let id = id.to_string();
+    let found = blocking(app.clone(), move |state| {
+        Ok(entries(state)?.into_iter().find(|entry| entry.id == id))
+    })
+    .await;
+    found.ok().flatten()
+}
+
+// A click on the notification (or one of its actions) counts as reading it
+pub fn clicked(app: &AppHandle, id: &str) {
+    let (app, ids) = (app.clone(), vec![id.to_string()]);
+    tauri::async_runtime::spawn(async move {
+        let handle = app.clone();
+        let result = blocking(app, move |state| mark(&handle, state, Some(&ids))).await;
+        if let Err(e) = result {
+            tracing::warn!("{}", e);
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn list_notifications(
+    app: AppHandle,
+    unread_only: Option<bool>,
+) -> Result<Vec<Entry>, String> {
+    let unread_only = unread_only.unwrap_or(false);
+    blocking(app, move |state| {
+        let entries = entries(state)?;
+        Ok(entries
+            .into_iter()
+            .filter(|entry| !unread_only || !entry.read)
+            .collect())
+    })
+    .await
+}
+
+// Without ids, marks everything read
+#[tauri::command]
+pub async fn mark_read(app: AppHandle, ids: Option<Vec<String>>) -> Result<usize, String> {
+    let handle = app.clone();
+    blocking(app, move |state| mark(&handle, state, ids.as_deref())).await
+}
+
+#[tauri::command]
+pub async fn clear_notifications(app: AppHandle) -> Result<usize, String> {
+    let handle = app.clone();
+    blocking(app, move |state| {
+        let cleared = state.store.clear(Some(NAMESPACE))?;
+        changed(&handle, state);
+        Ok(cleared)
+    })
+    .await
+}
@@ -0,0 +1,195 @@
+// Native desktop notifications for backend/job state changes, each kind
+// switchable in settings, plus `notify` for the frontend. Everything shown
+// also goes to the history (see `history`).
+
+pub mod badge;
+pub mod history;
+pub mod native;
+pub mod quiet;
+#[cfg(windows)]
+mod toast;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager};
+
+use crate::health::HealthState;
+use crate::i18n;
+use crate::jobs::JobProgress;
+use crate::state::AppState;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationSettings {
+    pub backend_down: bool,
+    pub backend_recovered: bool,
+    pub job_finished: bool,
+    pub update_ready: bool,
+    pub quiet_hours: QuietHours,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            backend_down: true,
+            backend_recovered: true,
+            job_finished: true,
+            update_ready: true,
+            quiet_hours: QuietHours::default(),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Kind {
+    BackendDown,
+    BackendRecovered,
+    JobFinished,
+    UpdateReady,
+}
+
+impl Kind {
+    // As recorded in the history
+    fn name(self) -> &'static str {
+        match self {
+            Kind::BackendDown => "backend_down",
+            Kind::BackendRecovered => "backend_recovered",
+            Kind::JobFinished => "job_finished",
+            Kind::UpdateReady => "update_ready",
+        }
+    }
+}
+
+impl NotificationSettings {
+    fn enabled(&self, kind: Kind) -> bool {
+        match kind {
+            Kind::BackendDown => self.backend_down,
+            Kind::BackendRecovered => self.backend_recovered,
+            Kind::JobFinished => self.job_finished,
+            Kind::UpdateReady => self.update_ready,
+        }
+    }
+}
+
+use self::native::{Action, Input, Native};
+use self::quiet::QuietHours;
+
+fn new_id() -> String {
+    format!("{:016x}", rand::random::<u64>())
+}
+
+// Quiet hours only hold back the popup; the history gets everything
+fn deliver(app: &AppHandle, kind: &str, notification: Native) {
+    let suppressed = quiet::active(app);
+    history::record(app, kind, &notification, suppressed);
+    if !suppressed {
+        native::show(app, notification);
+    }
+}
+
+fn send(app: &AppHandle, kind: Kind, title: &str, body: &str) {
+    send_with(app, kind, title, body, Vec::new(), None);
+}
+
+fn send_with(
+    app: &AppHandle,
+    kind: Kind,
+    title: &str,
+    body: &str,
+    actions: Vec<Action>,
+    data: Option<Value>,
+) {
+    let settings = app.state::<AppState>().settings.get();
+    if !settings.notifications.enabled(kind) {
+        return;
+    }
+    let notification = Native {
+        id: new_id(),
+        title: title.to_string(),
+        body: body.to_string(),
+        actions,
+        inputs: Vec::new(),
+        data,
+    };
+    deliver(app, kind.name(), notification);
+}
+
+// Only real transitions; the first probe after launch (from Unknown) stays quiet
+pub fn health_changed(app: &AppHandle, previous: HealthState, current: HealthState) {
+    match (previous, current) {
+        (HealthState::Up | HealthState::Degraded, HealthState::Down) => send(
+            app,
+            Kind::BackendDown,
+            &i18n::text(app, "notify.backend_down"),
+            &i18n::text(app, "notify.backend_down_body"),
+        ),
+        (HealthState::Down, HealthState::Up) => send(
+            app,
+            Kind::BackendRecovered,
+            &i18n::text(app, "notify.backend_recovered"),
+            &i18n::text(app, "notify.backend_recovered_body"),
+        ),
+        _ => {}
+    }
+}
+
+pub fn job_finished(app: &AppHandle, job: &JobProgress) {
+    let title = match job.status.as_str() {
+        "failed" => "notify.job_failed",
+        _ => "notify.job_finished",
+    };
+    let language = i18n::language(&app.state::<AppState>().settings.get());
+    let body = i18n::format(
+        language,
+        "notify.job_body",
+        &[&job.id.to_string(), &job.target, &job.status],
+    );
+    let view = Action {
+        id: "view".to_string(),
+        label: i18n::text(app, "notify.view_result"),
+    };
+    send_with(
+        app,
+        Kind::JobFinished,
+        &i18n::text(app, title),
+        &body,
+        vec![view],
+        Some(json!({ "job": job.id })),
+    );
+}
+
+pub fn update_ready(app: &AppHandle, version: &str) {
+    let language = i18n::language(&app.state::<AppState>().settings.get());
+    let body = i18n::format(language, "notify.update_ready_body", &[version]);
+    send(
+        app,
+        Kind::UpdateReady,
+        &i18n::text(app, "notify.update_ready"),
+        &body,
+    );
+}
+
+// For the frontend, in place of web notifications from a window that may be
+// hidden. Returns the id that `notification-action` events carry. `inputs`
+// are text fields shown on Windows only.
+#[tauri::command]
+pub async fn notify(
+    app: AppHandle,
+    title: String,
+    body: String,
+    actions: Option<Vec<Action>>,
+    inputs: Option<Vec<Input>>,
+    data: Option<Value>,
+) -> Result<String, String> {
+    let notification = Native {
+        id: new_id(),
+        title,
+        body,
+        actions: actions.unwrap_or_default(),
+        inputs: inputs.unwrap_or_default(),
+        data,
+    };
+    let id = notification.id.clone();
+    deliver(&app, "app", notification);
+    Ok(id)
+}
@@ -0,0 +1,215 @@
+// Notifications straight through the platform APIs, for what Tauri's wrapper
+// can't do: action buttons and knowing when one was clicked. Clicks bring the
+// main window forward and come back as `notification-action`; "default" is a
+// click on the notification itself, and a "dismiss" action just closes it.
+// Text inputs are only shown on Windows, where typed values come back in
+// `inputs`.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+
+use super::history;
+use crate::deeplink::DeepLink;
+use crate::window;
+
+pub const DEFAULT_ACTION: &str = "default";
+pub const DISMISS_ACTION: &str = "dismiss";
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Action {
+    pub id: String,
+    pub label: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Input {
+    pub id: String,
+    pub placeholder: Option<String>,
+}
+
+pub struct Native {
+    pub id: String,
+    pub title: String,
+    pub body: String,
+    pub actions: Vec<Action>,
+    pub inputs: Vec<Input>,
+    // Handed back with the click, e.g. which job the notification is about
+    pub data: Option<Value>,
+}
+
+#[derive(Clone, Serialize)]
+struct ActionEvent<'a> {
+    id: &'a str,
+    action: &'a str,
+    data: &'a Option<Value>,
+    inputs: &'a HashMap<String, String>,
+}
+
+pub fn activated(
+    app: &AppHandle,
+    id: &str,
+    action: &str,
+    data: &Option<Value>,
+    inputs: &HashMap<String, String>,
+) {
+    if action != DISMISS_ACTION {
+        window::activate(app);
+    }
+    history::clicked(app, id);
+    let event = ActionEvent {
+        id,
+        action,
+        data,
+        inputs,
+    };
+    let _ = app.emit_all("notification-action", event);
+}
+
+// aetherframe://notification/<id>/<action>, from a toast on Windows. The
+// process may have started just for it, so the data comes from the history.
+// Anyone can open such a link, so it only counts for a notification we showed
+// and an action it offered. Returns whether the link was one of ours.
+pub fn open_link(app: &AppHandle, link: &DeepLink) -> bool {
+    let [kind, id, action] = link.segments.as_slice() else {
+        return false;
+    };
+    if kind != "notification" {
+        return false;
+    }
+    let (app, id, action) = (app.clone(), id.clone(), action.clone());
+    tauri::async_runtime::spawn(async move {
+        let Some(entry) = history::find(&app, &id).await else {
+            tracing::warn!("Ignoring a link for unknown notification {}", id);
+            return;
+        };
+        let offered = action == DEFAULT_ACTION || entry.actions.iter().any(|a| a.id == action);
+        if !offered || action == DISMISS_ACTION {
+            tracing::warn!(
+                "Ignoring action {:?} notification {} never offered",
+                action,
+                id
+            );
+            return;
+        }
+        activated(&app, &id, &action, &entry.data, &HashMap::new());
+    });
+    true
+}
+
+// Showing (and on some platforms waiting for a click) blocks, so it happens
+// off the calling thread; failures are only logged
+#[cfg(all(unix, not(target_os = "macos")))]
+pub fn show(app: &AppHandle, notification: Native) {
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let mut builder = notify_rust::Notification::new();
+        builder
+            .appname(&app.package_info().name)
+            .summary(&notification.title)
+            .body(&notification.body)
+            .auto_icon();
+        if !notification.actions.is_empty() {
+            // Servers only report body clicks when a default action exists
+            builder.action(DEFAULT_ACTION, "");
+        }
+        for action in &notification.actions {
+            builder.action(&action.id, &action.label);
+        }
+        let handle = match builder.show() {
+            Ok(handle) => handle,
+            Err(e) => {
+                tracing::warn!("Failed to show notification: {}", e);
+                return;
+            }
+        };
+        // Without actions there's nothing to wait for
+        if notification.actions.is_empty() {
+            return;
+        }
+        handle.wait_for_action(|action| {
+            if action != "__closed" {
+                let inputs = HashMap::new();
+                activated(&app, &notification.id, action, &notification.data, &inputs);
+            }
+        });
+    });
+}
+
+#[cfg(target_os = "macos")]
+pub fn show(app: &AppHandle, notification: Native) {
+    use mac_notification_sys::{MainButton, NotificationResponse};
+
+    // Unbundled dev builds have no identifier of their own to post under
+    let identifier = if cfg!(feature = "custom-protocol") {
+        app.config().tauri.bundle.identifier.clone()
+    } else {
+        "com.apple.Terminal".to_string()
+    };
+    let _ = mac_notification_sys::set_application(&identifier);
+    let more = crate::i18n::text(app, "notify.actions");
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let labels: Vec<&str> = notification
+            .actions
+            .iter()
+            .map(|action| action.label.as_str())
+            .collect();
+        let mut builder = mac_notification_sys::Notification::new();
+        builder
+            .title(&notification.title)
+            .message(&notification.body);
+        match labels.as_slice() {
+            [] => {}
+            [label] => {
+                builder.main_button(MainButton::SingleAction(label));
+            }
+            _ => {
+                builder.main_button(MainButton::DropdownActions(&more, &labels));
+            }
+        }
+        let action = match builder.send() {
+            Ok(NotificationResponse::Click) => DEFAULT_ACTION,
+            Ok(NotificationResponse::ActionButton(label)) => {
+                match notification.actions.iter().find(|a| a.label == label) {
+                    Some(action) => action.id.as_str(),
+                    None => return,
+                }
+            }
+            Ok(_) => return,
+            Err(e) => {
+                tracing::warn!("Failed to show notification: {}", e);
+                return;
+            }
+        };
+        let inputs = HashMap::new();
+        activated(&app, &notification.id, action, &notification.data, &inputs);
+    });
+}
+
+#[cfg(windows)]
+pub fn show(app: &AppHandle, notification: Native) {
+    use std::path::MAIN_SEPARATOR as SEP;
+
+    // Toasts are attributed to the installed app's AppUserModelID; a binary
+    // run from target/ has none registered, so borrow PowerShell's like Tauri
+    const POWERSHELL_APP_ID: &str =
+        "{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\\WindowsPowerShell\\v1.0\\powershell.exe";
+    let dev = std::env::current_exe()
+        .ok()
+        .and_then(|exe| Some(exe.parent()?.display().to_string()))
+        .is_some_and(|dir| {
+            dir.ends_with(&format!("{SEP}target{SEP}debug"))
+                || dir.ends_with(&format!("{SEP}target{SEP}release"))
+        });
+    let app_id = if dev {
+        POWERSHELL_APP_ID.to_string()
+    } else {
+        app.config().tauri.bundle.identifier.clone()
+    };
+    if let Err(e) = super::toast::show(app, &app_id, notification) {
+        tracing::warn!("Failed to show notification: {}", e);
+    }
+}
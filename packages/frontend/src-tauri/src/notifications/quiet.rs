@@ -0,0 +1,90 @@
+// Do-not-disturb: a daily quiet window and, optionally, whenever something is
+// fullscreen. Suppressed notifications aren't shown but still go to the
+// history, so nothing is lost.
+
+use chrono::{Local, NaiveTime};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::state::AppState;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuietHours {
+    pub enabled: bool,
+    // Local "HH:MM"; a window past midnight (22:00-07:00) is fine
+    pub start: String,
+    pub end: String,
+    // Also hold notifications while one of our windows is fullscreen, or on
+    // Windows while any fullscreen app or presentation is running
+    pub suppress_when_fullscreen: bool,
+}
+
+impl Default for QuietHours {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start: "22:00".to_string(),
+            end: "07:00".to_string(),
+            suppress_when_fullscreen: false,
+        }
+    }
+}
+
+impl QuietHours {
+    fn covers(&self, now: NaiveTime) -> bool {
+        let parse = |value: &str| NaiveTime::parse_from_str(value.trim(), "%H:%M").ok();
+        let (Some(start), Some(end)) = (parse(&self.start), parse(&self.end)) else {
+            return false;
+        };
+        if start <= end {
+            start <= now && now < end
+        } else {
+            now >= start || now < end
+        }
+    }
+}
+
+pub fn active(app: &AppHandle) -> bool {
+    let quiet = app
+        .state::<AppState>()
+        .settings
+        .get()
+        .notifications
+        .quiet_hours;
+    if quiet.enabled && quiet.covers(Local::now().time()) {
+        return true;
+    }
+    quiet.suppress_when_fullscreen && fullscreen(app)
+}
+
+fn fullscreen(app: &AppHandle) -> bool {
+    let ours = app
+        .windows()
+        .values()
+        .any(|window| window.is_fullscreen().unwrap_or(false));
+    ours || os_busy()
+}
+
+// The shell's own answer to "should apps stay quiet right now"
+#[cfg(windows)]
+fn os_busy() -> bool {
+    use windows::Win32::UI::Shell::{
+        SHQueryUserNotificationState, QUNS_BUSY, QUNS_PRESENTATION_MODE,
+        QUNS_RUNNING_D3D_FULL_SCREEN,
+    };
+    match unsafe { SHQueryUserNotificationState() } {
+        Ok(state) => [
+            QUNS_BUSY,
+            QUNS_RUNNING_D3D_FULL_SCREEN,
+            QUNS_PRESENTATION_MODE,
+        ]
+        .contains(&state),
+        Err(_) => false,
+    }
+}
+
+#[cfg(not(windows))]
+fn os_busy() -> bool {
+    false
+}
@@ -0,0 +1,116 @@
+// Windows toasts built from our own XML, so they can have buttons and text
+// inputs and still reach the app after it has exited. The toast body and its
+// buttons use protocol activation: Windows opens an
+// aetherframe://notification/<id>/<action> link, which cold-starts the app or
+// is forwarded to the running one like any other deep link (see
+// `native::open_link`). Protocol activation can't carry what was typed, so a
+// toast with inputs activates in the foreground instead, and its buttons only
+// answer while this process is running.
+
+use std::collections::HashMap;
+
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use tauri::AppHandle;
+use windows::core::{Interface, HSTRING};
+use windows::Data::Xml::Dom::XmlDocument;
+use windows::Foundation::{IPropertyValue, TypedEventHandler};
+use windows::UI::Notifications::{
+    ToastActivatedEventArgs, ToastNotification, ToastNotificationManager,
+};
+
+use super::native::{self, Native, DEFAULT_ACTION, DISMISS_ACTION};
+use crate::deeplink::{self, SCHEME};
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn link(id: &str, action: &str) -> String {
+    format!(
+        "{}://notification/{}/{}",
+        SCHEME,
+        utf8_percent_encode(id, NON_ALPHANUMERIC),
+        utf8_percent_encode(action, NON_ALPHANUMERIC)
+    )
+}
+
+fn xml(notification: &Native, activation: &str) -> String {
+    let mut actions = String::new();
+    for input in &notification.inputs {
+        actions += &format!(
+            r#"<input id="{}" type="text" placeHolderContent="{}"/>"#,
+            escape(&input.id),
+            escape(input.placeholder.as_deref().unwrap_or_default())
+        );
+    }
+    for action in &notification.actions {
+        // The built-in dismiss button closes the toast without starting anything
+        if action.id == DISMISS_ACTION {
+            actions += &format!(
+                r#"<action content="{}" activationType="system" arguments="dismiss"/>"#,
+                escape(&action.label)
+            );
+            continue;
+        }
+        actions += &format!(
+            r#"<action content="{}" activationType="{}" arguments="{}"/>"#,
+            escape(&action.label),
+            activation,
+            escape(&link(&notification.id, &action.id))
+        );
+    }
+    format!(
+        r#"<toast activationType="{}" launch="{}"><visual><binding template="ToastGeneric"><text>{}</text><text>{}</text></binding></visual><actions>{}</actions></toast>"#,
+        activation,
+        escape(&link(&notification.id, DEFAULT_ACTION)),
+        escape(&notification.title),
+        escape(&notification.body),
+        actions
+    )
+}
+
+// What was typed into each input, by input id
+fn user_input(args: &ToastActivatedEventArgs) -> windows::core::Result<HashMap<String, String>> {
+    let mut inputs = HashMap::new();
+    for pair in args.UserInput()? {
+        let value = pair.Value()?.cast::<IPropertyValue>()?.GetString()?;
+        inputs.insert(pair.Key()?.to_string(), value.to_string());
+    }
+    Ok(inputs)
+}
+
+pub fn show(app: &AppHandle, app_id: &str, notification: Native) -> windows::core::Result<()> {
+    let foreground = !notification.inputs.is_empty();
+    let activation = if foreground { "foreground" } else { "protocol" };
+    let document = XmlDocument::new()?;
+    document.LoadXml(&HSTRING::from(xml(&notification, activation)))?;
+    let toast = ToastNotification::CreateToastNotification(&document)?;
+    if foreground {
+        let (app, data) = (app.clone(), notification.data.clone());
+        toast.Activated(&TypedEventHandler::new(
+            move |_toast: &Option<ToastNotification>,
+                  args: &Option<windows::core::IInspectable>| {
+                let Some(args) = args
+                    .as_ref()
+                    .and_then(|args| args.cast::<ToastActivatedEventArgs>().ok())
+                else {
+                    return Ok(());
+                };
+                let arguments = args.Arguments()?.to_string();
+                let Ok(link) = deeplink::parse(&arguments) else {
+                    return Ok(());
+                };
+                if let [_, id, action] = link.segments.as_slice() {
+                    let inputs = user_input(&args).unwrap_or_default();
+                    native::activated(&app, id, action, &data, &inputs);
+                }
+                Ok(())
+            },
+        ))?;
+    }
+    ToastNotificationManager::CreateToastNotifierWithId(&HSTRING::from(app_id))?.Show(&toast)
+}
@@ -0,0 +1,104 @@
+// First-run detection. On a fresh install nothing is started automatically;
+// the frontend gets `onboarding-required` and runs its setup wizard (backend
+// location, autostart, ...) before calling `complete_onboarding`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tauri::{AppHandle, Manager, State, Window};
+
+use crate::backend;
+use crate::profiles;
+use crate::state::AppState;
+use crate::util::now_millis;
+use crate::window;
+
+const MARKER: &str = "onboarded";
+// Left while the wizard runs, so quitting half way through asks again next time
+const PENDING_MARKER: &str = "onboarding-pending";
+// Written by every version that had settings, so its presence means an upgrade
+const SETTINGS_FILE: &str = "settings.json";
+
+pub struct Onboarding {
+    marker: PathBuf,
+    pending_marker: PathBuf,
+    pending: AtomicBool,
+}
+
+impl Onboarding {
+    // Run before the settings store is loaded, while "no settings.json" still
+    // means "never launched"
+    pub fn detect(dir: &Path) -> Self {
+        let marker = dir.join(MARKER);
+        let pending_marker = dir.join(PENDING_MARKER);
+        let pending =
+            !marker.exists() && (pending_marker.exists() || !dir.join(SETTINGS_FILE).exists());
+        let result = if pending {
+            write_marker(&pending_marker)
+        } else if !marker.exists() {
+            // Upgrading from before onboarding existed; don't start a wizard
+            write_marker(&marker)
+        } else {
+            Ok(())
+        };
+        if let Err(e) = result {
+            tracing::warn!("{}", e);
+        }
+        Self {
+            marker,
+            pending_marker,
+            pending: AtomicBool::new(pending),
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.pending.load(Ordering::SeqCst)
+    }
+}
+
+fn write_marker(marker: &Path) -> Result<(), String> {
+    if let Some(dir) = marker.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    fs::write(marker, now_millis().to_string())
+        .map_err(|e| format!("Failed to record onboarding: {}", e))
+}
+
+// Start what setup skipped on first run
+pub fn connect(app: &AppHandle) {
+    let profile = profiles::active(&app.state::<AppState>().settings.get());
+    profiles::connect(app, &profile);
+    backend::supervisor::start(app.clone());
+}
+
+// The webview isn't listening yet during setup, so announce it once the main
+// page has loaded
+pub fn on_page_load(window: &Window) {
+    if window.label() != window::MAIN {
+        return;
+    }
+    if let Some(state) = window.try_state::<AppState>() {
+        if state.onboarding.is_pending() {
+            let _ = window.emit("onboarding-required", ());
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn is_first_run(state: State<'_, AppState>) -> Result<bool, String> {
+    Ok(state.onboarding.is_pending())
+}
+
+// Called by the wizard once the user's choices have been saved through the
+// settings commands; connects to whatever backend they picked
+#[tauri::command]
+pub async fn complete_onboarding(app: AppHandle) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    write_marker(&state.onboarding.marker)?;
+    let _ = fs::remove_file(&state.onboarding.pending_marker);
+    if state.onboarding.pending.swap(false, Ordering::SeqCst) {
+        connect(&app);
+    }
+    Ok(())
+}
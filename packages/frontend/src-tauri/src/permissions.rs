@@ -0,0 +1,151 @@
+// Defense in depth on top of Tauri's allowlist: commands that start processes,
+// touch arbitrary files, handle credentials or repoint the backend only answer
+// the main window, and any command can be switched off by policy
+// (`security.disabled_commands`). While the idle lock is on, only the lock
+// screen's commands answer. Every refusal is logged.
+
+use tauri::{Invoke, Manager};
+
+use crate::backend::logs::{self, Level};
+use crate::idle;
+use crate::state::AppState;
+use crate::window::MAIN;
+
+// Command name and the windows allowed to call it
+const SENSITIVE: &[(&str, &[&str])] = &[
+    // Processes
+    ("relaunch_backend_with_args", &[MAIN]),
+    ("restart_backend", &[MAIN]),
+    ("splash_retry", &[MAIN, "splash"]),
+    ("download_update", &[MAIN]),
+    ("install_update", &[MAIN]),
+    ("update_backend", &[MAIN]),
+    ("enable_autostart", &[MAIN]),
+    ("disable_autostart", &[MAIN]),
+    // Keeps the machine from sleeping until released
+    ("inhibit_sleep", &[MAIN]),
+    ("release_sleep_inhibit", &[MAIN]),
+    // Leave the app: arbitrary (allowed) links, OS notifications
+    ("open_external", &[MAIN]),
+    ("notify", &[MAIN]),
+    // Other windows: opening them, talking to them, taking over the screen
+    ("open_window", &[MAIN]),
+    ("emit_to_window", &[MAIN]),
+    ("enter_kiosk_mode", &[MAIN]),
+    // Can restart the backend from anywhere
+    ("register_global_shortcut", &[MAIN]),
+    ("unregister_global_shortcut", &[MAIN]),
+    // Arbitrary paths on disk
+    ("upload_file", &[MAIN]),
+    ("download_file", &[MAIN]),
+    ("start_download", &[MAIN]),
+    ("pause_download", &[MAIN]),
+    ("resume_download", &[MAIN]),
+    ("cancel_download", &[MAIN]),
+    ("list_downloads", &[MAIN]),
+    ("open_path", &[MAIN]),
+    ("reveal_in_folder", &[MAIN]),
+    ("import_ca_certificate", &[MAIN]),
+    ("export_settings", &[MAIN]),
+    ("import_settings", &[MAIN]),
+    // Where the backend is and how we reach it
+    ("backend_request", &[MAIN]),
+    ("queue_or_send", &[MAIN]),
+    ("set_setting", &[MAIN]),
+    ("reset_setting", &[MAIN]),
+    // Settings changed through their own commands, like `set_setting`
+    ("complete_onboarding", &[MAIN]),
+    ("set_close_action", &[MAIN]),
+    ("set_start_minimized", &[MAIN]),
+    ("set_tray_menu", &[MAIN]),
+    ("set_language", &[MAIN]),
+    ("push_recent_item", &[MAIN]),
+    ("set_proxy_settings", &[MAIN]),
+    ("save_profile", &[MAIN]),
+    ("delete_profile", &[MAIN]),
+    ("switch_profile", &[MAIN]),
+    ("list_profiles", &[MAIN]),
+    // Credentials; settings come back with secrets decrypted
+    ("get_all_settings", &[MAIN]),
+    ("get_setting", &[MAIN]),
+    ("get_config", &[MAIN]),
+    ("store_secret", &[MAIN]),
+    ("get_secret", &[MAIN]),
+    ("delete_secret", &[MAIN]),
+    ("set_auth_token", &[MAIN]),
+    ("clear_auth_token", &[MAIN]),
+    ("login", &[MAIN]),
+    ("logout", &[MAIN]),
+    // Whatever the user last copied, possibly from another app
+    ("clipboard_read_text", &[MAIN]),
+    ("clipboard_read_image", &[MAIN]),
+    // Encrypted local data
+    ("cache_get", &[MAIN]),
+    ("cache_list", &[MAIN]),
+    ("cache_put", &[MAIN]),
+    ("cache_delete", &[MAIN]),
+    ("cache_clear", &[MAIN]),
+    ("record_job", &[MAIN]),
+    ("list_job_history", &[MAIN]),
+    ("delete_job_history", &[MAIN]),
+    ("add_favorite", &[MAIN]),
+    ("remove_favorite", &[MAIN]),
+    ("list_favorites", &[MAIN]),
+    ("save_backend_result", &[MAIN]),
+    ("get_backend_result", &[MAIN]),
+    ("clear_backend_results", &[MAIN]),
+    ("search_local", &[MAIN]),
+    ("export_data", &[MAIN]),
+    ("backup_now", &[MAIN]),
+    ("list_backups", &[MAIN]),
+    ("restore_backup", &[MAIN]),
+    ("fetch_cached_file", &[MAIN]),
+    ("clear_cache", &[MAIN]),
+    ("query_logs", &[MAIN]),
+    ("get_frontend_error_stats", &[MAIN]),
+    ("get_command_metrics", &[MAIN]),
+    ("get_worker_status", &[MAIN]),
+    ("list_crash_reports", &[MAIN]),
+    ("submit_crash_reports", &[MAIN]),
+    ("delete_crash_reports", &[MAIN]),
+    ("export_diagnostics", &[MAIN]),
+    ("list_offline_queue", &[MAIN]),
+    ("discard_queued_request", &[MAIN]),
+];
+
+fn denial(invoke: &Invoke) -> Option<String> {
+    let command = invoke.message.command();
+    let window = invoke.message.window();
+    let label = window.label();
+    if let Some((_, windows)) = SENSITIVE.iter().find(|(name, _)| *name == command) {
+        if !windows.contains(&label) {
+            return Some(format!("{} is not available to window {}", command, label));
+        }
+    }
+    let state = window.try_state::<AppState>()?;
+    if state.idle.is_locked() && !idle::WHILE_LOCKED.contains(&command) {
+        return Some(format!(
+            "{} is not available while the session is locked",
+            command
+        ));
+    }
+    let disabled = state.settings.get().security.disabled_commands;
+    disabled
+        .iter()
+        .any(|name| name == command)
+        .then(|| format!("{} is disabled by policy", command))
+}
+
+// Hands the invoke back if it may run; otherwise rejects it
+pub fn check(invoke: Invoke) -> Option<Invoke> {
+    let Some(reason) = denial(&invoke) else {
+        return Some(invoke);
+    };
+    let app = invoke.message.window().app_handle();
+    tracing::warn!("Denied command: {}", reason);
+    if app.try_state::<AppState>().is_some() {
+        logs::internal(&app, Level::Warning, format!("Denied command: {}", reason));
+    }
+    invoke.resolver.reject(reason);
+    None
+}
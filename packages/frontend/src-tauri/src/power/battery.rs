@@ -0,0 +1,74 @@
+// Whether the machine is running on battery right now. Pollers ask this every
+// round, so the answer is kept for CHECK_EVERY rather than asking the OS (or on
+// macOS, running pmset) each time. Desktops and anything we can't tell count
+// as plugged in.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const CHECK_EVERY: Duration = Duration::from_secs(60);
+
+#[derive(Default)]
+pub struct Battery {
+    last: Mutex<Option<(Instant, bool)>>,
+}
+
+impl Battery {
+    pub fn on_battery(&self) -> bool {
+        let mut last = self.last.lock().unwrap();
+        if let Some((at, on_battery)) = *last {
+            if at.elapsed() < CHECK_EVERY {
+                return on_battery;
+            }
+        }
+        let on_battery = platform::on_battery();
+        *last = Some((Instant::now(), on_battery));
+        on_battery
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    // ACLineStatus is 0 offline, 1 online, 255 unknown
+    pub fn on_battery() -> bool {
+        let mut status = SYSTEM_POWER_STATUS::default();
+        unsafe { GetSystemPowerStatus(&mut status) }.as_bool() && status.ACLineStatus == 0
+    }
+}
+
+// "Now drawing from 'Battery Power'" or "'AC Power'"
+#[cfg(target_os = "macos")]
+mod platform {
+    pub fn on_battery() -> bool {
+        std::process::Command::new("pmset")
+            .args(["-g", "batt"])
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).contains("'Battery Power'"))
+            .unwrap_or(false)
+    }
+}
+
+// On battery when there is a battery and no mains supply is online
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use std::fs;
+
+    pub fn on_battery() -> bool {
+        let Ok(supplies) = fs::read_dir("/sys/class/power_supply") else {
+            return false;
+        };
+        let mut has_battery = false;
+        for supply in supplies.flatten() {
+            let path = supply.path();
+            let read = |name: &str| fs::read_to_string(path.join(name)).unwrap_or_default();
+            match read("type").trim() {
+                "Mains" | "USB" if read("online").trim() == "1" => return false,
+                "Battery" => has_battery = true,
+                _ => {}
+            }
+        }
+        has_battery
+    }
+}
@@ -0,0 +1,244 @@
+// Keeping the machine awake. Each hold is a platform assertion that lasts
+// until it's released or the app exits: a power request on Windows, an IOKit
+// assertion on macOS, a logind inhibitor lock on Linux. The display may still
+// turn off; only idle sleep is blocked. The frontend takes holds with
+// `inhibit_sleep`, and the job tracker keeps one of its own while any backend
+// job is running (unless `power.keep_awake_during_jobs` is off).
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager};
+
+use crate::i18n;
+use crate::state::AppState;
+
+// Key of the job tracker's hold; frontend holds get random ids
+const JOBS: &str = "jobs";
+
+#[derive(Default)]
+pub struct Inhibitors {
+    held: Mutex<HashMap<String, platform::Assertion>>,
+}
+
+impl Inhibitors {
+    fn holds(&self, id: &str) -> bool {
+        self.held.lock().unwrap().contains_key(id)
+    }
+}
+
+// Takes (or replaces) the hold called `id`
+async fn hold(app: &AppHandle, id: String, reason: String) -> Result<(), String> {
+    let assertion = tauri::async_runtime::spawn_blocking(move || platform::Assertion::new(&reason))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("Failed to prevent sleep: {}", e))?;
+    let state = app.state::<AppState>();
+    state
+        .power
+        .inhibitors
+        .held
+        .lock()
+        .unwrap()
+        .insert(id, assertion);
+    Ok(())
+}
+
+fn release(app: &AppHandle, id: &str) -> bool {
+    let state = app.state::<AppState>();
+    let released = state.power.inhibitors.held.lock().unwrap().remove(id);
+    released.is_some()
+}
+
+// Called whenever the running job set changes
+pub async fn jobs_changed(app: &AppHandle, running: bool) {
+    let state = app.state::<AppState>();
+    let wanted = running && state.settings.get().power.keep_awake_during_jobs;
+    if wanted == state.power.inhibitors.holds(JOBS) {
+        return;
+    }
+    if !wanted {
+        release(app, JOBS);
+        return;
+    }
+    let reason = i18n::text(app, "power.jobs_running");
+    if let Err(e) = hold(app, JOBS.to_string(), reason).await {
+        tracing::warn!("{}", e);
+    }
+}
+
+// The setting may have changed while jobs are running
+pub fn reapply(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let running = !app.state::<AppState>().jobs.running().is_empty();
+        jobs_changed(&app, running).await;
+    });
+}
+
+#[cfg(windows)]
+mod platform {
+    use windows::core::PWSTR;
+    use windows::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows::Win32::System::Power::{
+        PowerClearRequest, PowerCreateRequest, PowerRequestSystemRequired, PowerSetRequest,
+    };
+    use windows::Win32::System::SystemServices::POWER_REQUEST_CONTEXT_VERSION;
+    use windows::Win32::System::Threading::{
+        POWER_REQUEST_CONTEXT_SIMPLE_STRING, REASON_CONTEXT, REASON_CONTEXT_0,
+    };
+
+    // Unlike SetThreadExecutionState, a power request isn't tied to the
+    // thread that made it, and shows up in `powercfg /requests` with the reason
+    pub struct Assertion(HANDLE);
+
+    impl Assertion {
+        pub fn new(reason: &str) -> Result<Self, String> {
+            let mut reason: Vec<u16> = reason.encode_utf16().chain(Some(0)).collect();
+            let context = REASON_CONTEXT {
+                Version: POWER_REQUEST_CONTEXT_VERSION,
+                Flags: POWER_REQUEST_CONTEXT_SIMPLE_STRING,
+                Reason: REASON_CONTEXT_0 {
+                    SimpleReasonString: PWSTR(reason.as_mut_ptr()),
+                },
+            };
+            let handle = unsafe { PowerCreateRequest(&context) }.map_err(|e| e.to_string())?;
+            let assertion = Self(handle);
+            if !unsafe { PowerSetRequest(handle, PowerRequestSystemRequired) }.as_bool() {
+                return Err(windows::core::Error::from_win32().to_string());
+            }
+            Ok(assertion)
+        }
+    }
+
+    impl Drop for Assertion {
+        fn drop(&mut self) {
+            unsafe {
+                PowerClearRequest(self.0, PowerRequestSystemRequired);
+                CloseHandle(self.0);
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::ffi::{c_char, c_void, CString};
+
+    type CFStringRef = *const c_void;
+
+    const UTF8: u32 = 0x0800_0100;
+    const LEVEL_ON: u32 = 255;
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFStringCreateWithCString(
+            allocator: *const c_void,
+            string: *const c_char,
+            encoding: u32,
+        ) -> CFStringRef;
+        fn CFRelease(object: *const c_void);
+    }
+
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        fn IOPMAssertionCreateWithName(
+            kind: CFStringRef,
+            level: u32,
+            name: CFStringRef,
+            id: *mut u32,
+        ) -> i32;
+        fn IOPMAssertionRelease(id: u32) -> i32;
+    }
+
+    fn cf_string(value: &str) -> CFStringRef {
+        let value = CString::new(value.replace('\0', "")).unwrap();
+        unsafe { CFStringCreateWithCString(std::ptr::null(), value.as_ptr(), UTF8) }
+    }
+
+    // Listed by `pmset -g assertions` under the reason
+    pub struct Assertion(u32);
+
+    impl Assertion {
+        pub fn new(reason: &str) -> Result<Self, String> {
+            let kind = cf_string("PreventUserIdleSystemSleep");
+            let name = cf_string(reason);
+            let mut id = 0;
+            let status = unsafe {
+                let status = IOPMAssertionCreateWithName(kind, LEVEL_ON, name, &mut id);
+                CFRelease(kind);
+                CFRelease(name);
+                status
+            };
+            if status != 0 {
+                return Err(format!("IOKit error {:#x}", status));
+            }
+            Ok(Self(id))
+        }
+    }
+
+    impl Drop for Assertion {
+        fn drop(&mut self) {
+            unsafe {
+                IOPMAssertionRelease(self.0);
+            }
+        }
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use zbus::blocking::{Connection, Proxy};
+    use zbus::zvariant::OwnedFd;
+
+    // logind keeps the lock for as long as the file descriptor is open. "idle"
+    // only: a "sleep" lock would also block a suspend the user asks for
+    pub struct Assertion(#[allow(dead_code)] OwnedFd);
+
+    impl Assertion {
+        pub fn new(reason: &str) -> Result<Self, String> {
+            let fd = (|| -> zbus::Result<OwnedFd> {
+                let connection = Connection::system()?;
+                let proxy = Proxy::new(
+                    &connection,
+                    "org.freedesktop.login1",
+                    "/org/freedesktop/login1",
+                    "org.freedesktop.login1.Manager",
+                )?;
+                proxy.call("Inhibit", &("idle", "AetherFrame", reason, "block"))
+            })();
+            fd.map(Self).map_err(|e| e.to_string())
+        }
+    }
+}
+
+// Returns the id to release it with
+#[tauri::command]
+pub async fn inhibit_sleep(app: AppHandle, reason: String) -> Result<String, String> {
+    let id = format!("{:016x}", rand::random::<u64>());
+    hold(&app, id.clone(), reason).await?;
+    Ok(id)
+}
+
+// Without an id, releases every hold the frontend took; the job tracker's
+// own hold is left alone
+#[tauri::command]
+pub async fn release_sleep_inhibit(app: AppHandle, id: Option<String>) -> Result<(), String> {
+    match id {
+        Some(id) if id == JOBS => Err("That hold belongs to the job tracker".to_string()),
+        Some(id) => release(&app, &id)
+            .then_some(())
+            .ok_or_else(|| format!("No sleep inhibitor {}", id)),
+        None => {
+            let state = app.state::<AppState>();
+            state
+                .power
+                .inhibitors
+                .held
+                .lock()
+                .unwrap()
+                .retain(|id, _| id == JOBS);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,266 @@
+// System sleep and wake. While the machine is suspended the pollers (health,
+// jobs, the supervisor) hold off, so probes that fail only because the
+// network is asleep don't turn into "backend down" or a restart. On wake the
+// HTTP client's pooled connections are dropped and health is checked at once,
+// and the frontend gets `system-sleep` / `system-resume`. Keeping the machine
+// awake in the first place is `inhibit`; whether it's on battery, `battery`.
+
+pub mod battery;
+pub mod inhibit;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tokio::sync::Notify;
+
+use crate::backend::logs::{self, Level};
+use crate::health;
+use crate::state::AppState;
+
+use self::battery::Battery;
+use self::inhibit::Inhibitors;
+
+// Networks (and VPNs) take a moment to come back after wake
+const RESUME_GRACE: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PowerSettings {
+    // Block idle sleep while backend jobs are running
+    pub keep_awake_during_jobs: bool,
+}
+
+impl Default for PowerSettings {
+    fn default() -> Self {
+        Self {
+            keep_awake_during_jobs: true,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Power {
+    asleep: AtomicBool,
+    woke: Notify,
+    resumed_at: Mutex<Option<Instant>>,
+    pub inhibitors: Inhibitors,
+    pub battery: Battery,
+}
+
+impl Power {
+    pub fn is_asleep(&self) -> bool {
+        self.asleep.load(Ordering::SeqCst)
+    }
+
+    // Just woken up, so failures may not mean anything yet
+    pub fn recently_resumed(&self) -> bool {
+        self.resumed_at
+            .lock()
+            .unwrap()
+            .is_some_and(|at| at.elapsed() < RESUME_GRACE)
+    }
+
+    // A poller's sleep between rounds: `duration`, cut short by a wake-up,
+    // and never ending while the system is asleep
+    pub async fn pause(&self, duration: Duration) {
+        let woke = self.woke.notified();
+        tokio::select! {
+            _ = tokio::time::sleep(duration) => {}
+            _ = woke => {}
+        }
+        while self.is_asleep() {
+            let woke = self.woke.notified();
+            if !self.is_asleep() {
+                break;
+            }
+            woke.await;
+        }
+    }
+}
+
+fn suspended(app: &AppHandle) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    if state.power.asleep.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    logs::internal(app, Level::Info, "System is going to sleep");
+    let _ = app.emit_all("system-sleep", ());
+}
+
+fn resumed(app: &AppHandle) {
+    let Some(state) = app.try_state::<AppState>() else {
+        return;
+    };
+    if !state.power.asleep.swap(false, Ordering::SeqCst) {
+        return;
+    }
+    *state.power.resumed_at.lock().unwrap() = Some(Instant::now());
+    logs::internal(app, Level::Info, "System woke up");
+    // Pooled keep-alive connections died with the network
+    if let Err(e) = state.http.reconfigure(app, &state.settings.get()) {
+        tracing::warn!("{}", e);
+    }
+    state.power.woke.notify_waiters();
+    let _ = app.emit_all("system-resume", ());
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move { health::check_now(&app).await });
+}
+
+pub fn start(app: &AppHandle) {
+    if let Err(e) = platform::watch(app) {
+        tracing::warn!("Not watching for sleep and wake: {}", e);
+    }
+}
+
+// logind announces both edges with PrepareForSleep(start)
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use tauri::AppHandle;
+    use zbus::blocking::{Connection, Proxy};
+
+    pub fn watch(app: &AppHandle) -> Result<(), String> {
+        let connection = Connection::system().map_err(|e| e.to_string())?;
+        let app = app.clone();
+        std::thread::spawn(move || {
+            let result = (|| -> zbus::Result<()> {
+                let proxy = Proxy::new(
+                    &connection,
+                    "org.freedesktop.login1",
+                    "/org/freedesktop/login1",
+                    "org.freedesktop.login1.Manager",
+                )?;
+                for message in proxy.receive_signal("PrepareForSleep")? {
+                    let start: bool = message.body().deserialize()?;
+                    if start {
+                        super::suspended(&app);
+                    } else {
+                        super::resumed(&app);
+                    }
+                }
+                Ok(())
+            })();
+            if let Err(e) = result {
+                tracing::warn!("Stopped watching for sleep and wake: {}", e);
+            }
+        });
+        Ok(())
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::ffi::c_void;
+    use std::sync::OnceLock;
+
+    use tauri::AppHandle;
+    use windows::Win32::Foundation::HANDLE;
+    use windows::Win32::System::Power::{
+        PowerRegisterSuspendResumeNotification, DEVICE_NOTIFY_CALLBACK,
+        DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        PBT_APMRESUMEAUTOMATIC, PBT_APMRESUMESUSPEND, PBT_APMSUSPEND,
+    };
+
+    static APP: OnceLock<AppHandle> = OnceLock::new();
+
+    unsafe extern "system" fn callback(
+        _context: *const c_void,
+        event: u32,
+        _setting: *const c_void,
+    ) -> u32 {
+        if let Some(app) = APP.get() {
+            match event {
+                PBT_APMSUSPEND => super::suspended(app),
+                PBT_APMRESUMEAUTOMATIC | PBT_APMRESUMESUSPEND => super::resumed(app),
+                _ => {}
+            }
+        }
+        0
+    }
+
+    pub fn watch(app: &AppHandle) -> Result<(), String> {
+        if APP.set(app.clone()).is_err() {
+            return Ok(());
+        }
+        // Both have to stay valid for as long as we're registered: forever
+        let parameters = Box::leak(Box::new(DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS {
+            Callback: Some(callback),
+            Context: std::ptr::null_mut(),
+        }));
+        let registration = Box::leak(Box::new(std::ptr::null_mut::<c_void>()));
+        let status = unsafe {
+            PowerRegisterSuspendResumeNotification(
+                DEVICE_NOTIFY_CALLBACK.0,
+                HANDLE(parameters as *mut DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS as isize),
+                registration,
+            )
+        };
+        if status != 0 {
+            return Err(format!("error {}", status));
+        }
+        Ok(())
+    }
+}
+
+// NSWorkspace posts will-sleep and did-wake notifications on the main thread
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::ffi::CString;
+    use std::sync::OnceLock;
+
+    use objc::declare::ClassDecl;
+    use objc::runtime::{Object, Sel};
+    use objc::{class, msg_send, sel, sel_impl};
+    use tauri::AppHandle;
+
+    static APP: OnceLock<AppHandle> = OnceLock::new();
+
+    extern "C" fn will_sleep(_this: &Object, _cmd: Sel, _note: *mut Object) {
+        if let Some(app) = APP.get() {
+            super::suspended(app);
+        }
+    }
+
+    extern "C" fn did_wake(_this: &Object, _cmd: Sel, _note: *mut Object) {
+        if let Some(app) = APP.get() {
+            super::resumed(app);
+        }
+    }
+
+    pub fn watch(app: &AppHandle) -> Result<(), String> {
+        if APP.set(app.clone()).is_err() {
+            return Ok(());
+        }
+        let mut decl = ClassDecl::new("AetherFramePowerObserver", class!(NSObject))
+            .ok_or("observer class already exists")?;
+        type Handler = extern "C" fn(&Object, Sel, *mut Object);
+        unsafe {
+            decl.add_method(sel!(willSleep:), will_sleep as Handler);
+            decl.add_method(sel!(didWake:), did_wake as Handler);
+            let observer: *mut Object = msg_send![decl.register(), new];
+            let workspace: *mut Object = msg_send![class!(NSWorkspace), sharedWorkspace];
+            let center: *mut Object = msg_send![workspace, notificationCenter];
+            for (selector, name) in [
+                (sel!(willSleep:), "NSWorkspaceWillSleepNotification"),
+                (sel!(didWake:), "NSWorkspaceDidWakeNotification"),
+            ] {
+                let name = CString::new(name).unwrap();
+                let name: *mut Object =
+                    msg_send![class!(NSString), stringWithUTF8String: name.as_ptr()];
+                let nil: *mut Object = std::ptr::null_mut();
+                let _: () = msg_send![center,
+                    addObserver: observer
+                    selector: selector
+                    name: name
+                    object: nil];
+            }
+        }
+        Ok(())
+    }
+}
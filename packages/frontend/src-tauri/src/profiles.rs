@@ -0,0 +1,173 @@
+// Named backend connection profiles (local sidecar, staging, production, ...)
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::{AppHandle, Manager, State};
+
+use crate::auth::login::OAuthSettings;
+use crate::backend;
+use crate::http::tls::TlsOptions;
+use crate::settings::{secret, Settings};
+use crate::state::AppState;
+use crate::tray;
+
+// Built-in profile for the bundled backend; always present
+pub const LOCAL_PROFILE: &str = "local";
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub name: String,
+    // None means "spawn the bundled sidecar"
+    pub url: Option<String>,
+    // Legacy plaintext token; prefer `set_auth_token`, which uses the keychain
+    pub auth_token: Option<String>,
+    // OAuth2 token endpoint used to refresh expiring tokens, absolute or backend-relative
+    pub token_url: Option<String>,
+    // Identity provider for interactive sign-in; see `auth::login`
+    pub oauth: Option<OAuthSettings>,
+    pub tls: TlsOptions,
+}
+
+#[derive(Serialize)]
+pub struct ProfileList {
+    pub active: String,
+    pub profiles: Vec<Profile>,
+}
+
+// The local profile honours the legacy `backend.attach_url` setting
+pub fn all(settings: &Settings) -> Vec<Profile> {
+    let local = Profile {
+        name: LOCAL_PROFILE.to_string(),
+        url: settings.backend.attach_url.clone(),
+        tls: settings.tls.clone(),
+        ..Default::default()
+    };
+    std::iter::once(local)
+        .chain(
+            settings
+                .profiles
+                .iter()
+                .filter(|p| p.name != LOCAL_PROFILE)
+                .cloned(),
+        )
+        .collect()
+}
+
+pub fn active_name(settings: &Settings) -> String {
+    settings
+        .active_profile
+        .clone()
+        .filter(|name| all(settings).iter().any(|p| &p.name == name))
+        .unwrap_or_else(|| LOCAL_PROFILE.to_string())
+}
+
+pub fn active(settings: &Settings) -> Profile {
+    let name = active_name(settings);
+    all(settings)
+        .into_iter()
+        .find(|p| p.name == name)
+        .unwrap_or_default()
+}
+
+// Point the backend connection at the given profile
+pub fn connect(app: &AppHandle, profile: &Profile) {
+    match &profile.url {
+        Some(url) => {
+            app.state::<AppState>().backend.kill();
+            backend::attach(app, url);
+        }
+        None => {
+            backend::detach(app);
+            if let Err(e) = backend::spawn(app) {
+                tracing::warn!("{}", e);
+            }
+        }
+    }
+}
+
+pub fn switch(app: &AppHandle, name: &str) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let store = &state.settings;
+    let profile = all(&store.get())
+        .into_iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| format!("Unknown profile: {}", name))?;
+
+    store.update(|s| s.active_profile = Some(profile.name.clone()))?;
+    // The shared client carries the profile's TLS settings
+    if let Err(e) = state.http.reconfigure(app, &store.get()) {
+        tracing::warn!("{}", e);
+    }
+    connect(app, &profile);
+    tray::rebuild(app);
+    let _ = app.emit_all("profile-changed", &profile.name);
+    Ok(())
+}
+
+// Without tokens, and with a URL that has credentials in it redacted the way
+// diagnostics show it
+fn redacted(profiles: Vec<Profile>) -> Result<Vec<Profile>, String> {
+    let mut tree = json!({ "profiles": profiles });
+    secret::redact(&mut tree);
+    let mut profiles: Vec<Profile> =
+        serde_json::from_value(tree["profiles"].take()).map_err(|e| e.to_string())?;
+    for profile in &mut profiles {
+        profile.auth_token = None;
+    }
+    Ok(profiles)
+}
+
+#[tauri::command]
+pub async fn list_profiles(state: State<'_, AppState>) -> Result<ProfileList, String> {
+    let settings = state.settings.get();
+    Ok(ProfileList {
+        active: active_name(&settings),
+        profiles: redacted(all(&settings))?,
+    })
+}
+
+#[tauri::command]
+pub async fn switch_profile(app: AppHandle, name: String) -> Result<(), String> {
+    switch(&app, &name)
+}
+
+// Add or replace a named profile
+#[tauri::command]
+pub async fn save_profile(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    profile: Profile,
+) -> Result<(), String> {
+    if profile.name.trim().is_empty() || profile.name == LOCAL_PROFILE {
+        return Err(format!("Invalid profile name: {:?}", profile.name));
+    }
+    if profile.url.is_none() {
+        return Err("Remote profiles need a backend URL".into());
+    }
+    state.settings.update(|s| {
+        s.profiles.retain(|p| p.name != profile.name);
+        s.profiles.push(profile);
+    })?;
+    state.http.reconfigure(&app, &state.settings.get())?;
+    tray::rebuild(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn delete_profile(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<(), String> {
+    let was_active = active_name(&state.settings.get()) == name;
+    state
+        .settings
+        .update(|s| s.profiles.retain(|p| p.name != name))?;
+    if was_active {
+        switch(&app, LOCAL_PROFILE)?;
+    } else {
+        tray::rebuild(&app);
+    }
+    Ok(())
+}
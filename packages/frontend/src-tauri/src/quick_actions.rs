@@ -0,0 +1,306 @@
+// Shortcuts outside the window: the taskbar jump list on Windows and the Dock
+// menu on macOS offer "New job", "Show status" and the recent items. Each one
+// is an aetherframe:// link, so it takes the same path as any other deep
+// link. A jump list entry launches the executable with the link, which the
+// single-instance handler forwards to the running app (or a cold start picks
+// up); the Dock menu hands it over in-process.
+
+use reqwest::Url;
+use tauri::{AppHandle, Manager};
+
+use crate::deeplink::SCHEME;
+use crate::i18n;
+use crate::settings::Settings;
+use crate::state::AppState;
+
+struct QuickAction {
+    title: String,
+    link: String,
+}
+
+fn link(route: &str) -> Option<String> {
+    let raw = format!("{}://{}", SCHEME, route.trim_start_matches('/'));
+    Url::parse(&raw).ok().map(String::from)
+}
+
+fn tasks(settings: &Settings) -> Vec<QuickAction> {
+    let language = i18n::language(settings);
+    [
+        ("actions.new_job", "jobs/new"),
+        ("actions.show_status", "status"),
+    ]
+    .into_iter()
+    .filter_map(|(key, route)| {
+        Some(QuickAction {
+            title: i18n::lookup(language, key).to_string(),
+            link: link(route)?,
+        })
+    })
+    .collect()
+}
+
+// Items without a route of their own open as /recent/<id>
+fn recent(settings: &Settings) -> Vec<QuickAction> {
+    settings
+        .recent
+        .iter()
+        .filter_map(|item| {
+            let route = match &item.route {
+                Some(route) => route.clone(),
+                None => format!("recent/{}", item.id),
+            };
+            Some(QuickAction {
+                title: item.label.clone(),
+                link: link(&route)?,
+            })
+        })
+        .collect()
+}
+
+pub fn start(app: &AppHandle) {
+    platform::install(app);
+    refresh(app);
+}
+
+// After the recent list or the language changes
+pub fn refresh(app: &AppHandle) {
+    let settings = app.state::<AppState>().settings.get();
+    let tasks = tasks(&settings);
+    let recent = recent(&settings);
+    let title = i18n::lookup(i18n::language(&settings), "actions.recent").to_string();
+    // Off the main thread; on Windows this is COM work
+    std::thread::spawn(move || {
+        if let Err(e) = platform::update(&tasks, &title, &recent) {
+            tracing::warn!("Failed to update the jump list: {}", e);
+        }
+    });
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::os::windows::ffi::OsStrExt;
+
+    use tauri::AppHandle;
+    use windows::core::{Interface, PCWSTR, PWSTR};
+    use windows::Win32::Storage::EnhancedStorage::PKEY_Title;
+    use windows::Win32::System::Com::StructuredStorage::{
+        PROPVARIANT, PROPVARIANT_0, PROPVARIANT_0_0, PROPVARIANT_0_0_0,
+    };
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED,
+    };
+    use windows::Win32::UI::Shell::Common::{IObjectArray, IObjectCollection};
+    use windows::Win32::UI::Shell::PropertiesSystem::IPropertyStore;
+    use windows::Win32::UI::Shell::{
+        DestinationList, EnumerableObjectCollection, ICustomDestinationList, IShellLinkW, ShellLink,
+    };
+
+    use super::QuickAction;
+    use crate::util::launcher;
+
+    const VT_LPWSTR: u16 = 31;
+
+    fn wide(value: impl AsRef<std::ffi::OsStr>) -> Vec<u16> {
+        value.as_ref().encode_wide().chain(Some(0)).collect()
+    }
+
+    pub fn install(_app: &AppHandle) {}
+
+    // A shortcut back to ourselves with the link as the only argument
+    unsafe fn shell_link(
+        program: &[u16],
+        action: &QuickAction,
+    ) -> windows::core::Result<IShellLinkW> {
+        let link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)?;
+        link.SetPath(PCWSTR(program.as_ptr()))?;
+        link.SetIconLocation(PCWSTR(program.as_ptr()), 0)?;
+        let arguments = wide(format!("\"{}\"", action.link));
+        link.SetArguments(PCWSTR(arguments.as_ptr()))?;
+        // The jump list shows the title property, not the description
+        let mut title = wide(&action.title);
+        let value = PROPVARIANT {
+            Anonymous: PROPVARIANT_0 {
+                Anonymous: std::mem::ManuallyDrop::new(PROPVARIANT_0_0 {
+                    vt: VT_LPWSTR,
+                    wReserved1: 0,
+                    wReserved2: 0,
+                    wReserved3: 0,
+                    Anonymous: PROPVARIANT_0_0_0 {
+                        pwszVal: PWSTR(title.as_mut_ptr()),
+                    },
+                }),
+            },
+        };
+        let store: IPropertyStore = link.cast()?;
+        store.SetValue(&PKEY_Title, &value)?;
+        store.Commit()?;
+        Ok(link)
+    }
+
+    unsafe fn collection(
+        program: &[u16],
+        actions: &[QuickAction],
+    ) -> windows::core::Result<IObjectArray> {
+        let collection: IObjectCollection =
+            CoCreateInstance(&EnumerableObjectCollection, None, CLSCTX_INPROC_SERVER)?;
+        for action in actions {
+            collection.AddObject(&shell_link(program, action)?)?;
+        }
+        collection.cast()
+    }
+
+    pub fn update(
+        tasks: &[QuickAction],
+        recent_title: &str,
+        recent: &[QuickAction],
+    ) -> Result<(), String> {
+        let program = wide(launcher()?);
+        let result = unsafe {
+            (|| -> windows::core::Result<()> {
+                CoInitializeEx(std::ptr::null(), COINIT_APARTMENTTHREADED)?;
+                let list: ICustomDestinationList =
+                    CoCreateInstance(&DestinationList, None, CLSCTX_INPROC_SERVER)?;
+                let mut slots = 0;
+                // Entries the user removed; ours are rebuilt in full each time
+                let _removed: IObjectArray = list.BeginList(&mut slots)?;
+                if !recent.is_empty() {
+                    let title = wide(recent_title);
+                    let recent = collection(&program, recent)?;
+                    list.AppendCategory(PCWSTR(title.as_ptr()), &recent)?;
+                }
+                list.AddUserTasks(&collection(&program, tasks)?)?;
+                list.CommitList()
+            })()
+        };
+        result.map_err(|e| e.to_string())
+    }
+}
+
+// AppKit asks the app delegate for the Dock menu each time it opens, so the
+// method is added to tao's delegate and builds the menu from current settings
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::ffi::{CStr, CString};
+    use std::os::raw::c_char;
+    use std::sync::OnceLock;
+
+    use objc::declare::ClassDecl;
+    use objc::runtime::{class_addMethod, object_getClass, Class, Imp, Object, Sel};
+    use objc::{class, msg_send, sel, sel_impl};
+    use tauri::{AppHandle, Manager};
+
+    use super::QuickAction;
+    use crate::deeplink;
+    use crate::state::AppState;
+
+    static APP: OnceLock<AppHandle> = OnceLock::new();
+    // Target of the menu items
+    static HANDLER: OnceLock<usize> = OnceLock::new();
+
+    unsafe fn ns_string(value: &str) -> *mut Object {
+        let value = CString::new(value.replace('\0', "")).unwrap();
+        msg_send![class!(NSString), stringWithUTF8String: value.as_ptr()]
+    }
+
+    unsafe fn add_item(menu: *mut Object, handler: *mut Object, action: &QuickAction) {
+        let item: *mut Object = msg_send![class!(NSMenuItem), alloc];
+        let item: *mut Object = msg_send![item,
+            initWithTitle: ns_string(&action.title)
+            action: sel!(openQuickAction:)
+            keyEquivalent: ns_string("")];
+        let _: () = msg_send![item, setTarget: handler];
+        let _: () = msg_send![item, setRepresentedObject: ns_string(&action.link)];
+        let _: () = msg_send![menu, addItem: item];
+        let _: () = msg_send![item, release];
+    }
+
+    extern "C" fn dock_menu(_this: &Object, _cmd: Sel, _sender: *mut Object) -> *mut Object {
+        let (Some(app), Some(handler)) = (APP.get(), HANDLER.get()) else {
+            return std::ptr::null_mut();
+        };
+        let settings = app.state::<AppState>().settings.get();
+        let handler = *handler as *mut Object;
+        unsafe {
+            let menu: *mut Object = msg_send![class!(NSMenu), new];
+            let menu: *mut Object = msg_send![menu, autorelease];
+            for action in super::recent(&settings) {
+                add_item(menu, handler, &action);
+            }
+            if !settings.recent.is_empty() {
+                let separator: *mut Object = msg_send![class!(NSMenuItem), separatorItem];
+                let _: () = msg_send![menu, addItem: separator];
+            }
+            for action in super::tasks(&settings) {
+                add_item(menu, handler, &action);
+            }
+            menu
+        }
+    }
+
+    extern "C" fn open_quick_action(_this: &Object, _cmd: Sel, item: *mut Object) {
+        let link = unsafe {
+            let link: *mut Object = msg_send![item, representedObject];
+            if link.is_null() {
+                return;
+            }
+            let utf8: *const c_char = msg_send![link, UTF8String];
+            CStr::from_ptr(utf8).to_string_lossy().into_owned()
+        };
+        if let Some(app) = APP.get() {
+            deeplink::open(app, &link);
+        }
+    }
+
+    // Setup runs after launch, so the delegate is in place
+    pub fn install(app: &AppHandle) {
+        if APP.set(app.clone()).is_err() {
+            return;
+        }
+        let Some(mut decl) = ClassDecl::new("AetherFrameQuickActions", class!(NSObject)) else {
+            return;
+        };
+        type Handler = extern "C" fn(&Object, Sel, *mut Object);
+        type MenuProvider = extern "C" fn(&Object, Sel, *mut Object) -> *mut Object;
+        unsafe {
+            decl.add_method(sel!(openQuickAction:), open_quick_action as Handler);
+            let handler: *mut Object = msg_send![decl.register(), new];
+            let _ = HANDLER.set(handler as usize);
+            let ns_app: *mut Object = msg_send![class!(NSApplication), sharedApplication];
+            let delegate: *mut Object = msg_send![ns_app, delegate];
+            if delegate.is_null() {
+                return;
+            }
+            let class = object_getClass(delegate) as *mut Class;
+            let types = CString::new("@@:@").unwrap();
+            let imp: Imp = std::mem::transmute(dock_menu as MenuProvider);
+            class_addMethod(class, sel!(applicationDockMenu:), imp, types.as_ptr());
+        }
+    }
+
+    pub fn update(
+        _tasks: &[QuickAction],
+        _title: &str,
+        _recent: &[QuickAction],
+    ) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+// Launchers only take actions from the installed .desktop file, which the
+// bundler writes without any
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use tauri::AppHandle;
+
+    use super::QuickAction;
+
+    pub fn install(_app: &AppHandle) {}
+
+    pub fn update(
+        _tasks: &[QuickAction],
+        _title: &str,
+        _recent: &[QuickAction],
+    ) -> Result<(), String> {
+        Ok(())
+    }
+}
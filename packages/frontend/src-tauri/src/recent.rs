@@ -0,0 +1,71 @@
+// Most-recently-used projects/documents, persisted in settings and shown in the tray
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::quick_actions;
+use crate::state::AppState;
+use crate::tray;
+use crate::util::now_millis;
+use crate::window;
+
+const MAX_ITEMS: usize = 10;
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecentItem {
+    // Stable key chosen by the frontend (project id, file path, ...)
+    pub id: String,
+    pub label: String,
+    // Where the frontend should navigate when the item is picked
+    pub route: Option<String>,
+    pub opened_at: u64,
+}
+
+// Bring the main window forward and tell the frontend where to go
+pub fn open(app: &AppHandle, id: &str) {
+    let settings = app.state::<AppState>().settings.get();
+    let Some(item) = settings.recent.into_iter().find(|i| i.id == id) else {
+        return;
+    };
+    if let Some(window) = window::activate(app) {
+        let _ = window.emit("navigate", item);
+    }
+}
+
+#[tauri::command]
+pub async fn get_recent_items(state: State<'_, AppState>) -> Result<Vec<RecentItem>, String> {
+    Ok(state.settings.get().recent)
+}
+
+// Move (or add) an item to the top of the list
+#[tauri::command]
+pub async fn push_recent_item(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    mut item: RecentItem,
+) -> Result<(), String> {
+    if item.id.trim().is_empty() {
+        return Err("Recent items need an id".into());
+    }
+    if item.label.trim().is_empty() {
+        item.label = item.id.clone();
+    }
+    item.opened_at = now_millis();
+    state.settings.update(|s| {
+        s.recent.retain(|i| i.id != item.id);
+        s.recent.insert(0, item);
+        s.recent.truncate(MAX_ITEMS);
+    })?;
+    tray::rebuild(&app);
+    quick_actions::refresh(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn clear_recent_items(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    state.settings.update(|s| s.recent.clear())?;
+    tray::rebuild(&app);
+    quick_actions::refresh(&app);
+    Ok(())
+}
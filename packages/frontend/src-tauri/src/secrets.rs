@@ -0,0 +1,88 @@
+// Credentials kept in the platform keychain (Keychain, Credential Manager,
+// Secret Service) instead of settings.json
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use keyring::Entry;
+use rand::RngCore;
+
+const SERVICE: &str = "AetherFrame";
+
+fn entry(key: &str) -> Result<Entry, String> {
+    Entry::new(SERVICE, key).map_err(|e| format!("Keychain unavailable: {}", e))
+}
+
+pub fn get(key: &str) -> Result<Option<String>, String> {
+    match entry(key)?.get_password() {
+        Ok(value) => Ok(Some(value)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read {} from keychain: {}", key, e)),
+    }
+}
+
+pub fn set(key: &str, value: &str) -> Result<(), String> {
+    entry(key)?
+        .set_password(value)
+        .map_err(|e| format!("Failed to store {} in keychain: {}", key, e))
+}
+
+pub fn delete(key: &str) -> Result<(), String> {
+    match entry(key)?.delete_password() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("Failed to remove {} from keychain: {}", key, e)),
+    }
+}
+
+// A random 256-bit key for encrypting data at rest, created on first use
+pub fn key(name: &str) -> Result<[u8; 32], String> {
+    if let Some(encoded) = get(name)? {
+        return STANDARD
+            .decode(encoded)
+            .ok()
+            .and_then(|key| key.try_into().ok())
+            .ok_or_else(|| format!("Key {} in the keychain is corrupt", name));
+    }
+    let mut key = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut key);
+    set(name, &STANDARD.encode(key))?;
+    Ok(key)
+}
+
+// The frontend gets its own namespace so it can't read or clobber the entries
+// the shell keeps for itself (auth tokens, the settings encryption key)
+const FRONTEND_PREFIX: &str = "app:";
+
+fn frontend_key(key: &str) -> Result<String, String> {
+    let key = key.trim();
+    if key.is_empty() || key.len() > 128 || key.chars().any(char::is_control) {
+        return Err(format!("Invalid secret name: {:?}", key));
+    }
+    Ok(format!("{}{}", FRONTEND_PREFIX, key))
+}
+
+// Keychain calls can block on an unlock prompt, so they run off the async runtime
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    tauri::async_runtime::spawn_blocking(f)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn store_secret(key: String, value: String) -> Result<(), String> {
+    let key = frontend_key(&key)?;
+    blocking(move || set(&key, &value)).await
+}
+
+#[tauri::command]
+pub async fn get_secret(key: String) -> Result<Option<String>, String> {
+    let key = frontend_key(&key)?;
+    blocking(move || get(&key)).await
+}
+
+#[tauri::command]
+pub async fn delete_secret(key: String) -> Result<(), String> {
+    let key = frontend_key(&key)?;
+    blocking(move || delete(&key)).await
+}
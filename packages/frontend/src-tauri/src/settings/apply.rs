@@ -0,0 +1,103 @@
+// Pushes a changed setting out to the subsystems that cache or act on it, so
+// edits made through `set_setting` take effect without a restart. Anything not
+// listed here (polling intervals, retries, notification toggles) is read fresh
+// from the store on every use.
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+
+use super::secret;
+use crate::i18n;
+use crate::logging;
+use crate::menu;
+use crate::power::inhibit;
+use crate::profiles;
+use crate::quick_actions;
+use crate::shortcuts;
+use crate::state::AppState;
+use crate::theme;
+use crate::tray;
+use crate::updater;
+use crate::window::{self, MAIN};
+
+#[derive(Clone, Serialize)]
+struct SettingChanged<'a> {
+    key: &'a str,
+    value: &'a Value,
+}
+
+pub fn apply(app: &AppHandle, key: &str) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let settings = state.settings.get();
+    match key.split('.').next().unwrap_or_default() {
+        "proxy" | "tls" | "profiles" => state.http.reconfigure(app, &settings)?,
+        // Reconnects and rebuilds the client with the profile's TLS options
+        "active_profile" => profiles::switch(app, &profiles::active_name(&settings))?,
+        "language" => {
+            tray::rebuild(app);
+            menu::refresh(app);
+            quick_actions::refresh(app);
+            let _ = app.emit_all("language-changed", i18n::language(&settings));
+        }
+        "tray" => tray::rebuild(app),
+        "recent" => {
+            tray::rebuild(app);
+            quick_actions::refresh(app);
+        }
+        "shortcuts" => shortcuts::reload(app),
+        "power" => inhibit::reapply(app),
+        "logging" => logging::reload(&settings.logging)?,
+        "file_cache" => state.file_cache.enforce(&settings.file_cache),
+        // A different channel or feed may have a different answer
+        "updater" => updater::check_in_background(app),
+        "window" => {
+            // Window effects have to be applied from the main thread
+            let handle = app.clone();
+            app.run_on_main_thread(move || window::apply_settings(&handle))
+                .map_err(|e| e.to_string())?;
+            if settings.window.match_system_theme {
+                theme::apply_chrome(app);
+            }
+            tray::rebuild(app);
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+// After every setting was replaced at once (import, restore)
+pub fn apply_all(app: &AppHandle) {
+    let settings = app.state::<AppState>().settings.get();
+    let Ok(Value::Object(sections)) = serde_json::to_value(settings) else {
+        return;
+    };
+    let redacted = redacted(app);
+    for (key, value) in sections {
+        if let Err(e) = apply(app, &key) {
+            tracing::warn!("Failed to apply {}: {}", key, e);
+        }
+        send(app, &key, &value, &redacted);
+    }
+}
+
+// Every window gets the key and its new value; only the main window sees
+// secrets, like with `get_setting`
+pub fn broadcast(app: &AppHandle, key: &str, value: &Value) {
+    send(app, key, value, &redacted(app));
+}
+
+fn redacted(app: &AppHandle) -> Value {
+    let settings = app.state::<AppState>().settings.get();
+    let mut tree = serde_json::to_value(settings).unwrap_or_default();
+    secret::redact(&mut tree);
+    tree
+}
+
+fn send(app: &AppHandle, key: &str, value: &Value, redacted: &Value) {
+    let hidden = super::lookup(redacted, key).unwrap_or(&Value::Null);
+    for (label, window) in app.windows() {
+        let value = if label == MAIN { value } else { hidden };
+        let _ = window.emit("settings-changed", SettingChanged { key, value });
+    }
+}
@@ -0,0 +1,123 @@
+// Portable settings file for moving a setup to another machine. Sensitive
+// fields are stripped, or re-encrypted under a passphrase when one is given,
+// since the keychain key used at rest never leaves this machine.
+
+use std::fs;
+use std::path::PathBuf;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chacha20poly1305::aead::{KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use tauri::{AppHandle, Manager};
+
+use super::{apply, migrate, secret, Settings};
+use crate::state::AppState;
+use crate::util::{now_millis, write_atomic};
+
+const FORMAT: &str = "aetherframe-settings";
+const KDF_ROUNDS: u32 = 210_000;
+const SALT_LEN: usize = 16;
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Secrets {
+    Stripped,
+    Passphrase,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Bundle {
+    format: String,
+    app_version: String,
+    exported_at: u64,
+    secrets: Secrets,
+    // Base64 PBKDF2 salt when `secrets` is passphrase
+    salt: Option<String>,
+    settings: Value,
+}
+
+#[derive(Serialize)]
+pub struct ImportReport {
+    // False when the bundle had secrets but no (or the wrong) passphrase was given
+    secrets_restored: bool,
+}
+
+fn derive(passphrase: &str, salt: &[u8]) -> ChaCha20Poly1305 {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, KDF_ROUNDS, &mut key);
+    ChaCha20Poly1305::new(Key::from_slice(&key))
+}
+
+#[tauri::command]
+pub async fn export_settings(
+    app: AppHandle,
+    path: PathBuf,
+    passphrase: Option<String>,
+) -> Result<(), String> {
+    let settings = app.state::<AppState>().settings.get();
+    let mut tree = serde_json::to_value(&settings).map_err(|e| e.to_string())?;
+    let (secrets, salt) = match passphrase.filter(|p| !p.is_empty()) {
+        Some(passphrase) => {
+            let mut salt = [0u8; SALT_LEN];
+            OsRng.fill_bytes(&mut salt);
+            secret::seal_with(&derive(&passphrase, &salt), &mut tree)?;
+            (Secrets::Passphrase, Some(STANDARD.encode(salt)))
+        }
+        None => {
+            secret::strip(&mut tree);
+            (Secrets::Stripped, None)
+        }
+    };
+    let bundle = Bundle {
+        format: FORMAT.to_string(),
+        app_version: app.package_info().version.to_string(),
+        exported_at: now_millis(),
+        secrets,
+        salt,
+        settings: tree,
+    };
+    let json = serde_json::to_string_pretty(&bundle).map_err(|e| e.to_string())?;
+    write_atomic(&path, json.as_bytes())
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+// Replaces every setting with the bundle's, migrating it first if it came from
+// an older version, then applies and announces each section
+#[tauri::command]
+pub async fn import_settings(
+    app: AppHandle,
+    path: PathBuf,
+    passphrase: Option<String>,
+) -> Result<ImportReport, String> {
+    let raw = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let bundle: Bundle =
+        serde_json::from_str(&raw).map_err(|e| format!("Not a settings bundle: {}", e))?;
+    if bundle.format != FORMAT {
+        return Err(format!("Not a settings bundle: {}", path.display()));
+    }
+
+    let mut tree = bundle.settings;
+    migrate::run(&mut tree);
+    let cipher = match (bundle.secrets, bundle.salt, passphrase) {
+        (Secrets::Passphrase, Some(salt), Some(passphrase)) => {
+            let salt = STANDARD.decode(salt).map_err(|e| e.to_string())?;
+            Some(derive(&passphrase, &salt))
+        }
+        _ => None,
+    };
+    let secrets_restored = secret::unseal(&mut tree, cipher.as_ref()) == 0;
+    let mut settings: Settings =
+        serde_json::from_value(tree).map_err(|e| format!("Invalid settings bundle: {}", e))?;
+    settings.version = migrate::CURRENT_VERSION;
+
+    let state = app.state::<AppState>();
+    state.settings.replace(settings)?;
+    apply::apply_all(&app);
+    Ok(ImportReport { secrets_restored })
+}
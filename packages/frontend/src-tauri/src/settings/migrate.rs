@@ -0,0 +1,49 @@
+// Upgrades settings files written by older versions. Each step takes the raw
+// JSON from version N to N + 1, so a rename never falls through to serde's
+// defaults and silently resets a preference.
+
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+// Bump together with a new entry in MIGRATIONS
+pub const CURRENT_VERSION: u32 = 1;
+
+type Migration = fn(&mut Value);
+
+// MIGRATIONS[n] upgrades version n to n + 1
+const MIGRATIONS: [Migration; CURRENT_VERSION as usize] = [v0_to_v1];
+
+// Files from before versioning; the layout itself is unchanged
+fn v0_to_v1(_settings: &mut Value) {}
+
+pub fn version_of(settings: &Value) -> u32 {
+    settings.get("version").and_then(Value::as_u64).unwrap_or(0) as u32
+}
+
+// Returns whether anything changed. A file newer than this build is left
+// alone; unknown fields in it are ignored on load.
+pub fn run(settings: &mut Value) -> bool {
+    let from = version_of(settings);
+    if from >= CURRENT_VERSION {
+        return false;
+    }
+    for migration in &MIGRATIONS[from as usize..] {
+        migration(settings);
+    }
+    if let Value::Object(map) = settings {
+        map.insert("version".to_string(), CURRENT_VERSION.into());
+    }
+    true
+}
+
+// Keep the file as it was before we rewrite it, e.g. settings.v0.json.bak
+pub fn backup(path: &Path, tag: &str) {
+    let mut name = path.file_stem().unwrap_or_default().to_owned();
+    name.push(format!(".{}.json.bak", tag));
+    let target = path.with_file_name(name);
+    if let Err(e) = fs::copy(path, &target) {
+        tracing::warn!("Failed to back up {}: {}", path.display(), e);
+    }
+}
@@ -0,0 +1,283 @@
+// User settings, persisted as JSON in the app config dir. Every subsystem keeps
+// its preferences here; the frontend reaches them by dotted key
+// (`window.close_action`) through the commands at the bottom.
+
+pub mod apply;
+pub mod bundle;
+pub mod migrate;
+pub mod secret;
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager, State};
+
+use crate::crash::CrashSettings;
+use crate::health::HealthSettings;
+use crate::http::proxy::ProxySettings;
+use crate::http::retry::RetrySettings;
+use crate::http::tls::TlsOptions;
+use crate::idle::IdleSettings;
+use crate::ingest::IngestSettings;
+use crate::logging::LoggingSettings;
+use crate::notifications::NotificationSettings;
+use crate::power::PowerSettings;
+use crate::profiles::Profile;
+use crate::recent::RecentItem;
+use crate::shortcuts::Shortcut;
+use crate::state::AppState;
+use crate::storage::backup::BackupSettings;
+use crate::storage::file_cache::FileCacheSettings;
+use crate::tray::TraySettings;
+use crate::updater::UpdaterSettings;
+use crate::util::write_atomic;
+use crate::window::WindowSettings;
+
+use self::secret::{SecuritySettings, Vault};
+
+const FILE_NAME: &str = "settings.json";
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    // Schema version of the file; see `migrate`
+    pub version: u32,
+    pub backend: BackendSettings,
+    pub profiles: Vec<Profile>,
+    pub active_profile: Option<String>,
+    pub http: RetrySettings,
+    pub proxy: ProxySettings,
+    // TLS options for the local profile; remote profiles carry their own
+    pub tls: TlsOptions,
+    // Newest first
+    pub recent: Vec<RecentItem>,
+    pub tray: TraySettings,
+    pub notifications: NotificationSettings,
+    // UI language for native strings; None follows the OS locale
+    pub language: Option<String>,
+    pub window: WindowSettings,
+    pub health: HealthSettings,
+    pub idle: IdleSettings,
+    pub updater: UpdaterSettings,
+    pub security: SecuritySettings,
+    // Global keyboard shortcuts; see `shortcuts`
+    pub shortcuts: Vec<Shortcut>,
+    // What happens to files dropped on a window
+    pub ingest: IngestSettings,
+    pub power: PowerSettings,
+    pub backup: BackupSettings,
+    // Size cap for files fetched with `fetch_cached_file`
+    pub file_cache: FileCacheSettings,
+    pub logging: LoggingSettings,
+    pub crash: CrashSettings,
+}
+
+// How the sidecar is launched
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackendSettings {
+    // Extra CLI flags, appended after the host/port we pass ourselves
+    pub args: Vec<String>,
+    pub env: HashMap<String, String>,
+    pub working_dir: Option<PathBuf>,
+    // When set, attach to this backend instead of spawning the sidecar
+    pub attach_url: Option<String>,
+}
+
+pub struct SettingsStore {
+    path: PathBuf,
+    vault: Vault,
+    settings: Mutex<Settings>,
+}
+
+impl SettingsStore {
+    // A missing or unreadable file falls back to defaults rather than failing startup
+    pub fn load(dir: PathBuf) -> Self {
+        let path = dir.join(FILE_NAME);
+        let vault = Vault::default();
+        let (mut settings, rewrite) = match fs::read_to_string(&path) {
+            Ok(raw) => Self::parse(&path, &raw, &vault).unwrap_or_else(|e| {
+                tracing::warn!("Ignoring malformed {}: {}", path.display(), e);
+                // It's about to be overwritten with defaults; keep a copy
+                migrate::backup(&path, "invalid");
+                (Settings::default(), false)
+            }),
+            Err(_) => (Settings::default(), false),
+        };
+        settings.version = settings.version.max(migrate::CURRENT_VERSION);
+        let store = Self {
+            path,
+            vault,
+            settings: Mutex::new(settings),
+        };
+        // Not fatal: the next save writes the new format anyway
+        if rewrite {
+            if let Err(e) = store.save(&store.get()) {
+                tracing::warn!("{}", e);
+            }
+        }
+        store
+    }
+
+    // Migrate the raw JSON first (backing up the old file) and decrypt sealed
+    // values. Returns whether the file should be written back, so a migration
+    // or newly enabled encryption only has to happen once.
+    fn parse(path: &Path, raw: &str, vault: &Vault) -> Result<(Settings, bool), String> {
+        let mut tree: Value = serde_json::from_str(raw).map_err(|e| e.to_string())?;
+        let from = migrate::version_of(&tree);
+        let migrated = migrate::run(&mut tree);
+        if migrated {
+            migrate::backup(path, &format!("v{}", from));
+        }
+        let unsealed = secret::has_plaintext(&mut tree);
+        vault.open(&mut tree);
+        let settings: Settings = serde_json::from_value(tree).map_err(|e| e.to_string())?;
+        let rewrite = migrated || (unsealed && settings.security.encrypt_secrets);
+        Ok((settings, rewrite))
+    }
+
+    // The file itself, for backups
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // Replace everything with the contents of a settings file from a backup
+    pub fn restore(&self, raw: &str) -> Result<(), String> {
+        let (mut settings, _) = Self::parse(&self.path, raw, &self.vault)?;
+        settings.version = settings.version.max(migrate::CURRENT_VERSION);
+        self.replace(settings)
+    }
+
+    pub fn get(&self) -> Settings {
+        self.settings.lock().unwrap().clone()
+    }
+
+    // For the panic hook, which mustn't wait on a lock the panicking thread holds
+    pub fn try_get(&self) -> Option<Settings> {
+        self.settings
+            .try_lock()
+            .ok()
+            .map(|settings| settings.clone())
+    }
+
+    pub fn update<F: FnOnce(&mut Settings)>(&self, f: F) -> Result<(), String> {
+        let mut settings = self.settings.lock().unwrap();
+        f(&mut settings);
+        self.save(&settings)
+    }
+
+    pub fn replace(&self, updated: Settings) -> Result<(), String> {
+        let mut settings = self.settings.lock().unwrap();
+        self.save(&updated)?;
+        *settings = updated;
+        Ok(())
+    }
+
+    // Set one dotted key, going through JSON so the value is type-checked
+    // against the real settings struct. Returns the value as stored.
+    pub fn set_key(&self, key: &str, value: Value) -> Result<Value, String> {
+        if key == "version" {
+            return Err("The settings version is managed by the app".to_string());
+        }
+        let mut settings = self.settings.lock().unwrap();
+        let mut tree = serde_json::to_value(&*settings).map_err(|e| e.to_string())?;
+        *lookup_mut(&mut tree, key)? = value;
+        let updated: Settings = serde_json::from_value(tree)
+            .map_err(|e| format!("Invalid value for {}: {}", key, e))?;
+        // serde ignores fields it doesn't know, so a key missing after the round
+        // trip was never a real setting
+        let stored = serde_json::to_value(&updated).map_err(|e| e.to_string())?;
+        let stored = lookup(&stored, key)
+            .cloned()
+            .ok_or_else(|| format!("Unknown setting: {}", key))?;
+        self.save(&updated)?;
+        *settings = updated;
+        Ok(stored)
+    }
+
+    fn save(&self, settings: &Settings) -> Result<(), String> {
+        let mut tree = serde_json::to_value(settings).map_err(|e| e.to_string())?;
+        if settings.security.encrypt_secrets {
+            // Without a keychain the choice is plaintext or losing the change
+            if let Err(e) = self.vault.seal(&mut tree) {
+                tracing::warn!("Storing sensitive settings unencrypted: {}", e);
+            }
+        }
+        let json = serde_json::to_string_pretty(&tree).map_err(|e| e.to_string())?;
+        write_atomic(&self.path, json.as_bytes())
+            .map_err(|e| format!("Failed to save settings: {}", e))
+    }
+}
+
+fn lookup<'a>(tree: &'a Value, key: &str) -> Option<&'a Value> {
+    key.split('.').try_fold(tree, |node, part| node.get(part))
+}
+
+// The last segment may be new so map-valued settings can gain entries
+// (e.g. `backend.env.PYTHONUNBUFFERED`)
+fn lookup_mut<'a>(tree: &'a mut Value, key: &str) -> Result<&'a mut Value, String> {
+    let unknown = || format!("Unknown setting: {}", key);
+    let (parent, last) = match key.rsplit_once('.') {
+        Some((parent, last)) => (Some(parent), last),
+        None => (None, key),
+    };
+    let mut node = tree;
+    for part in parent.into_iter().flat_map(|p| p.split('.')) {
+        node = node.get_mut(part).ok_or_else(unknown)?;
+    }
+    match node {
+        Value::Object(map) => Ok(map.entry(last).or_insert(Value::Null)),
+        _ => Err(unknown()),
+    }
+}
+
+#[tauri::command]
+pub async fn get_all_settings(state: State<'_, AppState>) -> Result<Value, String> {
+    serde_json::to_value(state.settings.get()).map_err(|e| e.to_string())
+}
+
+// Unset optional keys come back as null
+#[tauri::command]
+pub async fn get_setting(state: State<'_, AppState>, key: String) -> Result<Value, String> {
+    let tree = serde_json::to_value(state.settings.get()).map_err(|e| e.to_string())?;
+    lookup(&tree, &key)
+        .cloned()
+        .ok_or_else(|| format!("Unknown setting: {}", key))
+}
+
+// Store, apply live, then tell every window. A value the owning subsystem
+// rejects (e.g. an unparsable proxy) is rolled back.
+fn set_and_apply(app: &AppHandle, key: &str, value: Value) -> Result<Value, String> {
+    let state = app.state::<AppState>();
+    let store = &state.settings;
+    let tree = serde_json::to_value(store.get()).map_err(|e| e.to_string())?;
+    let previous = lookup(&tree, key).cloned();
+    let stored = store.set_key(key, value)?;
+    if let Err(e) = apply::apply(app, key) {
+        if let Some(previous) = previous {
+            let _ = store.set_key(key, previous);
+            let _ = apply::apply(app, key);
+        }
+        return Err(e);
+    }
+    apply::broadcast(app, key, &stored);
+    Ok(stored)
+}
+
+#[tauri::command]
+pub async fn set_setting(app: AppHandle, key: String, value: Value) -> Result<Value, String> {
+    set_and_apply(&app, &key, value)
+}
+
+#[tauri::command]
+pub async fn reset_setting(app: AppHandle, key: String) -> Result<Value, String> {
+    let defaults = serde_json::to_value(Settings::default()).map_err(|e| e.to_string())?;
+    let value = lookup(&defaults, &key)
+        .cloned()
+        .ok_or_else(|| format!("Unknown setting: {}", key))?;
+    set_and_apply(&app, &key, value)
+}
@@ -0,0 +1,314 @@
+// Encryption at rest for sensitive settings. Marked fields are sealed with
+// ChaCha20-Poly1305 before settings.json is written; the key lives in the OS
+// keychain, so the file on its own doesn't give anything away. In memory (and
+// over the command API) the values stay plaintext.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::secrets;
+
+const KEYCHAIN_ENTRY: &str = "settings-key";
+const PREFIX: &str = "enc:v1:";
+const NONCE_LEN: usize = 12;
+const REDACTED: &str = "<redacted>";
+
+// Dotted paths, `*` matching any map key or list index
+const SECRET_FIELDS: &[(&str, Sensitive)] = &[
+    ("proxy.password", always),
+    ("backend.attach_url", has_credentials),
+    ("profiles.*.url", has_credentials),
+    ("profiles.*.auth_token", always),
+    ("backend.env.*", looks_like_secret),
+];
+
+type Sensitive = fn(name: &str, value: &str) -> bool;
+
+fn always(_name: &str, _value: &str) -> bool {
+    true
+}
+
+// user:pass@host in a URL
+fn has_credentials(_name: &str, value: &str) -> bool {
+    let rest = value.split_once("://").map_or(value, |(_, rest)| rest);
+    let authority = rest.split('/').next().unwrap_or_default();
+    authority.contains('@')
+}
+
+// Env vars like OPENAI_API_KEY or DB_PASSWORD
+fn looks_like_secret(name: &str, _value: &str) -> bool {
+    let name = name.to_ascii_uppercase();
+    ["KEY", "TOKEN", "SECRET", "PASSWORD", "CREDENTIAL"]
+        .iter()
+        .any(|word| name.contains(word))
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SecuritySettings {
+    // Off stores everything in plaintext (e.g. where no keychain is available)
+    pub encrypt_secrets: bool,
+    // Commands refused for every window; see `permissions`
+    pub disabled_commands: Vec<String>,
+    // Hosts `open_external` may open besides the backend, e.g. "*.example.com"
+    pub external_hosts: Vec<String>,
+    // Folders `open_path` / `reveal_in_folder` may open, besides the app's
+    // own data, the backend's working directory and Downloads
+    pub file_dirs: Vec<PathBuf>,
+}
+
+impl Default for SecuritySettings {
+    fn default() -> Self {
+        Self {
+            encrypt_secrets: true,
+            disabled_commands: Vec::new(),
+            external_hosts: Vec::new(),
+            file_dirs: Vec::new(),
+        }
+    }
+}
+
+// Holds the cipher once the key has been fetched, so the keychain (which may
+// prompt on macOS) is only asked once per run
+#[derive(Default)]
+pub struct Vault {
+    cipher: Mutex<Option<ChaCha20Poly1305>>,
+}
+
+impl Vault {
+    fn cipher(&self) -> Result<ChaCha20Poly1305, String> {
+        let mut cipher = self.cipher.lock().unwrap();
+        if let Some(cipher) = cipher.as_ref() {
+            return Ok(cipher.clone());
+        }
+        let key = secrets::key(KEYCHAIN_ENTRY)?;
+        let created = ChaCha20Poly1305::new(Key::from_slice(&key));
+        *cipher = Some(created.clone());
+        Ok(created)
+    }
+
+    // Encrypt every sensitive field that isn't already
+    pub fn seal(&self, tree: &mut Value) -> Result<(), String> {
+        if !has_plaintext(tree) {
+            return Ok(());
+        }
+        seal_with(&self.cipher()?, tree)
+    }
+
+    // Decrypt sealed values wherever they are, so moving a field between
+    // schema versions can't strand it. Values that can't be decrypted (key
+    // lost, file from another machine) are removed, leaving the field at its
+    // default rather than holding garbage.
+    pub fn open(&self, tree: &mut Value) {
+        if !has_sealed(tree) {
+            return;
+        }
+        let cipher = self
+            .cipher()
+            .map_err(|e| tracing::warn!("Can't decrypt settings: {}", e))
+            .ok();
+        let dropped = unseal(tree, cipher.as_ref());
+        if dropped > 0 {
+            tracing::warn!("Dropped {} settings that could not be decrypted", dropped);
+        }
+    }
+}
+
+// Whether a file still has sensitive values written before encryption was on
+pub fn has_plaintext(tree: &mut Value) -> bool {
+    let mut found = false;
+    for_each_plaintext(tree, &mut |_| {
+        found = true;
+        true
+    });
+    found
+}
+
+pub fn seal_with(cipher: &ChaCha20Poly1305, tree: &mut Value) -> Result<(), String> {
+    let mut result = Ok(());
+    for_each_plaintext(tree, &mut |plain| {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        match cipher.encrypt(&nonce, plain.as_bytes()) {
+            Ok(sealed) => {
+                let mut blob = nonce.to_vec();
+                blob.extend(sealed);
+                *plain = format!("{}{}", PREFIX, STANDARD.encode(blob));
+            }
+            Err(e) => result = Err(format!("Failed to encrypt setting: {}", e)),
+        }
+        true
+    });
+    result
+}
+
+// Drop every sensitive field, e.g. before the settings leave the machine
+pub fn strip(tree: &mut Value) {
+    for_each_plaintext(tree, &mut |_| false);
+}
+
+// Replace every sensitive field with a placeholder, so it shows it was set
+pub fn redact(tree: &mut Value) {
+    for_each_plaintext(tree, &mut |value| {
+        *value = REDACTED.to_string();
+        true
+    });
+}
+
+fn decrypt(cipher: &ChaCha20Poly1305, value: &str) -> Option<String> {
+    let blob = STANDARD.decode(value.strip_prefix(PREFIX)?).ok()?;
+    if blob.len() < NONCE_LEN {
+        return None;
+    }
+    let (nonce, sealed) = blob.split_at(NONCE_LEN);
+    let plain = cipher.decrypt(Nonce::from_slice(nonce), sealed).ok()?;
+    String::from_utf8(plain).ok()
+}
+
+// Calls `f` on each plaintext, non-empty sensitive string; returning false
+// removes the field
+fn for_each_plaintext(tree: &mut Value, f: &mut dyn FnMut(&mut String) -> bool) {
+    for (path, sensitive) in SECRET_FIELDS {
+        let path: Vec<&str> = path.split('.').collect();
+        visit(tree, &path, *sensitive, f);
+    }
+}
+
+fn visit(
+    node: &mut Value,
+    path: &[&str],
+    sensitive: Sensitive,
+    f: &mut dyn FnMut(&mut String) -> bool,
+) {
+    let Some((first, rest)) = path.split_first() else {
+        return;
+    };
+    match node {
+        // Leaves are handled from their parent so they can be removed
+        Value::Object(map) if rest.is_empty() => map.retain(|key, child| {
+            let Value::String(value) = child else {
+                return true;
+            };
+            let matches = *first == "*" || first == key;
+            if !matches || value.is_empty() || value.starts_with(PREFIX) || !sensitive(key, value) {
+                return true;
+            }
+            f(value)
+        }),
+        Value::Object(map) if *first == "*" => {
+            for child in map.values_mut() {
+                visit(child, rest, sensitive, f);
+            }
+        }
+        Value::Object(map) => {
+            if let Some(child) = map.get_mut(*first) {
+                visit(child, rest, sensitive, f);
+            }
+        }
+        Value::Array(items) if *first == "*" => {
+            for child in items.iter_mut() {
+                visit(child, rest, sensitive, f);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_sealed(value: &Value) -> bool {
+    value.as_str().is_some_and(|s| s.starts_with(PREFIX))
+}
+
+fn has_sealed(node: &Value) -> bool {
+    match node {
+        Value::Object(map) => map.values().any(has_sealed),
+        Value::Array(items) => items.iter().any(has_sealed),
+        value => is_sealed(value),
+    }
+}
+
+// Returns how many values couldn't be decrypted (and were removed)
+pub fn unseal(node: &mut Value, cipher: Option<&ChaCha20Poly1305>) -> usize {
+    let mut dropped = 0;
+    match node {
+        Value::Object(map) => {
+            map.retain(|_, child| {
+                if !is_sealed(child) {
+                    dropped += unseal(child, cipher);
+                    return true;
+                }
+                let plain = cipher.and_then(|c| decrypt(c, child.as_str().unwrap_or_default()));
+                match plain {
+                    Some(plain) => {
+                        *child = Value::String(plain);
+                        true
+                    }
+                    None => {
+                        dropped += 1;
+                        false
+                    }
+                }
+            });
+        }
+        Value::Array(items) => {
+            for child in items.iter_mut() {
+                dropped += unseal(child, cipher);
+            }
+        }
+        _ => {}
+    }
+    dropped
+}
+
+#[cfg(test)]
+mod tests {
+    use chacha20poly1305::aead::KeyInit;
+    use chacha20poly1305::{ChaCha20Poly1305, Key};
+    use serde_json::{json, Value};
+
+    use super::{redact, seal_with, unseal, PREFIX, REDACTED};
+
+    fn settings() -> Value {
+        json!({
+            "proxy": { "password": "hunter2" },
+            "profiles": [
+                { "name": "work", "url": "https://api.example.com", "auth_token": "abc" },
+                { "name": "home", "url": "http://me:pw@localhost:8000", "auth_token": "" },
+            ],
+        })
+    }
+
+    fn sealed(value: &Value) -> bool {
+        value.as_str().is_some_and(|s| s.starts_with(PREFIX))
+    }
+
+    #[test]
+    fn profile_tokens_are_redacted() {
+        let mut tree = settings();
+        redact(&mut tree);
+        assert_eq!(tree["profiles"][0]["auth_token"], REDACTED);
+        assert_eq!(tree["profiles"][0]["url"], "https://api.example.com");
+        assert_eq!(tree["profiles"][1]["auth_token"], "");
+        assert_eq!(tree["profiles"][1]["url"], REDACTED);
+    }
+
+    #[test]
+    fn secrets_survive_a_seal_round_trip() {
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(&[7; 32]));
+        let mut tree = settings();
+        seal_with(&cipher, &mut tree).unwrap();
+        assert!(sealed(&tree["proxy"]["password"]));
+        assert!(sealed(&tree["profiles"][0]["auth_token"]));
+        assert!(sealed(&tree["profiles"][1]["url"]));
+        assert_eq!(tree["profiles"][0]["url"], "https://api.example.com");
+        assert_eq!(tree["profiles"][1]["auth_token"], "");
+
+        assert_eq!(unseal(&mut tree, Some(&cipher)), 0);
+        assert_eq!(tree, settings());
+    }
+}
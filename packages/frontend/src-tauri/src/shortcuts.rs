@@ -0,0 +1,167 @@
+// System-wide keyboard shortcuts, kept in `settings.shortcuts` and registered
+// at startup. A few actions are handled here (showing the window, restarting
+// the backend, ...); every press, those included, is also sent to the
+// frontend as `global-shortcut`, so it can bind its own actions by name.
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, GlobalShortcutManager, Manager};
+
+use crate::backend;
+use crate::state::AppState;
+use crate::window;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Shortcut {
+    // Tauri accelerator syntax, e.g. "CmdOrCtrl+Shift+A"
+    pub accelerator: String,
+    pub action: String,
+}
+
+#[derive(Clone, Serialize)]
+struct Pressed<'a> {
+    accelerator: &'a str,
+    action: &'a str,
+}
+
+// "ctrl + shift+a" and "Ctrl+Shift+A" are the same keys
+fn same_keys(a: &str, b: &str) -> bool {
+    let normalize = |value: &str| {
+        value
+            .split('+')
+            .map(|key| key.trim().to_ascii_lowercase())
+            .collect::<Vec<_>>()
+    };
+    normalize(a) == normalize(b)
+}
+
+fn run(app: &AppHandle, action: &str) -> Result<(), String> {
+    match action {
+        "show_window" => {
+            window::activate(app);
+        }
+        "toggle_window" => {
+            let main = app
+                .get_window(window::MAIN)
+                .ok_or("Main window is not available")?;
+            let showing = main.is_visible().unwrap_or(false) && main.is_focused().unwrap_or(false);
+            if showing {
+                main.hide().map_err(|e| e.to_string())?;
+            } else {
+                window::activate(app);
+            }
+        }
+        "toggle_widget" => window::widget::toggle(app)?,
+        "open_log_console" => window::console::open(app)?,
+        "restart_backend" => backend::restart(app, "restart requested by shortcut")?,
+        // The frontend's own
+        _ => {}
+    }
+    Ok(())
+}
+
+fn bind(app: &AppHandle, shortcut: &Shortcut) -> Result<(), String> {
+    let handle = app.clone();
+    let (accelerator, action) = (shortcut.accelerator.clone(), shortcut.action.clone());
+    app.global_shortcut_manager()
+        .register(&shortcut.accelerator, move || {
+            let pressed = Pressed {
+                accelerator: &accelerator,
+                action: &action,
+            };
+            let _ = handle.emit_all("global-shortcut", pressed);
+            if let Err(e) = run(&handle, &action) {
+                tracing::warn!("Shortcut {} failed: {}", accelerator, e);
+            }
+        })
+        // Registration only fails for bad syntax or keys someone else holds
+        .map_err(|e| {
+            format!(
+                "Can't use {}; another application may already have it: {}",
+                shortcut.accelerator, e
+            )
+        })
+}
+
+// (Re)registers everything in settings; also how `set_setting` edits apply
+pub fn reload(app: &AppHandle) {
+    if let Err(e) = app.global_shortcut_manager().unregister_all() {
+        tracing::warn!("Failed to release global shortcuts: {}", e);
+    }
+    for shortcut in app.state::<AppState>().settings.get().shortcuts {
+        if let Err(e) = bind(app, &shortcut) {
+            tracing::warn!("{}", e);
+        }
+    }
+}
+
+pub fn register(app: &AppHandle, accelerator: &str, action: &str) -> Result<(), String> {
+    let (accelerator, action) = (accelerator.trim(), action.trim());
+    if accelerator.is_empty() || action.is_empty() {
+        return Err("A shortcut needs both keys and an action".to_string());
+    }
+    let state = app.state::<AppState>();
+    let shortcuts = state.settings.get().shortcuts;
+    if let Some(existing) = shortcuts
+        .iter()
+        .find(|s| same_keys(&s.accelerator, accelerator))
+    {
+        if existing.action == action {
+            return Ok(());
+        }
+        return Err(format!(
+            "{} is already bound to {}",
+            existing.accelerator, existing.action
+        ));
+    }
+    let shortcut = Shortcut {
+        accelerator: accelerator.to_string(),
+        action: action.to_string(),
+    };
+    bind(app, &shortcut)?;
+    let saved = state
+        .settings
+        .update(|s| s.shortcuts.push(shortcut.clone()));
+    if saved.is_err() {
+        let _ = app.global_shortcut_manager().unregister(accelerator);
+    }
+    saved
+}
+
+pub fn unregister(app: &AppHandle, accelerator: &str) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let shortcut = state
+        .settings
+        .get()
+        .shortcuts
+        .into_iter()
+        .find(|s| same_keys(&s.accelerator, accelerator))
+        .ok_or_else(|| format!("No shortcut is bound to {}", accelerator))?;
+    app.global_shortcut_manager()
+        .unregister(&shortcut.accelerator)
+        .map_err(|e| e.to_string())?;
+    state.settings.update(|s| {
+        s.shortcuts
+            .retain(|s| !same_keys(&s.accelerator, &shortcut.accelerator))
+    })
+}
+
+// Built-in actions: show_window, toggle_window, toggle_widget,
+// open_log_console, restart_backend; anything else is only forwarded
+#[tauri::command]
+pub async fn register_global_shortcut(
+    app: AppHandle,
+    accel: String,
+    action: String,
+) -> Result<(), String> {
+    register(&app, &accel, &action)
+}
+
+#[tauri::command]
+pub async fn unregister_global_shortcut(app: AppHandle, accel: String) -> Result<(), String> {
+    unregister(&app, &accel)
+}
+
+#[tauri::command]
+pub async fn list_global_shortcuts(app: AppHandle) -> Result<Vec<Shortcut>, String> {
+    Ok(app.state::<AppState>().settings.get().shortcuts)
+}
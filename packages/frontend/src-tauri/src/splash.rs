@@ -0,0 +1,101 @@
+// Splash window shown while the backend boots; swaps to the main window once
+// /status answers
+
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State};
+
+use crate::backend;
+use crate::i18n;
+use crate::state::AppState;
+use crate::window;
+
+const READY_TIMEOUT: Duration = Duration::from_secs(60);
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Phase {
+    Starting,
+    Error,
+}
+
+#[derive(Serialize)]
+struct SplashState {
+    phase: Phase,
+    message: String,
+}
+
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(wait_for_backend(app));
+}
+
+async fn wait_for_backend(app: AppHandle) {
+    let deadline = Instant::now() + READY_TIMEOUT;
+
+    set_state(&app, Phase::Starting, &i18n::text(&app, "splash.starting"));
+    loop {
+        let state = app.state::<AppState>();
+        let request = state
+            .http
+            .backend()
+            .get(format!("{}/status", state.backend.url()))
+            .headers(state.auth.headers(&app).await)
+            .timeout(REQUEST_TIMEOUT);
+        let ready = match request.send().await {
+            Ok(response) => response.status().is_success(),
+            Err(_) => false,
+        };
+        if ready {
+            show_main(&app);
+            return;
+        }
+        if Instant::now() >= deadline {
+            let message = i18n::text(&app, "splash.timeout");
+            set_state(&app, Phase::Error, &message);
+            let _ = app.emit_all("backend-start-failed", message);
+            return;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+// Push state into splash.html; it has no JS API bundle, so we drive it via eval
+fn set_state(app: &AppHandle, phase: Phase, message: &str) {
+    if let Some(splash) = app.get_window("splash") {
+        let state = SplashState {
+            phase,
+            message: message.to_string(),
+        };
+        if let Ok(json) = serde_json::to_string(&state) {
+            let _ = splash.eval(&format!(
+                "window.setSplashState && window.setSplashState({})",
+                json
+            ));
+        }
+    }
+}
+
+fn show_main(app: &AppHandle) {
+    window::activate(app);
+    if let Some(splash) = app.get_window("splash") {
+        let _ = splash.close();
+    }
+}
+
+// Called from the splash error state
+#[tauri::command]
+pub async fn splash_retry(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    if !state.backend.is_running() {
+        backend::spawn(&app)?;
+    }
+    start(app);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn splash_continue(app: AppHandle) {
+    show_main(&app);
+}
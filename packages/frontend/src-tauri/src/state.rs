@@ -0,0 +1,148 @@
+// Everything the shell keeps between commands, held in Tauri managed state
+
+use serde::Serialize;
+use tauri::{AppHandle, State};
+
+use crate::auth::TokenManager;
+use crate::backend::logs::BackendLogs;
+use crate::backend::metrics::ResourceMonitor;
+use crate::backend::BackendProcess;
+use crate::clipboard::Clipboard;
+use crate::deeplink::DeepLinks;
+use crate::documents::Documents;
+use crate::downloads::Downloads;
+use crate::health::{HealthMonitor, HealthSnapshot};
+use crate::http::HttpClient;
+use crate::idle::IdleLock;
+use crate::jobs::JobTracker;
+use crate::logging::frontend::FrontendErrors;
+use crate::notifications::badge::Badge;
+use crate::onboarding::Onboarding;
+use crate::power::Power;
+use crate::profiles;
+use crate::settings::{Settings, SettingsStore};
+use crate::storage::db::Database;
+use crate::storage::encrypted::EncryptedStore;
+use crate::storage::file_cache::FileCache;
+use crate::storage::search::SearchIndex;
+use crate::theme::ThemeWatcher;
+use crate::tray::custom::CustomMenu;
+use crate::updater::Updater;
+use crate::window::console::LogConsole;
+use crate::window::geometry::WindowStateStore;
+use crate::window::kiosk::KioskMode;
+use crate::workers::WorkerPool;
+
+pub struct AppState {
+    pub backend: BackendProcess,
+    pub logs: BackendLogs,
+    pub resources: ResourceMonitor,
+    pub health: HealthMonitor,
+    pub settings: SettingsStore,
+    pub http: HttpClient,
+    pub auth: TokenManager,
+    pub jobs: JobTracker,
+    pub tray_menu: CustomMenu,
+    pub windows: WindowStateStore,
+    pub console: LogConsole,
+    pub kiosk: KioskMode,
+    pub onboarding: Onboarding,
+    pub store: EncryptedStore,
+    pub db: Database,
+    pub search: SearchIndex,
+    pub idle: IdleLock,
+    pub updater: Updater,
+    pub badge: Badge,
+    pub links: DeepLinks,
+    pub documents: Documents,
+    pub clipboard: Clipboard,
+    pub theme: ThemeWatcher,
+    pub power: Power,
+    pub downloads: Downloads,
+    pub file_cache: FileCache,
+    pub frontend_errors: FrontendErrors,
+    pub workers: WorkerPool,
+}
+
+impl AppState {
+    pub fn new(app: &AppHandle, port: u16) -> Result<Self, String> {
+        let config_dir = app
+            .path_resolver()
+            .app_config_dir()
+            .ok_or("no app config directory")?;
+        let onboarding = Onboarding::detect(&config_dir);
+        let settings = SettingsStore::load(config_dir.clone());
+        let windows = WindowStateStore::load(config_dir);
+        // Loading TLS roots and listing the file cache both wait on the disk;
+        // neither needs the other
+        let (http, file_cache) = std::thread::scope(|scope| {
+            let file_cache = scope.spawn(|| FileCache::open(app.path_resolver().app_cache_dir()));
+            let http = HttpClient::new(app, &settings.get());
+            (http, file_cache.join().expect("file cache scan panicked"))
+        });
+        let store = EncryptedStore::new(app.path_resolver().app_local_data_dir());
+        // Both open on first use, see `storage::warm_up`
+        let db = Database::new(app.path_resolver().app_local_data_dir());
+        let search = SearchIndex::new(app, app.path_resolver().app_local_data_dir());
+        Ok(Self {
+            backend: BackendProcess::new(port),
+            logs: BackendLogs::default(),
+            resources: ResourceMonitor::default(),
+            health: HealthMonitor::default(),
+            settings,
+            http,
+            auth: TokenManager::default(),
+            jobs: JobTracker::default(),
+            tray_menu: CustomMenu::default(),
+            windows,
+            console: LogConsole::default(),
+            kiosk: KioskMode::default(),
+            onboarding,
+            store,
+            db,
+            search,
+            idle: IdleLock::default(),
+            updater: Updater::default(),
+            badge: Badge::default(),
+            links: DeepLinks::default(),
+            documents: Documents::default(),
+            clipboard: Clipboard::default(),
+            theme: ThemeWatcher::default(),
+            power: Power::default(),
+            downloads: Downloads::default(),
+            file_cache,
+            frontend_errors: FrontendErrors::default(),
+            workers: WorkerPool::default(),
+        })
+    }
+}
+
+#[derive(Serialize)]
+pub struct AppStateSummary {
+    pub app_version: String,
+    pub backend_url: String,
+    pub backend_attached: bool,
+    pub backend_pid: Option<u32>,
+    pub active_profile: String,
+    pub health: HealthSnapshot,
+}
+
+#[tauri::command]
+pub async fn get_app_state(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<AppStateSummary, String> {
+    Ok(AppStateSummary {
+        app_version: app.package_info().version.to_string(),
+        backend_url: state.backend.url(),
+        backend_attached: state.backend.is_attached(),
+        backend_pid: state.backend.pid(),
+        active_profile: profiles::active_name(&state.settings.get()),
+        health: state.health.snapshot(),
+    })
+}
+
+#[tauri::command]
+pub async fn get_config(state: State<'_, AppState>) -> Result<Settings, String> {
+    Ok(state.settings.get())
+}
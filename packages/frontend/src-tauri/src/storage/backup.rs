@@ -0,0 +1,278 @@
+// Scheduled copies of the settings file and the SQLite database into a folder
+// the user picks (`backup.dir`), keeping the newest `backup.keep`. Each backup
+// is a folder of its own, written under a temporary name and renamed once
+// complete, with a manifest of checksums; `restore_backup` checks all of it
+// before touching anything. The encrypted cache and the search index aren't
+// included: the first is tied to this machine's keychain and the second is
+// rebuilt from the database.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager};
+
+use super::blocking;
+use super::db::Database;
+use crate::settings::apply;
+use crate::state::AppState;
+use crate::util::now_millis;
+
+const PREFIX: &str = "aetherframe-backup-";
+const MANIFEST: &str = "manifest.json";
+const SETTINGS_FILE: &str = "settings.json";
+const DB_FILE: &str = "aetherframe.db";
+const FORMAT: u32 = 1;
+// How often the schedule is looked at; the interval itself is in settings
+const CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackupSettings {
+    pub enabled: bool,
+    pub dir: Option<PathBuf>,
+    pub interval_hours: u64,
+    // Older backups beyond this are deleted
+    pub keep: usize,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: None,
+            interval_hours: 24,
+            keep: 5,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    format: u32,
+    app_version: String,
+    created_at: u64,
+    // File name -> SHA-256
+    files: BTreeMap<String, String>,
+}
+
+#[derive(Serialize)]
+pub struct BackupInfo {
+    pub path: PathBuf,
+    pub app_version: String,
+    pub created_at: u64,
+}
+
+fn sha256(path: &Path) -> Result<String, String> {
+    let bytes = fs::read(path).map_err(|e| format!("Can't read {}: {}", path.display(), e))?;
+    Ok(Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+fn read_manifest(backup: &Path) -> Result<Manifest, String> {
+    let raw = fs::read_to_string(backup.join(MANIFEST))
+        .map_err(|_| format!("{} is not a backup", backup.display()))?;
+    let manifest: Manifest =
+        serde_json::from_str(&raw).map_err(|e| format!("Invalid backup manifest: {}", e))?;
+    if manifest.format != FORMAT {
+        return Err(format!(
+            "{} was made by an incompatible version of the app",
+            backup.display()
+        ));
+    }
+    Ok(manifest)
+}
+
+// Newest first
+fn list(dir: &Path) -> Vec<BackupInfo> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<BackupInfo> = entries
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(PREFIX))
+        .filter_map(|entry| {
+            let manifest = read_manifest(&entry.path()).ok()?;
+            Some(BackupInfo {
+                path: entry.path(),
+                app_version: manifest.app_version,
+                created_at: manifest.created_at,
+            })
+        })
+        .collect();
+    backups.sort_by_key(|backup| std::cmp::Reverse(backup.created_at));
+    backups
+}
+
+fn rotate(dir: &Path, keep: usize) {
+    for old in list(dir).into_iter().skip(keep.max(1)) {
+        if let Err(e) = fs::remove_dir_all(&old.path) {
+            tracing::warn!("Failed to remove old backup {}: {}", old.path.display(), e);
+        }
+    }
+}
+
+fn create(
+    app: &AppHandle,
+    state: &AppState,
+    dir: &Path,
+    keep: usize,
+) -> Result<BackupInfo, String> {
+    let created_at = now_millis();
+    let name = format!("{}{}", PREFIX, chrono::Utc::now().format("%Y%m%d-%H%M%S"));
+    let target = dir.join(&name);
+    // Left behind by a crash mid-backup, it's never mistaken for a real one
+    let partial = dir.join(format!(".{}.tmp", name));
+    let _ = fs::remove_dir_all(&partial);
+    fs::create_dir_all(&partial)
+        .map_err(|e| format!("Can't create {}: {}", partial.display(), e))?;
+
+    let written = (|| {
+        let mut files = BTreeMap::new();
+        // The file as stored, so sealed secrets stay sealed
+        let settings = state.settings.path();
+        if settings.exists() {
+            fs::copy(settings, partial.join(SETTINGS_FILE)).map_err(|e| e.to_string())?;
+            files.insert(
+                SETTINGS_FILE.to_string(),
+                sha256(&partial.join(SETTINGS_FILE))?,
+            );
+        }
+        state.db.backup_to(&partial.join(DB_FILE))?;
+        files.insert(DB_FILE.to_string(), sha256(&partial.join(DB_FILE))?);
+        let manifest = Manifest {
+            format: FORMAT,
+            app_version: app.package_info().version.to_string(),
+            created_at,
+            files,
+        };
+        let json = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+        fs::write(partial.join(MANIFEST), json).map_err(|e| e.to_string())?;
+        fs::rename(&partial, &target).map_err(|e| e.to_string())
+    })();
+    if let Err(e) = written {
+        let _ = fs::remove_dir_all(&partial);
+        return Err(format!("Backup failed: {}", e));
+    }
+    rotate(dir, keep);
+    Ok(BackupInfo {
+        path: target,
+        app_version: app.package_info().version.to_string(),
+        created_at,
+    })
+}
+
+fn due(state: &AppState) -> Option<(PathBuf, usize)> {
+    let settings = state.settings.get().backup;
+    let dir = settings.dir.filter(|_| settings.enabled)?;
+    let interval = Duration::from_secs(settings.interval_hours.max(1) * 60 * 60);
+    let last = list(&dir).first().map_or(0, |backup| backup.created_at);
+    let due = now_millis().saturating_sub(last) >= interval.as_millis() as u64;
+    due.then_some((dir, settings.keep))
+}
+
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if app.state::<AppState>().backend.is_stopping() {
+                break;
+            }
+            let handle = app.clone();
+            let result = blocking(app.clone(), move |state| match due(state) {
+                Some((dir, keep)) => create(&handle, state, &dir, keep).map(Some),
+                None => Ok(None),
+            })
+            .await;
+            match result {
+                Ok(Some(backup)) => {
+                    tracing::info!("Backed up app data to {}", backup.path.display())
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!("{}", e),
+            }
+            app.state::<AppState>().power.pause(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+// Everything in the backup must match its manifest, and the database must be
+// sound and not from a newer app, before anything is replaced
+fn validate(backup: &Path) -> Result<Manifest, String> {
+    let manifest = read_manifest(backup)?;
+    if !manifest.files.contains_key(DB_FILE) {
+        return Err(format!("{} has no database", backup.display()));
+    }
+    for (name, expected) in &manifest.files {
+        if sha256(&backup.join(name))? != *expected {
+            return Err(format!("{} in {} is damaged", name, backup.display()));
+        }
+    }
+    if manifest.files.contains_key(SETTINGS_FILE) {
+        let raw = fs::read_to_string(backup.join(SETTINGS_FILE)).map_err(|e| e.to_string())?;
+        serde_json::from_str::<serde_json::Value>(&raw)
+            .map_err(|e| format!("The backed up settings are unreadable: {}", e))?;
+    }
+    Database::check_file(&backup.join(DB_FILE))?;
+    Ok(manifest)
+}
+
+fn restore(state: &AppState, backup: &Path) -> Result<Manifest, String> {
+    let manifest = validate(backup)?;
+    state.db.restore_from(&backup.join(DB_FILE))?;
+    state.search.reindex_results(&state.db);
+    if manifest.files.contains_key(SETTINGS_FILE) {
+        let raw = fs::read_to_string(backup.join(SETTINGS_FILE)).map_err(|e| e.to_string())?;
+        state.settings.restore(&raw)?;
+    }
+    Ok(manifest)
+}
+
+// Needs `backup.dir`; runs whether or not scheduled backups are on
+#[tauri::command]
+pub async fn backup_now(app: AppHandle) -> Result<BackupInfo, String> {
+    let handle = app.clone();
+    blocking(app, move |state| {
+        let settings = state.settings.get().backup;
+        let dir = settings
+            .dir
+            .ok_or("Choose a backup folder first (backup.dir)")?;
+        create(&handle, state, &dir, settings.keep)
+    })
+    .await
+}
+
+// Newest first
+#[tauri::command]
+pub async fn list_backups(app: AppHandle) -> Result<Vec<BackupInfo>, String> {
+    blocking(app, |state| {
+        Ok(state
+            .settings
+            .get()
+            .backup
+            .dir
+            .map(|dir| list(&dir))
+            .unwrap_or_default())
+    })
+    .await
+}
+
+// `path` is one backup's folder. Settings take effect at once, as after an
+// import; windows are told with `backup-restored`
+#[tauri::command]
+pub async fn restore_backup(app: AppHandle, path: PathBuf) -> Result<BackupInfo, String> {
+    let backup = path.clone();
+    let manifest = blocking(app.clone(), move |state| restore(state, &backup)).await?;
+    apply::apply_all(&app);
+    let info = BackupInfo {
+        path,
+        app_version: manifest.app_version,
+        created_at: manifest.created_at,
+    };
+    let _ = app.emit_all("backup-restored", &info);
+    Ok(info)
+}
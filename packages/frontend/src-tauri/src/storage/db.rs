@@ -0,0 +1,581 @@
+// SQLite database for the structured data the frontend would otherwise keep in
+// localStorage: job history, favorites and cached backend results, plus the
+// shell's own offline request queue (see `http::offline`) and downloads. The schema
+// is brought up to date at startup by running the `MIGRATIONS` the file hasn't
+// seen yet, counted in SQLite's `user_version`. Migrations only ever get
+// appended; one that has shipped is never edited.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::Duration;
+
+use rusqlite::{params, Connection, DatabaseName, OpenFlags, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::AppHandle;
+
+use super::blocking;
+use crate::util::now_millis;
+
+const FILE_NAME: &str = "aetherframe.db";
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+const DEFAULT_HISTORY_LIMIT: u32 = 100;
+
+const MIGRATIONS: &[&str] = &[
+    // 1: initial schema
+    "CREATE TABLE job_history (
+        id INTEGER PRIMARY KEY,
+        target TEXT NOT NULL,
+        status TEXT NOT NULL,
+        started_at INTEGER,
+        finished_at INTEGER,
+        summary TEXT,
+        updated_at INTEGER NOT NULL
+    );
+    CREATE INDEX job_history_updated ON job_history (updated_at);
+    CREATE TABLE favorites (
+        kind TEXT NOT NULL,
+        target TEXT NOT NULL,
+        label TEXT NOT NULL,
+        created_at INTEGER NOT NULL,
+        PRIMARY KEY (kind, target)
+    );
+    CREATE TABLE backend_results (
+        key TEXT PRIMARY KEY,
+        value TEXT,
+        stored_at INTEGER NOT NULL,
+        expires_at INTEGER
+    );",
+    // 2: requests held back while the backend was unreachable
+    "CREATE TABLE offline_queue (
+        seq INTEGER PRIMARY KEY AUTOINCREMENT,
+        id TEXT NOT NULL UNIQUE,
+        method TEXT NOT NULL,
+        path TEXT NOT NULL,
+        body TEXT,
+        headers TEXT NOT NULL,
+        queued_at INTEGER NOT NULL
+    );",
+    // 3: the download manager's items, as `downloads::Download` JSON
+    "CREATE TABLE downloads (
+        id TEXT PRIMARY KEY,
+        record TEXT NOT NULL,
+        created_at INTEGER NOT NULL
+    );",
+    // 4: the profile a queued request was meant for; NULL for ones queued
+    // before this was recorded
+    "ALTER TABLE offline_queue ADD COLUMN profile TEXT;",
+];
+
+#[derive(Serialize, Deserialize)]
+pub struct JobRecord {
+    pub id: u64,
+    pub target: String,
+    pub status: String,
+    pub started_at: Option<u64>,
+    pub finished_at: Option<u64>,
+    // Whatever the frontend wants to show later, e.g. verdict and counts
+    pub summary: Option<Value>,
+    // Set when saved
+    #[serde(default)]
+    pub updated_at: u64,
+}
+
+impl JobRecord {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get("id")?,
+            target: row.get("target")?,
+            status: row.get("status")?,
+            started_at: row.get("started_at")?,
+            finished_at: row.get("finished_at")?,
+            summary: row.get("summary")?,
+            updated_at: row.get("updated_at")?,
+        })
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct QueuedRequest {
+    pub id: String,
+    pub method: String,
+    pub path: String,
+    pub body: Option<Value>,
+    pub headers: HashMap<String, String>,
+    pub queued_at: u64,
+    pub profile: Option<String>,
+}
+
+impl QueuedRequest {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        let headers: Value = row.get("headers")?;
+        Ok(Self {
+            id: row.get("id")?,
+            method: row.get("method")?,
+            path: row.get("path")?,
+            body: row.get("body")?,
+            headers: serde_json::from_value(headers).unwrap_or_default(),
+            queued_at: row.get("queued_at")?,
+            profile: row.get("profile")?,
+        })
+    }
+}
+
+#[derive(Serialize)]
+pub struct Favorite {
+    pub kind: String,
+    pub target: String,
+    pub label: String,
+    pub created_at: u64,
+}
+
+impl Favorite {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            kind: row.get("kind")?,
+            target: row.get("target")?,
+            label: row.get("label")?,
+            created_at: row.get("created_at")?,
+        })
+    }
+}
+
+fn db_error(e: impl std::fmt::Display) -> String {
+    format!("Database error: {}", e)
+}
+
+fn migrate(conn: &mut Connection) -> Result<(), String> {
+    let version: usize = conn
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(db_error)?;
+    if version > MIGRATIONS.len() {
+        return Err(format!(
+            "The database is from a newer version of the app (schema {}, expected {})",
+            version,
+            MIGRATIONS.len()
+        ));
+    }
+    for (index, sql) in MIGRATIONS.iter().enumerate().skip(version) {
+        let tx = conn.transaction().map_err(db_error)?;
+        tx.execute_batch(sql)
+            .map_err(|e| format!("Database migration {} failed: {}", index + 1, e))?;
+        tx.pragma_update(None, "user_version", index + 1)
+            .map_err(db_error)?;
+        tx.commit().map_err(db_error)?;
+    }
+    Ok(())
+}
+
+fn open(path: &PathBuf) -> Result<Connection, String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let mut conn = Connection::open(path).map_err(db_error)?;
+    conn.busy_timeout(BUSY_TIMEOUT).map_err(db_error)?;
+    // WAL keeps reads going while a write is in progress
+    conn.pragma_update(None, "journal_mode", "WAL")
+        .map_err(db_error)?;
+    migrate(&mut conn)?;
+    let expired = conn
+        .execute(
+            "DELETE FROM backend_results WHERE expires_at <= ?1",
+            [now_millis()],
+        )
+        .map_err(db_error)?;
+    if expired > 0 {
+        tracing::info!("Removed {} expired backend results", expired);
+    }
+    Ok(conn)
+}
+
+// A database that fails to open or migrate only disables these commands; the
+// reason is kept so each of them can report it. It's opened (and migrated) by
+// whatever needs it first, or by `storage::warm_up` once the window is up, so
+// startup doesn't wait on the disk.
+pub struct Database {
+    dir: Option<PathBuf>,
+    conn: OnceLock<Result<Mutex<Connection>, String>>,
+}
+
+impl Database {
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        Self {
+            dir,
+            conn: OnceLock::new(),
+        }
+    }
+
+    fn opened(&self) -> &Result<Mutex<Connection>, String> {
+        self.conn.get_or_init(|| {
+            let conn = self
+                .dir
+                .as_ref()
+                .ok_or_else(|| "No app data directory".to_string())
+                .and_then(|dir| open(&dir.join(FILE_NAME)));
+            if let Err(e) = &conn {
+                tracing::error!("Failed to open the database: {}", e);
+            }
+            conn.map(Mutex::new)
+        })
+    }
+
+    pub fn warm_up(&self) {
+        let _ = self.opened();
+    }
+
+    // A consistent copy, even with writes going on
+    pub fn backup_to(&self, path: &Path) -> Result<(), String> {
+        self.conn()?
+            .execute("VACUUM INTO ?1", [path.to_string_lossy()])
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    // Whether `path` is a sound database this version can use
+    pub fn check_file(path: &Path) -> Result<(), String> {
+        let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(db_error)?;
+        let integrity: String = conn
+            .query_row("PRAGMA integrity_check", [], |row| row.get(0))
+            .map_err(db_error)?;
+        if integrity != "ok" {
+            return Err(format!("The database is damaged: {}", integrity));
+        }
+        let version: usize = conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .map_err(db_error)?;
+        if version > MIGRATIONS.len() {
+            return Err("The database is from a newer version of the app".to_string());
+        }
+        Ok(())
+    }
+
+    // Swaps the contents in under the open connection, then brings an older
+    // copy's schema up to date
+    pub fn restore_from(&self, path: &Path) -> Result<(), String> {
+        let mut conn = self.conn()?;
+        conn.restore(
+            DatabaseName::Main,
+            path,
+            None::<fn(rusqlite::backup::Progress)>,
+        )
+        .map_err(db_error)?;
+        migrate(&mut conn)
+    }
+
+    fn conn(&self) -> Result<MutexGuard<'_, Connection>, String> {
+        match self.opened() {
+            Ok(conn) => Ok(conn.lock().unwrap()),
+            Err(e) => Err(e.clone()),
+        }
+    }
+
+    pub fn record_job(&self, mut job: JobRecord) -> Result<(), String> {
+        job.updated_at = now_millis();
+        self.conn()?
+            .execute(
+                "INSERT INTO job_history
+                    (id, target, status, started_at, finished_at, summary, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                 ON CONFLICT (id) DO UPDATE SET
+                    target = excluded.target,
+                    status = excluded.status,
+                    started_at = coalesce(excluded.started_at, started_at),
+                    finished_at = coalesce(excluded.finished_at, finished_at),
+                    summary = coalesce(excluded.summary, summary),
+                    updated_at = excluded.updated_at",
+                params![
+                    job.id,
+                    job.target,
+                    job.status,
+                    job.started_at,
+                    job.finished_at,
+                    job.summary,
+                    job.updated_at
+                ],
+            )
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    // Most recently updated first; `before` continues from an earlier page
+    pub fn job_history(&self, limit: u32, before: Option<u64>) -> Result<Vec<JobRecord>, String> {
+        let conn = self.conn()?;
+        let mut statement = conn
+            .prepare(
+                "SELECT * FROM job_history
+                 WHERE ?1 IS NULL OR updated_at < ?1
+                 ORDER BY updated_at DESC LIMIT ?2",
+            )
+            .map_err(db_error)?;
+        let rows = statement
+            .query_map(params![before, limit], JobRecord::from_row)
+            .map_err(db_error)?;
+        rows.collect::<Result<_, _>>().map_err(db_error)
+    }
+
+    // Without an id, deletes the whole history
+    pub fn delete_jobs(&self, id: Option<u64>) -> Result<usize, String> {
+        self.conn()?
+            .execute("DELETE FROM job_history WHERE ?1 IS NULL OR id = ?1", [id])
+            .map_err(db_error)
+    }
+
+    // Adding an existing favorite only renames it
+    pub fn add_favorite(&self, kind: &str, target: &str, label: &str) -> Result<(), String> {
+        self.conn()?
+            .execute(
+                "INSERT INTO favorites (kind, target, label, created_at) VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT (kind, target) DO UPDATE SET label = excluded.label",
+                params![kind, target, label, now_millis()],
+            )
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    pub fn remove_favorite(&self, kind: &str, target: &str) -> Result<bool, String> {
+        let removed = self
+            .conn()?
+            .execute(
+                "DELETE FROM favorites WHERE kind = ?1 AND target = ?2",
+                [kind, target],
+            )
+            .map_err(db_error)?;
+        Ok(removed > 0)
+    }
+
+    // Oldest first
+    pub fn favorites(&self, kind: Option<&str>) -> Result<Vec<Favorite>, String> {
+        let conn = self.conn()?;
+        let mut statement = conn
+            .prepare(
+                "SELECT * FROM favorites WHERE ?1 IS NULL OR kind = ?1
+                 ORDER BY created_at, rowid",
+            )
+            .map_err(db_error)?;
+        let rows = statement
+            .query_map([kind], Favorite::from_row)
+            .map_err(db_error)?;
+        rows.collect::<Result<_, _>>().map_err(db_error)
+    }
+
+    // Returns when it expires
+    pub fn put_result(
+        &self,
+        key: &str,
+        value: &Value,
+        ttl_secs: Option<u64>,
+    ) -> Result<Option<u64>, String> {
+        let stored_at = now_millis();
+        let expires_at = ttl_secs.map(|ttl| stored_at + ttl * 1000);
+        self.conn()?
+            .execute(
+                "INSERT OR REPLACE INTO backend_results (key, value, stored_at, expires_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![key, value, stored_at, expires_at],
+            )
+            .map_err(db_error)?;
+        Ok(expires_at)
+    }
+
+    // Key, value, stored_at and expires_at of every live result
+    #[allow(clippy::type_complexity)]
+    pub fn results(&self) -> Result<Vec<(String, Value, u64, Option<u64>)>, String> {
+        let conn = self.conn()?;
+        let mut statement = conn
+            .prepare(
+                "SELECT key, value, stored_at, expires_at FROM backend_results
+                 WHERE expires_at IS NULL OR expires_at > ?1",
+            )
+            .map_err(db_error)?;
+        let rows = statement
+            .query_map([now_millis()], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })
+            .map_err(db_error)?;
+        rows.collect::<Result<_, _>>().map_err(db_error)
+    }
+
+    // Expired results read as missing and are removed at the next startup
+    pub fn get_result(&self, key: &str) -> Result<Option<Value>, String> {
+        self.conn()?
+            .query_row(
+                "SELECT value FROM backend_results
+                 WHERE key = ?1 AND (expires_at IS NULL OR expires_at > ?2)",
+                params![key, now_millis()],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(db_error)
+    }
+
+    // Keys starting with `prefix`, or all of them; returns the keys removed
+    pub fn clear_results(&self, prefix: Option<&str>) -> Result<Vec<String>, String> {
+        let conn = self.conn()?;
+        let mut statement = conn
+            .prepare(
+                "DELETE FROM backend_results WHERE ?1 IS NULL OR substr(key, 1, length(?1)) = ?1
+                 RETURNING key",
+            )
+            .map_err(db_error)?;
+        let rows = statement
+            .query_map([prefix], |row| row.get(0))
+            .map_err(db_error)?;
+        rows.collect::<Result<_, _>>().map_err(db_error)
+    }
+
+    // Returns how many requests for the same profile were already waiting
+    // ahead of this one
+    pub fn enqueue(&self, request: &QueuedRequest) -> Result<usize, String> {
+        let conn = self.conn()?;
+        let headers = serde_json::to_value(&request.headers).map_err(|e| e.to_string())?;
+        conn.execute(
+            "INSERT INTO offline_queue (id, method, path, body, headers, queued_at, profile)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                request.id,
+                request.method,
+                request.path,
+                request.body,
+                headers,
+                request.queued_at,
+                request.profile
+            ],
+        )
+        .map_err(db_error)?;
+        conn.query_row(
+            "SELECT count(*) - 1 FROM offline_queue WHERE profile IS ?1",
+            [&request.profile],
+            |row| row.get(0),
+        )
+        .map_err(db_error)
+    }
+
+    // In the order they were queued
+    pub fn queued(&self) -> Result<Vec<QueuedRequest>, String> {
+        let conn = self.conn()?;
+        let mut statement = conn
+            .prepare("SELECT * FROM offline_queue ORDER BY seq")
+            .map_err(db_error)?;
+        let rows = statement
+            .query_map([], QueuedRequest::from_row)
+            .map_err(db_error)?;
+        rows.collect::<Result<_, _>>().map_err(db_error)
+    }
+
+    pub fn save_download(&self, id: &str, record: Value, created_at: u64) -> Result<(), String> {
+        self.conn()?
+            .execute(
+                "INSERT INTO downloads (id, record, created_at) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (id) DO UPDATE SET record = excluded.record",
+                params![id, record, created_at],
+            )
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    // Oldest first
+    pub fn downloads(&self) -> Result<Vec<Value>, String> {
+        let conn = self.conn()?;
+        let mut statement = conn
+            .prepare("SELECT record FROM downloads ORDER BY created_at, rowid")
+            .map_err(db_error)?;
+        let rows = statement
+            .query_map([], |row| row.get(0))
+            .map_err(db_error)?;
+        rows.collect::<Result<_, _>>().map_err(db_error)
+    }
+
+    pub fn remove_download(&self, id: &str) -> Result<(), String> {
+        self.conn()?
+            .execute("DELETE FROM downloads WHERE id = ?1", [id])
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    pub fn dequeue(&self, id: &str) -> Result<bool, String> {
+        let removed = self
+            .conn()?
+            .execute("DELETE FROM offline_queue WHERE id = ?1", [id])
+            .map_err(db_error)?;
+        Ok(removed > 0)
+    }
+}
+
+#[tauri::command]
+pub async fn record_job(app: AppHandle, job: JobRecord) -> Result<(), String> {
+    blocking(app, move |state| state.db.record_job(job)).await
+}
+
+#[tauri::command]
+pub async fn list_job_history(
+    app: AppHandle,
+    limit: Option<u32>,
+    before: Option<u64>,
+) -> Result<Vec<JobRecord>, String> {
+    let limit = limit.unwrap_or(DEFAULT_HISTORY_LIMIT);
+    blocking(app, move |state| state.db.job_history(limit, before)).await
+}
+
+// Without an id, clears the whole history; returns how many were deleted
+#[tauri::command]
+pub async fn delete_job_history(app: AppHandle, id: Option<u64>) -> Result<usize, String> {
+    blocking(app, move |state| state.db.delete_jobs(id)).await
+}
+
+#[tauri::command]
+pub async fn add_favorite(
+    app: AppHandle,
+    kind: String,
+    target: String,
+    label: String,
+) -> Result<(), String> {
+    blocking(app, move |state| {
+        state.db.add_favorite(&kind, &target, &label)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn remove_favorite(app: AppHandle, kind: String, target: String) -> Result<bool, String> {
+    blocking(app, move |state| state.db.remove_favorite(&kind, &target)).await
+}
+
+#[tauri::command]
+pub async fn list_favorites(app: AppHandle, kind: Option<String>) -> Result<Vec<Favorite>, String> {
+    blocking(app, move |state| state.db.favorites(kind.as_deref())).await
+}
+
+#[tauri::command]
+pub async fn save_backend_result(
+    app: AppHandle,
+    key: String,
+    value: Value,
+    ttl_secs: Option<u64>,
+) -> Result<(), String> {
+    blocking(app, move |state| {
+        let expires_at = state.db.put_result(&key, &value, ttl_secs)?;
+        state.search.index_result(&key, &value, expires_at);
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn get_backend_result(app: AppHandle, key: String) -> Result<Option<Value>, String> {
+    blocking(app, move |state| state.db.get_result(&key)).await
+}
+
+#[tauri::command]
+pub async fn clear_backend_results(
+    app: AppHandle,
+    prefix: Option<String>,
+) -> Result<usize, String> {
+    blocking(app, move |state| {
+        let removed = state.db.clear_results(prefix.as_deref())?;
+        state.search.remove_results(&removed);
+        Ok(removed.len())
+    })
+    .await
+}
@@ -0,0 +1,274 @@
+// Encrypted key-value store for cached backend data and history. Every record
+// is sealed with ChaCha20-Poly1305 and looked up by an HMAC of its namespace
+// and key, so the file reveals neither content nor names. The master key lives
+// in the OS keychain; without it the database is unreadable.
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use hmac::{Hmac, Mac};
+use redb::{Database, ReadableTable, TableDefinition};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+
+use crate::secrets;
+use crate::util::now_millis;
+
+const FILE_NAME: &str = "cache.redb";
+const KEYCHAIN_ENTRY: &str = "cache-db-key";
+const NONCE_LEN: usize = 12;
+// Key id -> nonce || ciphertext
+const ENTRIES: TableDefinition<&[u8], &[u8]> = TableDefinition::new("entries");
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Serialize, Deserialize)]
+struct Record {
+    namespace: String,
+    key: String,
+    value: Value,
+    stored_at: u64,
+    expires_at: Option<u64>,
+}
+
+impl Record {
+    fn expired(&self, now: u64) -> bool {
+        self.expires_at.is_some_and(|at| at <= now)
+    }
+}
+
+#[derive(Serialize)]
+pub struct CacheEntry {
+    pub key: String,
+    pub value: Value,
+    pub stored_at: u64,
+    pub expires_at: Option<u64>,
+}
+
+struct Opened {
+    db: Database,
+    cipher: ChaCha20Poly1305,
+    // Separate from the cipher key so ids can't be used to attack it
+    id_key: [u8; 32],
+}
+
+impl Opened {
+    fn id(&self, namespace: &str, key: &str) -> Vec<u8> {
+        let mut mac = <HmacSha256 as Mac>::new_from_slice(&self.id_key).expect("any key length");
+        mac.update(namespace.as_bytes());
+        mac.update(&[0]);
+        mac.update(key.as_bytes());
+        mac.finalize().into_bytes().to_vec()
+    }
+
+    // The id is bound in as associated data, so a record can't be swapped
+    // under another key without failing to decrypt
+    fn seal(&self, id: &[u8], record: &Record) -> Result<Vec<u8>, String> {
+        let plain = serde_json::to_vec(record).map_err(|e| e.to_string())?;
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let sealed = self
+            .cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: &plain,
+                    aad: id,
+                },
+            )
+            .map_err(|e| format!("Failed to encrypt cache entry: {}", e))?;
+        let mut blob = nonce.to_vec();
+        blob.extend(sealed);
+        Ok(blob)
+    }
+
+    fn open(&self, id: &[u8], blob: &[u8]) -> Option<Record> {
+        if blob.len() < NONCE_LEN {
+            return None;
+        }
+        let (nonce, sealed) = blob.split_at(NONCE_LEN);
+        let payload = Payload {
+            msg: sealed,
+            aad: id,
+        };
+        let plain = self
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), payload)
+            .ok()?;
+        serde_json::from_slice(&plain).ok()
+    }
+
+    // Drop expired entries, plus any that no longer decrypt (e.g. the
+    // keychain entry was reset)
+    fn purge(&self) -> Result<usize, String> {
+        let now = now_millis();
+        let tx = self.db.begin_write().map_err(db_error)?;
+        let mut removed = 0;
+        {
+            let mut table = tx.open_table(ENTRIES).map_err(db_error)?;
+            table
+                .retain(|id, blob| {
+                    let keep = self.open(id, blob).is_some_and(|r| !r.expired(now));
+                    removed += usize::from(!keep);
+                    keep
+                })
+                .map_err(db_error)?;
+        }
+        tx.commit().map_err(db_error)?;
+        Ok(removed)
+    }
+}
+
+fn db_error(e: impl std::fmt::Display) -> String {
+    format!("Cache database error: {}", e)
+}
+
+// Derive independent subkeys from the one stored in the keychain
+fn subkey(master: &[u8; 32], purpose: &str) -> [u8; 32] {
+    let mut mac = <HmacSha256 as Mac>::new_from_slice(master).expect("any key length");
+    mac.update(purpose.as_bytes());
+    mac.finalize().into_bytes().into()
+}
+
+// Opened on first use, so a locked keychain doesn't hold up startup
+pub struct EncryptedStore {
+    path: Option<PathBuf>,
+    opened: Mutex<Option<Arc<Opened>>>,
+}
+
+impl EncryptedStore {
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        Self {
+            path: dir.map(|dir| dir.join(FILE_NAME)),
+            opened: Mutex::new(None),
+        }
+    }
+
+    fn opened(&self) -> Result<Arc<Opened>, String> {
+        let mut opened = self.opened.lock().unwrap();
+        if let Some(opened) = opened.as_ref() {
+            return Ok(opened.clone());
+        }
+        let path = self.path.as_ref().ok_or("No app data directory")?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let master = secrets::key(KEYCHAIN_ENTRY)?;
+        let db = Database::create(path).map_err(db_error)?;
+        let created = Arc::new(Opened {
+            db,
+            cipher: ChaCha20Poly1305::new(Key::from_slice(&subkey(&master, "cipher"))),
+            id_key: subkey(&master, "id"),
+        });
+        // Also creates the table on a fresh file
+        let removed = created.purge()?;
+        if removed > 0 {
+            tracing::info!("Removed {} expired or unreadable cache entries", removed);
+        }
+        *opened = Some(created.clone());
+        Ok(created)
+    }
+
+    pub fn put(
+        &self,
+        namespace: &str,
+        key: &str,
+        value: Value,
+        ttl_secs: Option<u64>,
+    ) -> Result<(), String> {
+        let opened = self.opened()?;
+        let stored_at = now_millis();
+        let record = Record {
+            namespace: namespace.to_string(),
+            key: key.to_string(),
+            value,
+            stored_at,
+            expires_at: ttl_secs.map(|ttl| stored_at + ttl * 1000),
+        };
+        let id = opened.id(namespace, key);
+        let blob = opened.seal(&id, &record)?;
+        let tx = opened.db.begin_write().map_err(db_error)?;
+        {
+            let mut table = tx.open_table(ENTRIES).map_err(db_error)?;
+            table
+                .insert(id.as_slice(), blob.as_slice())
+                .map_err(db_error)?;
+        }
+        tx.commit().map_err(db_error)
+    }
+
+    pub fn get(&self, namespace: &str, key: &str) -> Result<Option<Value>, String> {
+        let opened = self.opened()?;
+        let id = opened.id(namespace, key);
+        let tx = opened.db.begin_read().map_err(db_error)?;
+        let table = tx.open_table(ENTRIES).map_err(db_error)?;
+        let blob = table.get(id.as_slice()).map_err(db_error)?;
+        // Expired entries are left for the next purge
+        Ok(blob
+            .and_then(|blob| opened.open(&id, blob.value()))
+            .filter(|record| !record.expired(now_millis()))
+            .map(|record| record.value))
+    }
+
+    pub fn delete(&self, namespace: &str, key: &str) -> Result<(), String> {
+        let opened = self.opened()?;
+        let id = opened.id(namespace, key);
+        let tx = opened.db.begin_write().map_err(db_error)?;
+        {
+            let mut table = tx.open_table(ENTRIES).map_err(db_error)?;
+            table.remove(id.as_slice()).map_err(db_error)?;
+        }
+        tx.commit().map_err(db_error)
+    }
+
+    // Names are only recoverable by decrypting, so this reads every record
+    pub fn list(&self, namespace: &str) -> Result<Vec<CacheEntry>, String> {
+        let opened = self.opened()?;
+        let now = now_millis();
+        let tx = opened.db.begin_read().map_err(db_error)?;
+        let table = tx.open_table(ENTRIES).map_err(db_error)?;
+        let mut entries = Vec::new();
+        for item in table.iter().map_err(db_error)? {
+            let (id, blob) = item.map_err(db_error)?;
+            let Some(record) = opened.open(id.value(), blob.value()) else {
+                continue;
+            };
+            if record.namespace == namespace && !record.expired(now) {
+                entries.push(CacheEntry {
+                    key: record.key,
+                    value: record.value,
+                    stored_at: record.stored_at,
+                    expires_at: record.expires_at,
+                });
+            }
+        }
+        entries.sort_by_key(|entry| entry.stored_at);
+        Ok(entries)
+    }
+
+    // Returns how many entries were removed
+    pub fn clear(&self, namespace: Option<&str>) -> Result<usize, String> {
+        let opened = self.opened()?;
+        let tx = opened.db.begin_write().map_err(db_error)?;
+        let mut removed = 0;
+        {
+            let mut table = tx.open_table(ENTRIES).map_err(db_error)?;
+            table
+                .retain(|id, blob| {
+                    let matches = match namespace {
+                        Some(namespace) => opened
+                            .open(id, blob)
+                            .map_or(true, |r| r.namespace == namespace),
+                        None => true,
+                    };
+                    removed += usize::from(matches);
+                    !matches
+                })
+                .map_err(db_error)?;
+        }
+        tx.commit().map_err(db_error)?;
+        Ok(removed)
+    }
+}
@@ -0,0 +1,261 @@
+// Gets the user's data out of the app as CSV, JSON or NDJSON. `kind` names a
+// local collection, or is a backend path whose JSON answer is exported as-is
+// (an array becomes one row per item). Rows are written to a temp file next to
+// the target, which only replaces it once everything is on disk, and progress
+// goes out as `export-progress`.
+//
+// CSV columns are the union of the rows' top-level keys, in first-seen order;
+// nested values are written as JSON. Text cells that a spreadsheet would run
+// as a formula get a leading apostrophe.
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use reqwest::Method;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+
+use super::blocking;
+use crate::http::transfer;
+use crate::notifications::history;
+use crate::state::AppState;
+
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+const FORMULA_PREFIXES: &[char] = &['=', '+', '-', '@', '\t', '\r'];
+
+#[derive(Clone, Copy)]
+enum Format {
+    Csv,
+    Json,
+    Ndjson,
+}
+
+impl Format {
+    fn parse(format: &str) -> Result<Self, String> {
+        match format.to_ascii_lowercase().as_str() {
+            "csv" => Ok(Format::Csv),
+            "json" => Ok(Format::Json),
+            "ndjson" | "jsonl" => Ok(Format::Ndjson),
+            _ => Err(format!("Unknown export format: {}", format)),
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct ExportProgress {
+    path: PathBuf,
+    rows: usize,
+    total: usize,
+    done: bool,
+}
+
+#[derive(Serialize)]
+pub struct ExportResult {
+    pub path: PathBuf,
+    pub rows: usize,
+    pub bytes: u64,
+}
+
+fn rows_of(items: impl Serialize) -> Result<Vec<Value>, String> {
+    match serde_json::to_value(items).map_err(|e| e.to_string())? {
+        Value::Array(rows) => Ok(rows),
+        Value::Null => Ok(Vec::new()),
+        row => Ok(vec![row]),
+    }
+}
+
+#[derive(Serialize)]
+struct ResultRow {
+    key: String,
+    value: Value,
+    stored_at: u64,
+    expires_at: Option<u64>,
+}
+
+fn local_rows(state: &AppState, kind: &str) -> Result<Vec<Value>, String> {
+    match kind {
+        "job_history" => rows_of(state.db.job_history(u32::MAX, None)?),
+        "favorites" => rows_of(state.db.favorites(None)?),
+        "backend_results" => {
+            let results = state.db.results()?.into_iter().map(|result| ResultRow {
+                key: result.0,
+                value: result.1,
+                stored_at: result.2,
+                expires_at: result.3,
+            });
+            rows_of(results.collect::<Vec<_>>())
+        }
+        "logs" => rows_of(state.logs.tail(usize::MAX)),
+        "notifications" => rows_of(history::entries(state)?),
+        _ => Err(format!("Unknown export kind: {}", kind)),
+    }
+}
+
+async fn backend_rows(app: &AppHandle, path: &str) -> Result<Vec<Value>, String> {
+    if path.starts_with("//") {
+        return Err(format!(
+            "Backend path must start with a single '/': {}",
+            path
+        ));
+    }
+    let state = app.state::<AppState>();
+    let response = transfer::request(app, &state, Method::GET, path)
+        .await?
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to fetch {}: {}", path, e))?;
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| format!("{} didn't return JSON: {}", path, e))?;
+    rows_of(body)
+}
+
+fn csv_cell(value: Option<&Value>) -> String {
+    let text = match value {
+        None | Some(Value::Null) => return String::new(),
+        Some(Value::String(s)) if s.starts_with(FORMULA_PREFIXES) => format!("'{}", s),
+        Some(Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
+    };
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
+
+fn columns(rows: &[Value]) -> Vec<String> {
+    let mut columns: Vec<String> = Vec::new();
+    for row in rows {
+        if let Value::Object(map) = row {
+            for key in map.keys() {
+                if !columns.contains(key) {
+                    columns.push(key.clone());
+                }
+            }
+        }
+    }
+    // Rows that aren't objects go in a single column
+    if columns.is_empty() && !rows.is_empty() {
+        columns.push("value".to_string());
+    }
+    columns
+}
+
+fn write_rows(
+    out: &mut impl Write,
+    format: Format,
+    rows: &[Value],
+    mut progress: impl FnMut(usize),
+) -> std::io::Result<()> {
+    let columns = columns(rows);
+    match format {
+        Format::Csv => {
+            let header: Vec<String> = columns
+                .iter()
+                .map(|c| csv_cell(Some(&Value::String(c.clone()))))
+                .collect();
+            writeln!(out, "{}", header.join(","))?;
+        }
+        Format::Json => writeln!(out, "[")?,
+        Format::Ndjson => {}
+    }
+    for (index, row) in rows.iter().enumerate() {
+        match format {
+            Format::Csv => {
+                let cells: Vec<String> = match row {
+                    Value::Object(map) => columns.iter().map(|c| csv_cell(map.get(c))).collect(),
+                    other => vec![csv_cell(Some(other))],
+                };
+                writeln!(out, "{}", cells.join(","))?;
+            }
+            Format::Json => {
+                let separator = if index + 1 < rows.len() { "," } else { "" };
+                writeln!(out, "  {}{}", row, separator)?;
+            }
+            Format::Ndjson => writeln!(out, "{}", row)?,
+        }
+        progress(index + 1);
+    }
+    if let Format::Json = format {
+        writeln!(out, "]")?;
+    }
+    out.flush()
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    PathBuf::from(tmp)
+}
+
+fn export(app: &AppHandle, rows: &[Value], format: Format, path: &Path) -> Result<u64, String> {
+    let tmp = temp_path(path);
+    let file = File::create(&tmp).map_err(|e| format!("Can't write {}: {}", tmp.display(), e))?;
+    let mut out = BufWriter::new(file);
+    let total = rows.len();
+    let mut last_emit = Instant::now();
+    let emit = |rows, done| {
+        let progress = ExportProgress {
+            path: path.to_path_buf(),
+            rows,
+            total,
+            done,
+        };
+        let _ = app.emit_all("export-progress", progress);
+    };
+    let written = write_rows(&mut out, format, rows, |rows| {
+        if last_emit.elapsed() >= PROGRESS_INTERVAL {
+            last_emit = Instant::now();
+            emit(rows, false);
+        }
+    });
+    drop(out);
+    if let Err(e) = written.and_then(|_| fs::rename(&tmp, path)) {
+        let _ = fs::remove_file(&tmp);
+        return Err(format!("Failed to export to {}: {}", path.display(), e));
+    }
+    emit(total, true);
+    fs::metadata(path)
+        .map(|m| m.len())
+        .map_err(|e| e.to_string())
+}
+
+// `kind` is "job_history", "favorites", "backend_results", "logs",
+// "notifications" or a backend path like "/api/jobs"; `format` is "csv",
+// "json" or "ndjson". `path` usually comes from `save_file_dialog`
+#[tauri::command]
+pub async fn export_data(
+    app: AppHandle,
+    kind: String,
+    format: String,
+    path: PathBuf,
+) -> Result<ExportResult, String> {
+    let format = Format::parse(&format)?;
+    if !path.is_absolute() || path.parent().map_or(true, |dir| !dir.is_dir()) {
+        return Err(format!(
+            "{} is not a file in an existing folder",
+            path.display()
+        ));
+    }
+    let rows = if kind.starts_with('/') {
+        backend_rows(&app, &kind).await?
+    } else {
+        let kind = kind.clone();
+        blocking(app.clone(), move |state| local_rows(state, &kind)).await?
+    };
+    let target = path.clone();
+    let handle = app.clone();
+    let count = rows.len();
+    let bytes = blocking(app, move |_| export(&handle, &rows, format, &target)).await?;
+    Ok(ExportResult {
+        path,
+        rows: count,
+        bytes,
+    })
+}
@@ -0,0 +1,321 @@
+// Files fetched from the backend to be shown or opened locally (attachments,
+// generated artifacts), kept in the app's cache dir so asking again is free.
+// The total size is capped by `file_cache.max_mb`; past it, the least recently
+// used files go first. A file's mtime is its last use, so the order survives
+// restarts. Names are a hash of the profile and the absolute URL (so two
+// backends never share a "/files/1") plus its extension, which keeps the OS's
+// "open with" working. A hit is still checked against `sha256` when one is given.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use filetime::FileTime;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, State};
+
+use super::blocking;
+use crate::http::transfer;
+use crate::profiles;
+use crate::state::AppState;
+
+const DIR_NAME: &str = "files";
+const MB: u64 = 1024 * 1024;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FileCacheSettings {
+    pub max_mb: u64,
+}
+
+impl Default for FileCacheSettings {
+    fn default() -> Self {
+        Self { max_mb: 2048 }
+    }
+}
+
+struct Entry {
+    size: u64,
+    // Millis since the epoch
+    last_used: u64,
+}
+
+#[derive(Serialize)]
+pub struct CachedFile {
+    pub path: PathBuf,
+    pub bytes: u64,
+    // Served from the cache without a request
+    pub hit: bool,
+}
+
+#[derive(Serialize)]
+pub struct CacheStats {
+    pub dir: Option<PathBuf>,
+    pub files: usize,
+    pub bytes: u64,
+    pub max_bytes: u64,
+    // Since the app started
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+pub struct FileCache {
+    dir: Option<PathBuf>,
+    entries: Mutex<HashMap<String, Entry>>,
+    // One fetch per file at a time; the others wait and then hit
+    fetching: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+fn millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64)
+}
+
+// e.g. "3f2a…9c.pdf" for ".../report.pdf?download=1"
+fn file_name(profile: &str, url: &str) -> String {
+    let hash: String = Sha256::digest(format!("{}\n{}", profile, url).as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    let extension = url
+        .split(['?', '#'])
+        .next()
+        .and_then(|path| path.rsplit('/').next())
+        .and_then(|name| name.rsplit_once('.'))
+        .map(|(_, ext)| ext)
+        // "part" would look like an unfinished download
+        .filter(|ext| (1..=8).contains(&ext.len()) && !ext.eq_ignore_ascii_case("part"))
+        .filter(|ext| ext.chars().all(|c| c.is_ascii_alphanumeric()));
+    match extension {
+        Some(ext) => format!("{}.{}", hash, ext.to_ascii_lowercase()),
+        None => hash,
+    }
+}
+
+impl FileCache {
+    // Takes stock of what earlier sessions left; unfinished .part files stay
+    // out of the count, and are resumed if the same file is asked for again
+    pub fn open(cache_dir: Option<PathBuf>) -> Self {
+        let dir = cache_dir.map(|dir| dir.join(DIR_NAME));
+        let mut entries = HashMap::new();
+        if let Some(read) = dir.as_ref().and_then(|dir| fs::read_dir(dir).ok()) {
+            for entry in read.flatten() {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                let Ok(meta) = entry.metadata() else {
+                    continue;
+                };
+                if !meta.is_file() || transfer::is_partial(&name) {
+                    continue;
+                }
+                let last_used = meta.modified().map(millis).unwrap_or(0);
+                entries.insert(
+                    name,
+                    Entry {
+                        size: meta.len(),
+                        last_used,
+                    },
+                );
+            }
+        }
+        Self {
+            dir,
+            entries: Mutex::new(entries),
+            fetching: Mutex::new(HashMap::new()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+        }
+    }
+
+    fn dir(&self) -> Result<&Path, String> {
+        self.dir.as_deref().ok_or("No cache directory".to_string())
+    }
+
+    // Marks the file used, if it's cached and still on disk
+    fn touch(&self, name: &str) -> Option<CachedFile> {
+        let path = self.dir.as_ref()?.join(name);
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.get_mut(name)?;
+        if !path.is_file() {
+            entries.remove(name);
+            return None;
+        }
+        let now = SystemTime::now();
+        entry.last_used = millis(now);
+        if let Err(e) = filetime::set_file_mtime(&path, FileTime::from_system_time(now)) {
+            tracing::warn!("Failed to touch {}: {}", path.display(), e);
+        }
+        Some(CachedFile {
+            path,
+            bytes: entry.size,
+            hit: true,
+        })
+    }
+
+    // Drops the least recently used files until the total fits under `max`;
+    // `keep` is never evicted, even when it's bigger than the cap on its own
+    fn evict(&self, max: u64, keep: Option<&str>) {
+        let Some(dir) = &self.dir else {
+            return;
+        };
+        let mut entries = self.entries.lock().unwrap();
+        let mut total: u64 = entries.values().map(|e| e.size).sum();
+        if total <= max {
+            return;
+        }
+        let mut oldest: Vec<(String, u64, u64)> = entries
+            .iter()
+            .filter(|(name, _)| Some(name.as_str()) != keep)
+            .map(|(name, e)| (name.clone(), e.last_used, e.size))
+            .collect();
+        oldest.sort_by_key(|(_, last_used, _)| *last_used);
+        for (name, _, size) in oldest {
+            if total <= max {
+                break;
+            }
+            let path = dir.join(&name);
+            match fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => {
+                    tracing::warn!("Failed to evict {}: {}", path.display(), e);
+                    continue;
+                }
+            }
+            entries.remove(&name);
+            total -= size;
+            self.evictions.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    // Called when `file_cache.max_mb` changes
+    pub fn enforce(&self, settings: &FileCacheSettings) {
+        self.evict(settings.max_mb * MB, None);
+    }
+
+    fn stats(&self, settings: &FileCacheSettings) -> CacheStats {
+        let entries = self.entries.lock().unwrap();
+        CacheStats {
+            dir: self.dir.clone(),
+            files: entries.len(),
+            bytes: entries.values().map(|e| e.size).sum(),
+            max_bytes: settings.max_mb * MB,
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+        }
+    }
+
+    // Deletes every cached file, and what's left of unfinished ones
+    fn clear(&self) -> Result<usize, String> {
+        let dir = self.dir()?;
+        let mut entries = self.entries.lock().unwrap();
+        let count = entries.len();
+        entries.clear();
+        match fs::remove_dir_all(dir) {
+            Ok(()) => Ok(count),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(count),
+            Err(e) => Err(format!("Failed to clear {}: {}", dir.display(), e)),
+        }
+    }
+}
+
+async fn fetch(
+    app: &AppHandle,
+    state: &AppState,
+    url: &str,
+    name: &str,
+    sha256: Option<&str>,
+    request_id: Option<String>,
+) -> Result<CachedFile, String> {
+    if let Some(file) = state.file_cache.touch(name) {
+        let intact = match sha256 {
+            Some(expected) => transfer::sha256_file(app, &file.path)
+                .await
+                .is_ok_and(|actual| actual.eq_ignore_ascii_case(expected)),
+            None => true,
+        };
+        if intact {
+            state.file_cache.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(file);
+        }
+        state.file_cache.entries.lock().unwrap().remove(name);
+        let _ = tokio::fs::remove_file(&file.path).await;
+    }
+    state.file_cache.misses.fetch_add(1, Ordering::Relaxed);
+    let dest = state.file_cache.dir()?.join(name);
+    let request = transfer::request(app, state, Method::GET, url).await?;
+    let result = transfer::download(app, request, dest.clone(), sha256, request_id).await?;
+    state.file_cache.entries.lock().unwrap().insert(
+        name.to_string(),
+        Entry {
+            size: result.bytes,
+            last_used: millis(SystemTime::now()),
+        },
+    );
+    let settings = state.settings.get().file_cache;
+    let keep = name.to_string();
+    blocking(app.clone(), move |state| {
+        state.file_cache.evict(settings.max_mb * MB, Some(&keep));
+        Ok(())
+    })
+    .await?;
+    Ok(CachedFile {
+        path: dest,
+        bytes: result.bytes,
+        hit: false,
+    })
+}
+
+// `url` is a backend path or an absolute URL, fetched like `download_file`.
+// With `sha256`, a fresh download must match it; progress goes out as
+// `transfer-progress` under `request_id`
+#[tauri::command]
+pub async fn fetch_cached_file(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    url: String,
+    sha256: Option<String>,
+    request_id: Option<String>,
+) -> Result<CachedFile, String> {
+    let (resolved, _) = transfer::resolve(&app, &url);
+    let name = file_name(&profiles::active_name(&state.settings.get()), &resolved);
+    let lock = state
+        .file_cache
+        .fetching
+        .lock()
+        .unwrap()
+        .entry(name.clone())
+        .or_default()
+        .clone();
+    let result = {
+        let _fetching = lock.lock().await;
+        fetch(&app, &state, &url, &name, sha256.as_deref(), request_id).await
+    };
+    // Our clone and the map's are all that's left once nobody else is waiting
+    let mut fetching = state.file_cache.fetching.lock().unwrap();
+    if Arc::strong_count(&lock) <= 2 {
+        fetching.remove(&name);
+    }
+    result
+}
+
+#[tauri::command]
+pub async fn get_cache_stats(state: State<'_, AppState>) -> Result<CacheStats, String> {
+    Ok(state.file_cache.stats(&state.settings.get().file_cache))
+}
+
+// Returns how many files were removed
+#[tauri::command]
+pub async fn clear_cache(app: AppHandle) -> Result<usize, String> {
+    blocking(app, |state| state.file_cache.clear()).await
+}
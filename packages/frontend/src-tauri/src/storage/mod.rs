@@ -0,0 +1,84 @@
+// Data the shell keeps on disk for the frontend, in the app's local data dir
+// (not roamed or synced, unlike the config dir)
+
+pub mod backup;
+pub mod db;
+pub mod encrypted;
+pub mod export;
+pub mod file_cache;
+pub mod search;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+
+use crate::state::AppState;
+
+use self::encrypted::CacheEntry;
+
+// Opens the database and search index off the main thread once the first page
+// has loaded, so startup doesn't wait on them and the first query doesn't
+// either. Anything that needs them sooner opens them itself.
+pub fn warm_up(app: &AppHandle) {
+    static STARTED: AtomicBool = AtomicBool::new(false);
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let state = app.state::<AppState>();
+        state.db.warm_up();
+        state.search.warm_up();
+    });
+}
+
+// Database calls do disk I/O and may hit the keychain on first use
+pub async fn blocking<T: Send + 'static>(
+    app: AppHandle,
+    f: impl FnOnce(&AppState) -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    tauri::async_runtime::spawn_blocking(move || f(&app.state::<AppState>()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn cache_put(
+    app: AppHandle,
+    namespace: String,
+    key: String,
+    value: Value,
+    ttl_secs: Option<u64>,
+) -> Result<(), String> {
+    blocking(app, move |state| {
+        state.store.put(&namespace, &key, value, ttl_secs)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn cache_get(
+    app: AppHandle,
+    namespace: String,
+    key: String,
+) -> Result<Option<Value>, String> {
+    blocking(app, move |state| state.store.get(&namespace, &key)).await
+}
+
+#[tauri::command]
+pub async fn cache_delete(app: AppHandle, namespace: String, key: String) -> Result<(), String> {
+    blocking(app, move |state| state.store.delete(&namespace, &key)).await
+}
+
+// Oldest first
+#[tauri::command]
+pub async fn cache_list(app: AppHandle, namespace: String) -> Result<Vec<CacheEntry>, String> {
+    blocking(app, move |state| state.store.list(&namespace)).await
+}
+
+// Without a namespace, wipes the whole store
+#[tauri::command]
+pub async fn cache_clear(app: AppHandle, namespace: Option<String>) -> Result<usize, String> {
+    blocking(app, move |state| state.store.clear(namespace.as_deref())).await
+}
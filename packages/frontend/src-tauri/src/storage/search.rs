@@ -0,0 +1,507 @@
+// Full-text index over what the shell keeps locally, cached backend results
+// and log lines, so `search_local` still answers when the backend is slow or
+// offline. Writes are handed to a background thread that commits about once a
+// second, so a hit can lag the data by that much. Results drop out of hits
+// when they expire and log lines after LOG_RETENTION; both are purged from
+// disk at open and then every PURGE_INTERVAL. The encrypted cache
+// isn't indexed; that would put its contents on disk in the clear.
+//
+// The index is derived data. One that can't be opened (e.g. written by an
+// older schema) is thrown away and rebuilt from the database; logs from
+// before that are gone, as they are from the ring buffer.
+
+use std::ops::Bound;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tantivy::collector::TopDocs;
+use tantivy::directory::MmapDirectory;
+use tantivy::query::{AllQuery, BooleanQuery, Occur, Query, QueryParser, RangeQuery, TermQuery};
+use tantivy::schema::{
+    Field, IndexRecordOption, Schema, Value as _, INDEXED, STORED, STRING, TEXT,
+};
+use tantivy::snippet::SnippetGenerator;
+use tantivy::{doc, Index, IndexReader, IndexWriter, ReloadPolicy, TantivyDocument, Term};
+use tauri::{AppHandle, Manager};
+
+use super::blocking;
+use super::db::Database;
+use crate::backend::logs::LogLine;
+use crate::state::AppState;
+use crate::util::now_millis;
+
+const DIR_NAME: &str = "search";
+const WRITER_MEMORY: usize = 32 * 1024 * 1024;
+const COMMIT_INTERVAL: Duration = Duration::from_secs(1);
+const LOG_RETENTION: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+// Every backend log line is indexed, so a long session has to shed old ones
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const DEFAULT_LIMIT: usize = 50;
+const SNIPPET_CHARS: usize = 200;
+// Writes kept while the index isn't open yet
+const MAX_PENDING: usize = 10_000;
+const RESULT: &str = "result";
+const LOG: &str = "log";
+
+#[derive(Clone, Copy)]
+struct Fields {
+    // "<kind>:<key>", the unit of replacement
+    id: Field,
+    kind: Field,
+    key: Field,
+    // Log level; empty for results
+    level: Field,
+    text: Field,
+    timestamp: Field,
+    // u64::MAX when it never expires
+    expires_at: Field,
+}
+
+fn schema() -> (Schema, Fields) {
+    let mut builder = Schema::builder();
+    let fields = Fields {
+        id: builder.add_text_field("id", STRING),
+        kind: builder.add_text_field("kind", STRING | STORED),
+        key: builder.add_text_field("key", STRING | STORED),
+        level: builder.add_text_field("level", STRING | STORED),
+        text: builder.add_text_field("text", TEXT | STORED),
+        timestamp: builder.add_u64_field("timestamp", INDEXED | STORED),
+        expires_at: builder.add_u64_field("expires_at", INDEXED),
+    };
+    (builder.build(), fields)
+}
+
+enum Op {
+    Add(TantivyDocument),
+    Delete(Term),
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct SearchFilters {
+    // "result" or "log"
+    pub kind: Option<String>,
+    // For logs: "debug", "info", "warning" or "error"
+    pub level: Option<String>,
+    // Milliseconds since the Unix epoch, inclusive
+    pub since: Option<u64>,
+    pub until: Option<u64>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub struct SearchHit {
+    pub kind: String,
+    // The result's key, for `get_backend_result`; a log line's own id
+    pub key: String,
+    pub level: Option<String>,
+    pub score: f32,
+    pub timestamp: u64,
+    pub snippet: String,
+    // Byte ranges of `snippet` that matched
+    pub highlights: Vec<(usize, usize)>,
+}
+
+struct Opened {
+    index: Index,
+    reader: IndexReader,
+    fields: Fields,
+    writes: Mutex<Sender<Op>>,
+}
+
+fn flush(pending: &mut Vec<Op>, opened: &Opened) {
+    if pending.is_empty() {
+        return;
+    }
+    let writes = opened.writes.lock().unwrap();
+    for op in pending.drain(..) {
+        let _ = writes.send(op);
+    }
+}
+
+// Opened like the database, on first use or by `storage::warm_up`. Writes
+// that arrive before then (log lines, mostly) wait in `pending` rather than
+// open it on whatever thread they came from, which may be the main one.
+pub struct SearchIndex {
+    app: AppHandle,
+    dir: Option<PathBuf>,
+    opened: OnceLock<Result<Opened, String>>,
+    fields: Fields,
+    pending: Mutex<Vec<Op>>,
+    // Tells log lines with the same timestamp apart
+    sequence: AtomicU64,
+}
+
+// All the text in a JSON value, object keys included
+fn flatten(value: &Value, out: &mut String) {
+    match value {
+        Value::String(s) => {
+            out.push_str(s);
+            out.push(' ');
+        }
+        Value::Number(n) => {
+            out.push_str(&n.to_string());
+            out.push(' ');
+        }
+        Value::Array(items) => items.iter().for_each(|item| flatten(item, out)),
+        Value::Object(map) => {
+            for (key, item) in map {
+                out.push_str(key);
+                out.push(' ');
+                flatten(item, out);
+            }
+        }
+        Value::Bool(_) | Value::Null => {}
+    }
+}
+
+fn id_term(fields: &Fields, kind: &str, key: &str) -> Term {
+    Term::from_field_text(fields.id, &format!("{}:{}", kind, key))
+}
+
+fn term_query(field: Field, value: &str) -> Box<dyn Query> {
+    Box::new(TermQuery::new(
+        Term::from_field_text(field, value),
+        IndexRecordOption::Basic,
+    ))
+}
+
+fn range(field: &str, lower: Bound<u64>, upper: Bound<u64>) -> Box<dyn Query> {
+    Box::new(RangeQuery::new_u64_bounds(field.to_string(), lower, upper))
+}
+
+fn index_error(e: impl std::fmt::Display) -> String {
+    format!("Search index error: {}", e)
+}
+
+fn open_index(dir: &Path, schema: Schema) -> Result<Index, String> {
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let directory = MmapDirectory::open(dir).map_err(index_error)?;
+    Index::open_or_create(directory, schema).map_err(index_error)
+}
+
+// Deletes expired results and log lines past LOG_RETENTION; takes effect with
+// the next commit
+fn purge(writer: &IndexWriter, fields: &Fields) -> Result<(), String> {
+    let now = now_millis();
+    let expired = range("expires_at", Bound::Unbounded, Bound::Included(now));
+    writer.delete_query(expired).map_err(index_error)?;
+    let cutoff = now.saturating_sub(LOG_RETENTION.as_millis() as u64);
+    let old_logs = BooleanQuery::new(vec![
+        (Occur::Must, term_query(fields.kind, LOG)),
+        (
+            Occur::Must,
+            range("timestamp", Bound::Unbounded, Bound::Excluded(cutoff)),
+        ),
+    ]);
+    writer
+        .delete_query(Box::new(old_logs))
+        .map_err(index_error)?;
+    Ok(())
+}
+
+// Applies writes as they come, committing at most every COMMIT_INTERVAL and
+// once more when the app shuts the channel; purges every PURGE_INTERVAL
+fn write_loop(mut writer: IndexWriter, fields: Fields, ops: mpsc::Receiver<Op>) {
+    let mut dirty = false;
+    let mut last_commit = Instant::now();
+    let mut last_purge = Instant::now();
+    loop {
+        if last_purge.elapsed() >= PURGE_INTERVAL {
+            match purge(&writer, &fields) {
+                Ok(()) => dirty = true,
+                Err(e) => tracing::warn!("{}", e),
+            }
+            last_purge = Instant::now();
+        }
+        match ops.recv_timeout(COMMIT_INTERVAL) {
+            Ok(op) => {
+                let applied = match op {
+                    Op::Add(doc) => writer.add_document(doc).map(drop),
+                    Op::Delete(term) => {
+                        writer.delete_term(term);
+                        Ok(())
+                    }
+                };
+                if let Err(e) = applied {
+                    tracing::warn!("{}", index_error(e));
+                }
+                dirty = true;
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => {
+                if dirty {
+                    let _ = writer.commit();
+                }
+                break;
+            }
+        }
+        if dirty && last_commit.elapsed() >= COMMIT_INTERVAL {
+            if let Err(e) = writer.commit() {
+                tracing::warn!("{}", index_error(e));
+            }
+            dirty = false;
+            last_commit = Instant::now();
+        }
+    }
+}
+
+fn open(dir: &Path, db: &Database) -> Result<Opened, String> {
+    let (schema, fields) = schema();
+    let (index, rebuilt) = match open_index(dir, schema.clone()) {
+        Ok(index) => (index, false),
+        Err(e) => {
+            tracing::warn!("Rebuilding the search index: {}", e);
+            let _ = std::fs::remove_dir_all(dir);
+            (open_index(dir, schema)?, true)
+        }
+    };
+    let fresh = rebuilt
+        || index
+            .searchable_segment_ids()
+            .map_err(index_error)?
+            .is_empty();
+    let reader = index
+        .reader_builder()
+        .reload_policy(ReloadPolicy::OnCommitWithDelay)
+        .try_into()
+        .map_err(index_error)?;
+    let writer: IndexWriter = index.writer(WRITER_MEMORY).map_err(index_error)?;
+
+    // Whatever went stale while the app wasn't running
+    purge(&writer, &fields)?;
+    if fresh {
+        for (key, value, stored_at, expires_at) in db.results().unwrap_or_default() {
+            writer
+                .add_document(result_doc(&fields, &key, &value, stored_at, expires_at))
+                .map_err(index_error)?;
+        }
+    }
+
+    let (writes, ops) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut writer = writer;
+        if let Err(e) = writer.commit() {
+            tracing::warn!("{}", index_error(e));
+        }
+        write_loop(writer, fields, ops);
+    });
+    Ok(Opened {
+        index,
+        reader,
+        fields,
+        writes: Mutex::new(writes),
+    })
+}
+
+fn result_doc(
+    fields: &Fields,
+    key: &str,
+    value: &Value,
+    stored_at: u64,
+    expires_at: Option<u64>,
+) -> TantivyDocument {
+    let mut text = key.replace(['/', ':', '.', '_', '-'], " ");
+    text.push(' ');
+    flatten(value, &mut text);
+    doc!(
+        fields.id => format!("{}:{}", RESULT, key),
+        fields.kind => RESULT,
+        fields.key => key,
+        fields.level => "",
+        fields.text => text,
+        fields.timestamp => stored_at,
+        fields.expires_at => expires_at.unwrap_or(u64::MAX),
+    )
+}
+
+impl SearchIndex {
+    pub fn new(app: &AppHandle, dir: Option<PathBuf>) -> Self {
+        Self {
+            app: app.clone(),
+            dir,
+            opened: OnceLock::new(),
+            fields: schema().1,
+            pending: Mutex::new(Vec::new()),
+            sequence: AtomicU64::new(0),
+        }
+    }
+
+    // A fresh index is filled from the database, which opens it too if need be
+    fn opened(&self) -> Result<&Opened, String> {
+        let opened = self.opened.get_or_init(|| {
+            let opened = self
+                .dir
+                .as_ref()
+                .ok_or_else(|| "No app data directory".to_string())
+                .and_then(|dir| open(&dir.join(DIR_NAME), &self.app.state::<AppState>().db));
+            if let Err(e) = &opened {
+                tracing::error!("Failed to open the search index: {}", e);
+            }
+            opened
+        });
+        let mut pending = self.pending.lock().unwrap();
+        match opened {
+            Ok(opened) => flush(&mut pending, opened),
+            Err(_) => pending.clear(),
+        }
+        opened.as_ref().map_err(|e| e.clone())
+    }
+
+    pub fn warm_up(&self) {
+        let _ = self.opened();
+    }
+
+    // Indexing is best effort; a closed index just misses the update
+    fn write(&self, op: impl FnOnce(&Fields) -> Op) {
+        let mut pending = self.pending.lock().unwrap();
+        match self.opened.get() {
+            Some(Ok(opened)) => {
+                flush(&mut pending, opened);
+                let _ = opened.writes.lock().unwrap().send(op(&opened.fields));
+            }
+            Some(Err(_)) => {}
+            None if pending.len() < MAX_PENDING => pending.push(op(&self.fields)),
+            None => {}
+        }
+    }
+
+    pub fn index_result(&self, key: &str, value: &Value, expires_at: Option<u64>) {
+        self.write(|fields| Op::Delete(id_term(fields, RESULT, key)));
+        self.write(|fields| Op::Add(result_doc(fields, key, value, now_millis(), expires_at)));
+    }
+
+    // After the database was replaced wholesale
+    pub fn reindex_results(&self, db: &Database) {
+        self.write(|fields| Op::Delete(Term::from_field_text(fields.kind, RESULT)));
+        for (key, value, stored_at, expires_at) in db.results().unwrap_or_default() {
+            self.write(|fields| Op::Add(result_doc(fields, &key, &value, stored_at, expires_at)));
+        }
+    }
+
+    pub fn remove_results(&self, keys: &[String]) {
+        for key in keys {
+            self.write(|fields| Op::Delete(id_term(fields, RESULT, key)));
+        }
+    }
+
+    pub fn index_log(&self, line: &LogLine) {
+        let sequence = self.sequence.fetch_add(1, Ordering::Relaxed);
+        let key = format!("{}-{}", line.timestamp, sequence);
+        let level = serde_json::to_value(line.level)
+            .ok()
+            .and_then(|level| level.as_str().map(str::to_string))
+            .unwrap_or_default();
+        self.write(|fields| {
+            Op::Add(doc!(
+                fields.id => format!("{}:{}", LOG, key),
+                fields.kind => LOG,
+                fields.key => key,
+                fields.level => level,
+                fields.text => line.line.clone(),
+                fields.timestamp => line.timestamp,
+                fields.expires_at => u64::MAX,
+            ))
+        });
+    }
+
+    // Best matches first. Without a query, everything that passes the
+    // filters, in index order
+    pub fn search(&self, query: &str, filters: &SearchFilters) -> Result<Vec<SearchHit>, String> {
+        let opened = self.opened()?;
+        let fields = &opened.fields;
+        let text_query: Box<dyn Query> = if query.trim().is_empty() {
+            Box::new(AllQuery)
+        } else {
+            // Stray quotes or colons shouldn't make a search fail
+            let parser = QueryParser::for_index(&opened.index, vec![fields.text]);
+            parser.parse_query_lenient(query).0
+        };
+        let mut clauses = vec![
+            (Occur::Must, text_query.box_clone()),
+            (
+                Occur::Must,
+                range(
+                    "expires_at",
+                    Bound::Excluded(now_millis()),
+                    Bound::Unbounded,
+                ),
+            ),
+        ];
+        if let Some(kind) = &filters.kind {
+            clauses.push((Occur::Must, term_query(fields.kind, kind)));
+        }
+        if let Some(level) = &filters.level {
+            clauses.push((Occur::Must, term_query(fields.level, level)));
+        }
+        if filters.since.is_some() || filters.until.is_some() {
+            let lower = filters.since.map_or(Bound::Unbounded, Bound::Included);
+            let upper = filters.until.map_or(Bound::Unbounded, Bound::Included);
+            clauses.push((Occur::Must, range("timestamp", lower, upper)));
+        }
+
+        let searcher = opened.reader.searcher();
+        let limit = filters.limit.unwrap_or(DEFAULT_LIMIT).max(1);
+        let top = searcher
+            .search(&BooleanQuery::new(clauses), &TopDocs::with_limit(limit))
+            .map_err(index_error)?;
+        let mut snippets =
+            SnippetGenerator::create(&searcher, &*text_query, fields.text).map_err(index_error)?;
+        snippets.set_max_num_chars(SNIPPET_CHARS);
+
+        let mut hits = Vec::with_capacity(top.len());
+        for (score, address) in top {
+            let doc: TantivyDocument = searcher.doc(address).map_err(index_error)?;
+            let text = |field| {
+                doc.get_first(field)
+                    .and_then(|value| value.as_str())
+                    .unwrap_or_default()
+                    .to_string()
+            };
+            let snippet = snippets.snippet_from_doc(&doc);
+            // Nothing to highlight (e.g. no query): the start of the text
+            let (fragment, highlights) = if snippet.is_empty() {
+                (
+                    text(fields.text).chars().take(SNIPPET_CHARS).collect(),
+                    vec![],
+                )
+            } else {
+                let highlights = snippet
+                    .highlighted()
+                    .iter()
+                    .map(|range| (range.start, range.end))
+                    .collect();
+                (snippet.fragment().to_string(), highlights)
+            };
+            let level = text(fields.level);
+            hits.push(SearchHit {
+                kind: text(fields.kind),
+                key: text(fields.key),
+                level: (!level.is_empty()).then_some(level),
+                score,
+                timestamp: doc
+                    .get_first(fields.timestamp)
+                    .and_then(|value| value.as_u64())
+                    .unwrap_or_default(),
+                snippet: fragment,
+                highlights,
+            });
+        }
+        Ok(hits)
+    }
+}
+
+#[tauri::command]
+pub async fn search_local(
+    app: AppHandle,
+    query: String,
+    filters: Option<SearchFilters>,
+) -> Result<Vec<SearchHit>, String> {
+    let filters = filters.unwrap_or_default();
+    blocking(app, move |state| state.search.search(&query, &filters)).await
+}
@@ -0,0 +1,170 @@
+// What the app is running on, for the diagnostics bundle and for the frontend
+// to warn about setups it doesn't support (old webviews, little memory). The
+// hardware parts that are slow to find out (the GPU, on macOS and Linux a
+// child process) are looked up once per run.
+
+use std::sync::OnceLock;
+
+use serde::Serialize;
+use sysinfo::{Disks, System};
+use tauri::AppHandle;
+
+use crate::files;
+
+#[derive(Clone, Serialize)]
+pub struct DiskInfo {
+    pub mount_point: String,
+    pub total: u64,
+    pub available: u64,
+    pub removable: bool,
+}
+
+#[derive(Clone, Serialize)]
+pub struct SystemInfo {
+    pub os: String,
+    pub os_version: Option<String>,
+    pub kernel: Option<String>,
+    pub arch: String,
+    pub cpu: Option<String>,
+    pub cores: usize,
+    pub gpus: Vec<String>,
+    // Bytes
+    pub memory_total: u64,
+    pub memory_available: u64,
+    pub disks: Vec<DiskInfo>,
+    // Free space where the app keeps its data
+    pub data_disk_available: Option<u64>,
+    pub webview_version: Option<String>,
+    pub app_version: String,
+    pub tauri_version: String,
+}
+
+// Free space on the volume `path` would be written to, if the OS says
+pub fn available_space(disks: &Disks, path: &std::path::Path) -> Option<u64> {
+    let existing = path.ancestors().find(|dir| dir.exists())?;
+    let path = files::without_verbatim_prefix(existing.canonicalize().ok()?);
+    disks
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| disk.available_space())
+}
+
+#[cfg(target_os = "windows")]
+fn find_gpus() -> Vec<String> {
+    use windows::Win32::Graphics::Dxgi::{
+        CreateDXGIFactory1, IDXGIFactory1, DXGI_ADAPTER_FLAG_SOFTWARE,
+    };
+
+    let Ok(factory) = (unsafe { CreateDXGIFactory1::<IDXGIFactory1>() }) else {
+        return Vec::new();
+    };
+    let mut gpus = Vec::new();
+    // Ends with DXGI_ERROR_NOT_FOUND past the last adapter
+    for index in 0.. {
+        let Ok(adapter) = (unsafe { factory.EnumAdapters1(index) }) else {
+            break;
+        };
+        let Ok(desc) = (unsafe { adapter.GetDesc1() }) else {
+            continue;
+        };
+        // The Basic Render Driver is always there and never interesting
+        if desc.Flags & DXGI_ADAPTER_FLAG_SOFTWARE.0 != 0 {
+            continue;
+        }
+        let len = desc.Description.iter().position(|c| *c == 0).unwrap_or(128);
+        gpus.push(String::from_utf16_lossy(&desc.Description[..len]));
+    }
+    gpus
+}
+
+#[cfg(target_os = "macos")]
+fn find_gpus() -> Vec<String> {
+    let Ok(output) = std::process::Command::new("system_profiler")
+        .arg("SPDisplaysDataType")
+        .output()
+    else {
+        return Vec::new();
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("Chipset Model:"))
+        .map(|model| model.trim().to_string())
+        .collect()
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn find_gpus() -> Vec<String> {
+    let Ok(output) = std::process::Command::new("lspci").output() else {
+        return Vec::new();
+    };
+    // e.g. "01:00.0 VGA compatible controller: NVIDIA Corporation GA104 [...]"
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| {
+            [
+                "VGA compatible controller",
+                "3D controller",
+                "Display controller",
+            ]
+            .iter()
+            .any(|class| line.contains(class))
+        })
+        .filter_map(|line| line.splitn(3, ':').nth(2))
+        .map(|model| model.trim().to_string())
+        .collect()
+}
+
+fn gpus() -> Vec<String> {
+    static GPUS: OnceLock<Vec<String>> = OnceLock::new();
+    GPUS.get_or_init(find_gpus).clone()
+}
+
+// Takes a moment on first use (GPU lookup, CPU refresh); run it off the main thread
+pub fn collect(app: &AppHandle) -> SystemInfo {
+    let mut system = System::new();
+    system.refresh_memory();
+    system.refresh_cpu();
+    let disks = Disks::new_with_refreshed_list();
+    let data_disk_available = app
+        .path_resolver()
+        .app_local_data_dir()
+        .and_then(|dir| available_space(&disks, &dir));
+    SystemInfo {
+        os: std::env::consts::OS.to_string(),
+        os_version: System::long_os_version(),
+        kernel: System::kernel_version(),
+        arch: std::env::consts::ARCH.to_string(),
+        cpu: system
+            .cpus()
+            .first()
+            .map(|cpu| cpu.brand().trim().to_string())
+            .filter(|brand| !brand.is_empty()),
+        cores: system.cpus().len(),
+        gpus: gpus(),
+        memory_total: system.total_memory(),
+        memory_available: system.available_memory(),
+        disks: disks
+            .list()
+            .iter()
+            .map(|disk| DiskInfo {
+                mount_point: disk.mount_point().to_string_lossy().into_owned(),
+                total: disk.total_space(),
+                available: disk.available_space(),
+                removable: disk.is_removable(),
+            })
+            .collect(),
+        data_disk_available,
+        webview_version: tauri::webview_version().ok(),
+        app_version: app.package_info().version.to_string(),
+        tauri_version: tauri::VERSION.to_string(),
+    }
+}
+
+#[tauri::command]
+pub async fn get_system_info(app: AppHandle) -> Result<SystemInfo, String> {
+    tauri::async_runtime::spawn_blocking(move || collect(&app))
+        .await
+        .map_err(|e| e.to_string())
+}
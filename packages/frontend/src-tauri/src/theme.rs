@@ -0,0 +1,228 @@
+// The OS light/dark preference. Windows and macOS report changes as a window
+// event. On Linux that event only follows the GTK theme name, so the desktop
+// portal's color-scheme (what GNOME and KDE actually switch) is read and
+// watched instead. Changes go out as `theme-changed`, redraw the tray icon
+// and, with `window.match_system_theme`, restyle native window chrome.
+
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::state::AppState;
+use crate::tray;
+use crate::window;
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    Light,
+    Dark,
+}
+
+impl From<tauri::Theme> for Theme {
+    fn from(theme: tauri::Theme) -> Self {
+        match theme {
+            tauri::Theme::Dark => Theme::Dark,
+            _ => Theme::Light,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ThemeWatcher {
+    current: Mutex<Option<Theme>>,
+}
+
+// Light until something says otherwise
+pub fn system(app: &AppHandle) -> Theme {
+    app.try_state::<AppState>()
+        .and_then(|state| *state.theme.current.lock().unwrap())
+        .unwrap_or(Theme::Light)
+}
+
+fn from_window(app: &AppHandle) -> Option<Theme> {
+    Some(app.get_window(window::MAIN)?.theme().ok()?.into())
+}
+
+pub fn start(app: &AppHandle) {
+    let initial = platform::detect().or_else(|| from_window(app));
+    if let Some(theme) = initial {
+        *app.state::<AppState>().theme.current.lock().unwrap() = Some(theme);
+    }
+    if app
+        .state::<AppState>()
+        .settings
+        .get()
+        .window
+        .match_system_theme
+    {
+        apply_chrome(app);
+    }
+    platform::watch(app);
+}
+
+pub fn changed(app: &AppHandle, theme: Theme) {
+    let state = app.state::<AppState>();
+    {
+        let mut current = state.theme.current.lock().unwrap();
+        if *current == Some(theme) {
+            return;
+        }
+        *current = Some(theme);
+    }
+    let _ = app.emit_all("theme-changed", theme);
+    tray::icon::refresh(app);
+    if state.settings.get().window.match_system_theme {
+        apply_chrome(app);
+    }
+}
+
+// Window events only know about the GTK theme on Linux; the portal wins there
+pub fn on_window_theme(app: &AppHandle, theme: tauri::Theme) {
+    if !cfg!(all(unix, not(target_os = "macos"))) || platform::detect().is_none() {
+        changed(app, theme.into());
+    }
+}
+
+// Title bars (and on Linux, GTK's own widgets) in the system's mode
+pub fn apply_chrome(app: &AppHandle) {
+    let dark = system(app) == Theme::Dark;
+    let handle = app.clone();
+    let result = app.run_on_main_thread(move || platform::set_chrome(&handle, dark));
+    if let Err(e) = result {
+        tracing::warn!("Failed to restyle windows: {}", e);
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use tauri::{AppHandle, Manager};
+    use windows::Win32::Foundation::BOOL;
+    use windows::Win32::Graphics::Dwm::{DwmSetWindowAttribute, DWMWINDOWATTRIBUTE};
+
+    use super::Theme;
+
+    // DWMWA_USE_IMMERSIVE_DARK_MODE, Windows 10 20H1 and later
+    const DARK_MODE: DWMWINDOWATTRIBUTE = DWMWINDOWATTRIBUTE(20);
+
+    pub fn detect() -> Option<Theme> {
+        None
+    }
+
+    pub fn watch(_app: &AppHandle) {}
+
+    pub fn set_chrome(app: &AppHandle, dark: bool) {
+        let value = BOOL::from(dark);
+        for window in app.windows().values() {
+            let Ok(hwnd) = window.hwnd() else {
+                continue;
+            };
+            let result = unsafe {
+                DwmSetWindowAttribute(
+                    hwnd,
+                    DARK_MODE,
+                    (&value as *const BOOL).cast(),
+                    std::mem::size_of::<BOOL>() as u32,
+                )
+            };
+            if let Err(e) = result {
+                tracing::warn!("Failed to set the title bar theme: {}", e);
+            }
+        }
+    }
+}
+
+// AppKit already draws windows in the system appearance
+#[cfg(target_os = "macos")]
+mod platform {
+    use tauri::AppHandle;
+
+    use super::Theme;
+
+    pub fn detect() -> Option<Theme> {
+        None
+    }
+
+    pub fn watch(_app: &AppHandle) {}
+
+    pub fn set_chrome(_app: &AppHandle, _dark: bool) {}
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use gtk::prelude::*;
+    use tauri::AppHandle;
+    use zbus::blocking::{Connection, Proxy};
+    use zbus::zvariant::{OwnedValue, Value};
+
+    use super::Theme;
+
+    const NAMESPACE: &str = "org.freedesktop.appearance";
+    const KEY: &str = "color-scheme";
+
+    fn settings(connection: &Connection) -> zbus::Result<Proxy<'static>> {
+        Proxy::new(
+            connection,
+            "org.freedesktop.portal.Desktop",
+            "/org/freedesktop/portal/desktop",
+            "org.freedesktop.portal.Settings",
+        )
+    }
+
+    // 1 prefers dark, 2 prefers light, 0 has no preference. Older portals
+    // wrap the value in an extra variant.
+    fn scheme(value: &Value) -> Option<Theme> {
+        match value {
+            Value::U32(1) => Some(Theme::Dark),
+            Value::U32(_) => Some(Theme::Light),
+            Value::Value(inner) => scheme(inner),
+            _ => None,
+        }
+    }
+
+    pub fn detect() -> Option<Theme> {
+        let connection = Connection::session().ok()?;
+        let value: OwnedValue = settings(&connection)
+            .ok()?
+            .call("Read", &(NAMESPACE, KEY))
+            .ok()?;
+        scheme(&value)
+    }
+
+    pub fn watch(app: &AppHandle) {
+        let app = app.clone();
+        std::thread::spawn(move || {
+            let result = (|| -> zbus::Result<()> {
+                let connection = Connection::session()?;
+                let proxy = settings(&connection)?;
+                for message in proxy.receive_signal("SettingChanged")? {
+                    let (namespace, key, value): (String, String, OwnedValue) =
+                        message.body().deserialize()?;
+                    if namespace != NAMESPACE || key != KEY {
+                        continue;
+                    }
+                    if let Some(theme) = scheme(&value) {
+                        super::changed(&app, theme);
+                    }
+                }
+                Ok(())
+            })();
+            // No portal (e.g. a bare window manager): window events still work
+            if let Err(e) = result {
+                tracing::warn!("Not watching the system theme: {}", e);
+            }
+        });
+    }
+
+    pub fn set_chrome(_app: &AppHandle, dark: bool) {
+        if let Some(settings) = gtk::Settings::default() {
+            settings.set_gtk_application_prefer_dark_theme(dark);
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn get_system_theme(app: AppHandle) -> Result<Theme, String> {
+    Ok(system(&app))
+}
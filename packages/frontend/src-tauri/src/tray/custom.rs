@@ -0,0 +1,117 @@
+// Frontend-defined tray entries: a declarative item list sent with
+// `set_tray_menu`, rendered above Quit, with clicks sent back as events
+
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{
+    AppHandle, CustomMenuItem, Manager, State, SystemTrayMenu, SystemTrayMenuItem,
+    SystemTraySubmenu,
+};
+
+use crate::state::AppState;
+
+// Keeps frontend ids from colliding with the built-in ones
+pub const PREFIX: &str = "custom:";
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum MenuEntry {
+    Item {
+        id: String,
+        label: String,
+        #[serde(default = "enabled_by_default")]
+        enabled: bool,
+        #[serde(default)]
+        checked: bool,
+    },
+    Separator,
+    Submenu {
+        label: String,
+        items: Vec<MenuEntry>,
+    },
+}
+
+#[derive(Default)]
+pub struct CustomMenu {
+    entries: Mutex<Vec<MenuEntry>>,
+}
+
+impl CustomMenu {
+    pub fn entries(&self) -> Vec<MenuEntry> {
+        self.entries.lock().unwrap().clone()
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct ClickPayload {
+    id: String,
+}
+
+fn check_ids<'a>(entries: &'a [MenuEntry], seen: &mut HashSet<&'a str>) -> Result<(), String> {
+    for entry in entries {
+        match entry {
+            MenuEntry::Item { id, .. } => {
+                if id.trim().is_empty() {
+                    return Err("Tray menu items need an id".into());
+                }
+                if !seen.insert(id) {
+                    return Err(format!("Duplicate tray menu id: {}", id));
+                }
+            }
+            MenuEntry::Separator => {}
+            MenuEntry::Submenu { items, .. } => check_ids(items, seen)?,
+        }
+    }
+    Ok(())
+}
+
+pub fn append(mut menu: SystemTrayMenu, entries: &[MenuEntry]) -> SystemTrayMenu {
+    for entry in entries {
+        menu = match entry {
+            MenuEntry::Item {
+                id,
+                label,
+                enabled,
+                checked,
+            } => {
+                let mut item = CustomMenuItem::new(format!("{}{}", PREFIX, id), label.clone());
+                if !enabled {
+                    item = item.disabled();
+                }
+                if *checked {
+                    item = item.selected();
+                }
+                menu.add_item(item)
+            }
+            MenuEntry::Separator => menu.add_native_item(SystemTrayMenuItem::Separator),
+            MenuEntry::Submenu { label, items } => menu.add_submenu(SystemTraySubmenu::new(
+                label.clone(),
+                append(SystemTrayMenu::new(), items),
+            )),
+        };
+    }
+    menu
+}
+
+pub fn on_click(app: &AppHandle, id: &str) {
+    let _ = app.emit_all("tray-menu-click", ClickPayload { id: id.to_string() });
+}
+
+// Replaces the whole custom section; an empty list removes it
+#[tauri::command]
+pub async fn set_tray_menu(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    items: Vec<MenuEntry>,
+) -> Result<(), String> {
+    check_ids(&items, &mut HashSet::new())?;
+    *state.tray_menu.entries.lock().unwrap() = items;
+    super::rebuild(&app);
+    Ok(())
+}
@@ -0,0 +1,125 @@
+// Tray icon rendering: the app icon with a coloured status badge and, while
+// jobs run, a progress bar, drawn at runtime so we don't ship a PNG per state
+// (or per light/dark taskbar)
+
+use std::sync::OnceLock;
+
+use tauri::{AppHandle, Icon, Manager};
+
+use crate::health::HealthState;
+use crate::state::AppState;
+use crate::theme::Theme;
+use crate::util::{decode_png, Rgba};
+
+use super::platform::{self, BASE_ICON};
+
+fn base() -> &'static Rgba {
+    static BASE: OnceLock<Rgba> = OnceLock::new();
+    BASE.get_or_init(|| decode_png(BASE_ICON).expect("bundled tray icon is not a valid PNG"))
+}
+
+fn badge_color(state: HealthState) -> Option<[u8; 3]> {
+    match state {
+        HealthState::Unknown => None,
+        HealthState::Up => Some([46, 204, 113]),
+        HealthState::Degraded => Some([241, 196, 15]),
+        HealthState::Down => Some([231, 76, 60]),
+    }
+}
+
+// Rim and empty-bar colour: dark on light taskbars, light on dark ones
+fn outline(theme: Theme) -> [u8; 3] {
+    match theme {
+        Theme::Light => [30, 30, 30],
+        Theme::Dark => [225, 225, 225],
+    }
+}
+
+// Filled circle in the bottom-right corner with a rim so it reads on any
+// menu bar colour
+fn draw_badge(image: &mut Rgba, color: [u8; 3], rim_color: [u8; 3]) {
+    let size = image.width.min(image.height) as f32;
+    let radius = size * 0.22;
+    let rim = (size * 0.06).max(1.0);
+    let cx = image.width as f32 - radius - rim;
+    let cy = image.height as f32 - radius - rim;
+
+    for y in 0..image.height {
+        for x in 0..image.width {
+            let dx = x as f32 + 0.5 - cx;
+            let dy = y as f32 + 0.5 - cy;
+            let distance = (dx * dx + dy * dy).sqrt();
+            let rgb = if distance <= radius {
+                color
+            } else if distance <= radius + rim {
+                rim_color
+            } else {
+                continue;
+            };
+            let i = ((y * image.width + x) * 4) as usize;
+            image.pixels[i..i + 4].copy_from_slice(&[rgb[0], rgb[1], rgb[2], 255]);
+        }
+    }
+}
+
+// Bar along the bottom edge, filled left to right
+fn draw_progress(image: &mut Rgba, percent: f64, track: [u8; 3]) {
+    let height = (image.height / 8).max(2);
+    let filled = (image.width as f64 * percent.clamp(0.0, 100.0) / 100.0).round() as u32;
+    for y in image.height - height..image.height {
+        for x in 0..image.width {
+            let rgb = if x < filled { [52, 152, 219] } else { track };
+            let i = ((y * image.width + x) * 4) as usize;
+            image.pixels[i..i + 4].copy_from_slice(&[rgb[0], rgb[1], rgb[2], 255]);
+        }
+    }
+}
+
+pub fn render(state: HealthState, progress: Option<f64>, theme: Theme) -> Icon {
+    let base = base();
+    let mut image = Rgba {
+        width: base.width,
+        height: base.height,
+        pixels: base.pixels.clone(),
+    };
+    if let Some(color) = badge_color(state) {
+        draw_badge(&mut image, color, outline(theme));
+    }
+    if let Some(percent) = progress {
+        draw_progress(&mut image, percent, outline(theme));
+    }
+    Icon::Rgba {
+        rgba: image.pixels,
+        width: image.width,
+        height: image.height,
+    }
+}
+
+// Monochrome glyph for macOS: the dark lettering becomes opaque, the
+// background transparent
+#[cfg(target_os = "macos")]
+pub fn render_template() -> Icon {
+    let base = base();
+    let rgba = base
+        .pixels
+        .chunks_exact(4)
+        .flat_map(|p| {
+            let luma = 0.2126 * p[0] as f32 + 0.7152 * p[1] as f32 + 0.0722 * p[2] as f32;
+            let alpha = ((160.0 - luma) / 140.0 * 255.0).clamp(0.0, 255.0) as u8;
+            [0, 0, 0, alpha.min(p[3])]
+        })
+        .collect();
+    Icon::Rgba {
+        rgba,
+        width: base.width,
+        height: base.height,
+    }
+}
+
+// Redraw from the current health and job progress
+pub fn refresh(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    if let Err(e) = platform::set_icon(app, state.health.snapshot().state, state.jobs.overall()) {
+        tracing::warn!("Failed to update tray icon: {}", e);
+    }
+}
@@ -0,0 +1,259 @@
+// System tray menu and click handling
+
+pub mod custom;
+pub mod icon;
+pub mod platform;
+pub mod status;
+
+use serde::{Deserialize, Serialize};
+use tauri::{
+    AppHandle, CustomMenuItem, Manager, SystemTray, SystemTrayEvent, SystemTrayHandle,
+    SystemTrayMenu, SystemTrayMenuItem, SystemTraySubmenu,
+};
+
+use crate::autostart;
+use crate::backend;
+use crate::external;
+use crate::i18n;
+use crate::profiles;
+use crate::recent;
+use crate::settings::Settings;
+use crate::state::AppState;
+use crate::updater::{self, UpdateInfo};
+use crate::window;
+
+use self::custom::MenuEntry;
+
+const TRAY_ID: &str = "main";
+// Prefix for the per-profile entries in the profile submenu
+const PROFILE_PREFIX: &str = "profile:";
+const RECENT_PREFIX: &str = "recent:";
+// Disabled line showing backend version/uptime, retitled by `status`
+pub const INFO_ITEM: &str = "backend-info";
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LeftClick {
+    // Hide when the window is visible and focused, show it otherwise
+    Toggle,
+    // Always bring the window forward (the old behaviour)
+    Show,
+}
+
+// macOS menu bar items conventionally just open; elsewhere a toggle is expected
+impl Default for LeftClick {
+    fn default() -> Self {
+        if cfg!(target_os = "macos") {
+            LeftClick::Show
+        } else {
+            LeftClick::Toggle
+        }
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TraySettings {
+    pub left_click: LeftClick,
+}
+
+pub fn system_tray() -> SystemTray {
+    let settings = Settings::default();
+    SystemTray::new().with_id(TRAY_ID).with_menu(menu(
+        &settings,
+        &status::not_connected(&settings),
+        &[],
+        None,
+        false,
+        false,
+    ))
+}
+
+// None when running without a tray (see `platform::available`)
+pub fn handle(app: &AppHandle) -> Option<SystemTrayHandle> {
+    app.tray_handle_by_id(TRAY_ID)
+}
+
+fn menu(
+    settings: &Settings,
+    info: &str,
+    custom: &[MenuEntry],
+    update: Option<&UpdateInfo>,
+    ready: bool,
+    autostart: bool,
+) -> SystemTrayMenu {
+    let language = i18n::language(settings);
+    let label = |key| i18n::lookup(language, key);
+    let info = CustomMenuItem::new(INFO_ITEM.to_string(), info).disabled();
+    let quit = CustomMenuItem::new("quit".to_string(), label("tray.quit"));
+    let hide = CustomMenuItem::new("hide".to_string(), label("tray.hide"));
+    let show = CustomMenuItem::new("show".to_string(), label("tray.show"));
+    let docs = CustomMenuItem::new("docs".to_string(), label("tray.docs"));
+    let status = CustomMenuItem::new("status".to_string(), label("tray.status"));
+    let restart = CustomMenuItem::new("restart".to_string(), label("tray.restart"));
+    let widget = CustomMenuItem::new("widget".to_string(), label("tray.widget"));
+    let console = CustomMenuItem::new("console".to_string(), label("tray.console"));
+    let mut on_top = CustomMenuItem::new("always-on-top".to_string(), label("tray.always_on_top"));
+    if settings.window.always_on_top {
+        on_top = on_top.selected();
+    }
+    let mut login = CustomMenuItem::new("autostart".to_string(), label("tray.autostart"));
+    if autostart {
+        login = login.selected();
+    }
+
+    let mut menu = SystemTrayMenu::new().add_item(info);
+    if ready {
+        let title = label("tray.update_ready");
+        menu = menu.add_item(CustomMenuItem::new("update-install".to_string(), title));
+    } else if let Some(update) = update {
+        let title = i18n::format(language, "tray.update", &[&update.version]);
+        menu = menu.add_item(CustomMenuItem::new("update".to_string(), title));
+    }
+    let mut menu = menu
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(show)
+        .add_item(hide)
+        .add_item(on_top)
+        .add_item(login)
+        .add_item(widget)
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(status)
+        .add_item(docs)
+        .add_item(console)
+        .add_item(restart)
+        .add_submenu(profile_submenu(settings))
+        .add_submenu(recent_submenu(settings));
+    if !custom.is_empty() {
+        menu = custom::append(menu.add_native_item(SystemTrayMenuItem::Separator), custom);
+    }
+    menu.add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(quit)
+}
+
+// The submenu title doubles as the active-profile indicator
+fn profile_submenu(settings: &Settings) -> SystemTraySubmenu {
+    let active = profiles::active_name(settings);
+    let mut submenu = SystemTrayMenu::new();
+    for profile in profiles::all(settings) {
+        let mut item = CustomMenuItem::new(
+            format!("{}{}", PROFILE_PREFIX, profile.name),
+            profile.name.clone(),
+        );
+        if profile.name == active {
+            item = item.selected();
+        }
+        submenu = submenu.add_item(item);
+    }
+    let title = i18n::format(i18n::language(settings), "tray.profile", &[&active]);
+    SystemTraySubmenu::new(title, submenu)
+}
+
+fn recent_submenu(settings: &Settings) -> SystemTraySubmenu {
+    let language = i18n::language(settings);
+    let mut submenu = SystemTrayMenu::new();
+    if settings.recent.is_empty() {
+        let empty = i18n::lookup(language, "tray.recent_empty");
+        submenu = submenu.add_item(CustomMenuItem::new("recent-empty", empty).disabled());
+    }
+    for item in &settings.recent {
+        submenu = submenu.add_item(CustomMenuItem::new(
+            format!("{}{}", RECENT_PREFIX, item.id),
+            item.label.clone(),
+        ));
+    }
+    SystemTraySubmenu::new(i18n::lookup(language, "tray.recent"), submenu)
+}
+
+// Re-render the menu after anything it displays has changed
+pub fn rebuild(app: &AppHandle) {
+    let Some(tray) = handle(app) else {
+        return;
+    };
+    let state = app.state::<AppState>();
+    let settings = state.settings.get();
+    let custom = state.tray_menu.entries();
+    let update = state.updater.available();
+    let _ = tray.set_menu(menu(
+        &settings,
+        &status::backend_info(app),
+        &custom,
+        update.as_ref(),
+        state.updater.ready().is_some(),
+        autostart::is_enabled(app),
+    ));
+}
+
+pub fn on_event(app: &AppHandle, event: SystemTrayEvent) {
+    match event {
+        SystemTrayEvent::LeftClick {
+            position: _,
+            size: _,
+            ..
+        } => {
+            let window = app.get_window("main").unwrap();
+            let state = app.state::<AppState>();
+            let left_click = state.settings.get().tray.left_click;
+            let in_front =
+                window.is_visible().unwrap_or(false) && window.is_focused().unwrap_or(false);
+            if left_click == LeftClick::Toggle && in_front && !state.kiosk.is_active() {
+                window.hide().unwrap();
+            } else {
+                window::activate(app);
+            }
+        }
+        SystemTrayEvent::MenuItemClick { id, .. } => match id.as_str() {
+            "quit" => window::quit(app),
+            "hide" if app.state::<AppState>().kiosk.is_active() => {}
+            "hide" => {
+                let window = app.get_window("main").unwrap();
+                window.hide().unwrap();
+            }
+            "show" => {
+                window::activate(app);
+            }
+            "docs" => external::open_docs(app),
+            "always-on-top" => {
+                let on_top = app.state::<AppState>().settings.get().window.always_on_top;
+                if let Err(e) = window::pin(app, !on_top) {
+                    tracing::warn!("{}", e);
+                }
+            }
+            "autostart" => {
+                if let Err(e) = autostart::toggle(app) {
+                    tracing::warn!("{}", e);
+                }
+            }
+            "console" => {
+                if let Err(e) = window::console::open(app) {
+                    tracing::warn!("{}", e);
+                }
+            }
+            "widget" => {
+                if let Err(e) = window::widget::toggle(app) {
+                    tracing::warn!("{}", e);
+                }
+            }
+            "restart" => {
+                if let Err(e) = backend::restart(app, "restart requested from tray") {
+                    tracing::warn!("{}", e);
+                }
+            }
+            "status" => window::navigate(app, "/status"),
+            "update" => window::navigate(app, "/updates"),
+            "update-install" => updater::prompt_restart(app),
+            other => {
+                if let Some(name) = other.strip_prefix(PROFILE_PREFIX) {
+                    if let Err(e) = profiles::switch(app, name) {
+                        tracing::warn!("{}", e);
+                    }
+                } else if let Some(id) = other.strip_prefix(RECENT_PREFIX) {
+                    recent::open(app, id);
+                } else if let Some(id) = other.strip_prefix(custom::PREFIX) {
+                    custom::on_click(app, id);
+                }
+            }
+        },
+        _ => {}
+    }
+}
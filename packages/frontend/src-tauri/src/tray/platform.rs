@@ -0,0 +1,73 @@
+// Per-OS tray differences: whether a tray exists at all, which icon size to
+// start from, and macOS template icons
+
+use tauri::AppHandle;
+
+use crate::health::HealthState;
+use crate::theme;
+
+// Set to skip the tray entirely (e.g. minimal window managers without one)
+const DISABLE_ENV: &str = "AETHERFRAME_NO_TRAY";
+
+// Windows and macOS scale the icon down for 100-200% displays; a large
+// source keeps it crisp. The Linux indicator wants small icons.
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+pub const BASE_ICON: &[u8] = include_bytes!("../../icons/128x128.png");
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub const BASE_ICON: &[u8] = include_bytes!("../../icons/32x32.png");
+
+pub fn available() -> bool {
+    if std::env::var_os(DISABLE_ENV).is_some() {
+        return false;
+    }
+    has_indicator_library()
+}
+
+// tauri panics at startup when neither appindicator flavour can be loaded, so
+// probe for it first and run without a tray instead
+#[cfg(target_os = "linux")]
+fn has_indicator_library() -> bool {
+    const LIBRARIES: [&[u8]; 4] = [
+        b"libayatana-appindicator3.so.1\0",
+        b"libayatana-appindicator3.so\0",
+        b"libappindicator3.so.1\0",
+        b"libappindicator3.so\0",
+    ];
+    LIBRARIES.iter().any(|name| unsafe {
+        let handle = libc::dlopen(name.as_ptr().cast(), libc::RTLD_LAZY);
+        if handle.is_null() {
+            return false;
+        }
+        libc::dlclose(handle);
+        true
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn has_indicator_library() -> bool {
+    true
+}
+
+// macOS tints template icons to match the menu bar, but drops all colour, so
+// only use one while there's nothing coloured to show
+#[cfg(target_os = "macos")]
+pub fn set_icon(app: &AppHandle, state: HealthState, progress: Option<f64>) -> tauri::Result<()> {
+    let Some(tray) = super::handle(app) else {
+        return Ok(());
+    };
+    let template = progress.is_none() && matches!(state, HealthState::Unknown | HealthState::Up);
+    if template {
+        tray.set_icon(super::icon::render_template())?;
+    } else {
+        tray.set_icon(super::icon::render(state, progress, theme::system(app)))?;
+    }
+    tray.set_icon_as_template(template)
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn set_icon(app: &AppHandle, state: HealthState, progress: Option<f64>) -> tauri::Result<()> {
+    let Some(tray) = super::handle(app) else {
+        return Ok(());
+    };
+    tray.set_icon(super::icon::render(state, progress, theme::system(app)))
+}
@@ -0,0 +1,146 @@
+// Periodic tray status: health, running jobs and backend memory in the tooltip,
+// backend version and uptime in the info menu item
+
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+
+use crate::health::{HealthSnapshot, HealthState};
+use crate::i18n;
+use crate::settings::Settings;
+use crate::state::AppState;
+use crate::util::now_millis;
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last = String::new();
+        let mut last_info = String::new();
+        loop {
+            let state = app.state::<AppState>();
+            if state.backend.is_stopping() {
+                break;
+            }
+            let Some(tray) = super::handle(&app) else {
+                break;
+            };
+            let line = summary(&app);
+            // Linux tray implementations have no tooltip; tauri ignores the call there
+            if line != last {
+                if let Err(e) = tray.set_tooltip(&line) {
+                    tracing::warn!("Failed to update tray tooltip: {}", e);
+                }
+                last = line;
+            }
+            let info = backend_info(&app);
+            if info != last_info {
+                let item = tray.get_item(super::INFO_ITEM);
+                if let Err(e) = item.set_title(&info) {
+                    tracing::warn!("Failed to update tray menu: {}", e);
+                }
+                last_info = info;
+            }
+            tokio::time::sleep(REFRESH_INTERVAL).await;
+        }
+    });
+}
+
+fn running_jobs(health: &HealthSnapshot) -> Option<u64> {
+    health
+        .status
+        .as_ref()?
+        .pointer("/metrics/jobs_by_status/running")?
+        .as_u64()
+}
+
+// e.g. "Backend: running · 3 jobs · 212 MB RAM"
+pub fn summary(app: &AppHandle) -> String {
+    let state = app.state::<AppState>();
+    let language = i18n::language(&state.settings.get());
+    let health = state.health.snapshot();
+    let label = i18n::lookup(
+        language,
+        match health.state {
+            HealthState::Unknown => "status.starting",
+            HealthState::Up => "status.running",
+            HealthState::Degraded => "status.degraded",
+            HealthState::Down => "status.down",
+        },
+    );
+    let mut parts = vec![i18n::format(language, "status.backend", &[label])];
+    if health.state != HealthState::Down {
+        if let Some(jobs) = running_jobs(&health) {
+            let key = if jobs == 1 {
+                "status.job_one"
+            } else {
+                "status.job_many"
+            };
+            parts.push(i18n::format(language, key, &[&jobs.to_string()]));
+        }
+    }
+    if let Some(percent) = state.jobs.overall() {
+        parts.push(format!("{:.0}%", percent));
+    }
+    // Only known for the sidecar we spawned
+    if let Some(metrics) = state
+        .backend
+        .pid()
+        .and_then(|pid| state.resources.latest(pid))
+    {
+        let megabytes = (metrics.memory_bytes / (1024 * 1024)).to_string();
+        parts.push(i18n::format(language, "status.memory", &[&megabytes]));
+    }
+    parts.join(" · ")
+}
+
+fn format_uptime(secs: u64) -> String {
+    let (days, hours, minutes) = (secs / 86_400, secs / 3_600 % 24, secs / 60 % 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+pub fn not_connected(settings: &Settings) -> String {
+    i18n::lookup(i18n::language(settings), "status.not_connected").to_string()
+}
+
+// e.g. "Backend 0.1.0 · up 2h 14m", from the last /status the health monitor saw
+pub fn backend_info(app: &AppHandle) -> String {
+    let state = app.state::<AppState>();
+    let settings = state.settings.get();
+    let language = i18n::language(&settings);
+    let health = state.health.snapshot();
+    let Some(status) = health.status.filter(|_| health.state != HealthState::Down) else {
+        return not_connected(&settings);
+    };
+    let version = status
+        .get("version")
+        .and_then(|v| v.as_str())
+        .unwrap_or_else(|| i18n::lookup(language, "status.unknown_version"));
+    // Advance the reported uptime by however long ago that /status was taken
+    let uptime = status
+        .get("uptime_sec")
+        .and_then(|v| v.as_f64())
+        .map(|secs| {
+            let age = health
+                .checked_at
+                .map(|at| now_millis().saturating_sub(at) / 1000)
+                .unwrap_or(0);
+            secs as u64 + age
+        });
+    match uptime {
+        Some(secs) => i18n::format(
+            language,
+            "status.version_uptime",
+            &[version, &format_uptime(secs)],
+        ),
+        None => i18n::format(language, "status.version", &[version]),
+    }
+}
@@ -0,0 +1,181 @@
+// Delta updates: when the feed lists a patch from the running version, the
+// new package is rebuilt from the one that version was installed from, which
+// `install` keeps under `updates/base`. The result goes through the same
+// signature check as a full download, so a bad patch only costs a retry.
+//
+// Feed entries may carry, next to Tauri's own fields,
+// `"deltas": [{"from": "1.2.0", "url": "...", "sha256": "..."}]`,
+// each a bipatch (bidiff) patch against the `from` version's package.
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use super::download::updates_dir;
+use super::UpdateInfo;
+use crate::http::transfer;
+use crate::state::AppState;
+use crate::util::write_atomic;
+use crate::workers::Priority;
+
+pub const BASE_DIR: &str = "base";
+const BASE_FILE: &str = "base.json";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Deserialize)]
+struct Feed {
+    #[serde(default)]
+    platforms: HashMap<String, Platform>,
+}
+
+#[derive(Deserialize)]
+struct Platform {
+    #[serde(default)]
+    deltas: Vec<Patch>,
+}
+
+#[derive(Deserialize)]
+struct Patch {
+    from: String,
+    url: String,
+    sha256: Option<String>,
+}
+
+// Which version the package in `updates/base` installs
+#[derive(Serialize, Deserialize)]
+struct Base {
+    version: String,
+    file: String,
+}
+
+// Copy the package about to be installed into `updates/base` so the next
+// update can patch it. Only trusted once that version is the one running, so
+// a failed install just wastes the space.
+pub fn keep_as_base(app: &AppHandle, package: &Path, version: &str) -> Result<(), String> {
+    let dir = updates_dir(app)
+        .ok_or("No app cache directory")?
+        .join(BASE_DIR);
+    let file = package
+        .file_name()
+        .ok_or("Update package has no file name")?
+        .to_string_lossy()
+        .to_string();
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let dest = dir.join(&file);
+    fs::copy(package, &dest).map_err(|e| e.to_string())?;
+    let base = Base {
+        version: version.to_string(),
+        file,
+    };
+    let json = serde_json::to_vec(&base).map_err(|e| e.to_string())?;
+    write_atomic(&dir.join(BASE_FILE), &json).map_err(|e| e.to_string())
+}
+
+// The package the running version was installed from, if we still have it
+fn base(app: &AppHandle) -> Option<PathBuf> {
+    let dir = updates_dir(app)?.join(BASE_DIR);
+    let text = fs::read_to_string(dir.join(BASE_FILE)).ok()?;
+    let base: Base = serde_json::from_str(&text).ok()?;
+    if base.version != app.package_info().version.to_string() {
+        return None;
+    }
+    let path = dir.join(Path::new(&base.file).file_name()?);
+    path.is_file().then_some(path)
+}
+
+// The feed URL with the placeholders Tauri fills in for its own requests
+fn feed_url(app: &AppHandle, target: &str) -> String {
+    let (os, arch) = target.split_once('-').unwrap_or((target, ""));
+    app.state::<AppState>()
+        .settings
+        .get()
+        .updater
+        .feed()
+        .replace(
+            "{{current_version}}",
+            &app.package_info().version.to_string(),
+        )
+        .replace("{{target}}", os)
+        .replace("{{arch}}", arch)
+}
+
+async fn find_patch(app: &AppHandle, target: &str) -> Result<Option<Patch>, String> {
+    let feed: Feed = app
+        .state::<AppState>()
+        .http
+        .client()
+        .get(feed_url(app, target))
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Update feed unavailable: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Unexpected update feed: {}", e))?;
+    let current = app.package_info().version.to_string();
+    let mut platforms = feed.platforms;
+    Ok(platforms
+        .remove(target)
+        .and_then(|platform| platform.deltas.into_iter().find(|d| d.from == current)))
+}
+
+fn apply(base: &Path, patch: &Path, dest: &Path) -> io::Result<()> {
+    let patch = BufReader::new(File::open(patch)?);
+    let base = BufReader::new(File::open(base)?);
+    let mut reader = bipatch::Reader::new(patch, base)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+    let mut tmp = dest.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    let mut out = BufWriter::new(File::create(&tmp)?);
+    io::copy(&mut reader, &mut out)?;
+    out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+    fs::rename(&tmp, dest)
+}
+
+// Build `dest` from a patch; Ok(false) when there's no usable patch and the
+// full package has to be downloaded
+pub async fn fetch(
+    app: &AppHandle,
+    info: &UpdateInfo,
+    dest: &Path,
+    request_id: &str,
+) -> Result<bool, String> {
+    let (Some(base), Some(target)) = (base(app), tauri::updater::target()) else {
+        return Ok(false);
+    };
+    let Some(patch) = find_patch(app, &target).await? else {
+        return Ok(false);
+    };
+    super::downloading(app, info, request_id, true);
+    let mut patch_path = dest.as_os_str().to_owned();
+    patch_path.push(".patch");
+    let patch_path = PathBuf::from(patch_path);
+    let request = app.state::<AppState>().http.client().get(&patch.url);
+    transfer::download(
+        app,
+        request,
+        patch_path.clone(),
+        patch.sha256.as_deref(),
+        Some(request_id.to_string()),
+    )
+    .await?;
+    let (patch_file, out) = (patch_path.clone(), dest.to_path_buf());
+    let applied = app
+        .state::<AppState>()
+        .workers
+        .run(Priority::Normal, "apply update patch", move || {
+            apply(&base, &patch_file, &out).map_err(|e| e.to_string())
+        })
+        .await;
+    let _ = fs::remove_file(&patch_path);
+    applied.map_err(|e| format!("Failed to apply update patch: {}", e))?;
+    Ok(true)
+}
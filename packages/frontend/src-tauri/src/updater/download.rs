@@ -0,0 +1,155 @@
+// Background download of a shell update into the cache dir, checked against
+// the release signature before it is offered for install. Interrupted
+// downloads resume where they stopped (see `transfer::download`), and a delta
+// patch is tried first where the feed has one (see `delta`).
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use minisign_verify::{PublicKey, Signature};
+use tauri::{AppHandle, Manager};
+
+use super::{delta, Ready, Release, UpdateInfo};
+use crate::backend::logs::{self, Level};
+use crate::http::transfer;
+use crate::notifications;
+use crate::state::AppState;
+use crate::tray;
+use crate::workers::Priority;
+
+const DIR_NAME: &str = "updates";
+
+pub fn updates_dir(app: &AppHandle) -> Option<PathBuf> {
+    app.path_resolver()
+        .app_cache_dir()
+        .map(|dir| dir.join(DIR_NAME))
+}
+
+// Tauri's signatures are base64 over minisign's text format, keys likewise
+fn decode(value: &str, what: &str) -> Result<String, String> {
+    STANDARD
+        .decode(value.trim())
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .ok_or_else(|| format!("Malformed update {}", what))
+}
+
+pub fn verify(app: &AppHandle, path: &Path, signature: &str) -> Result<(), String> {
+    let pubkey = &app.config().tauri.updater.pubkey;
+    if pubkey.trim().is_empty() {
+        return Err("This build has no update signing key".to_string());
+    }
+    let key = PublicKey::decode(&decode(pubkey, "signing key")?)
+        .map_err(|e| format!("Invalid update signing key: {}", e))?;
+    let signature = Signature::decode(&decode(signature, "signature")?)
+        .map_err(|e| format!("Invalid update signature: {}", e))?;
+    let bytes = std::fs::read(path).map_err(|e| format!("Can't read update: {}", e))?;
+    key.verify(&bytes, &signature, true)
+        .map_err(|e| format!("Update signature does not match: {}", e))
+}
+
+// Last path segment of the artifact URL, e.g. "AetherFrame_1.2.0_x64-setup.exe.zip"
+fn file_name(url: &str) -> String {
+    url.split(['?', '#'])
+        .next()
+        .and_then(|path| path.rsplit('/').next())
+        .filter(|name| !name.is_empty() && !name.contains(['\\', ':']) && *name != "..")
+        .unwrap_or("update.bin")
+        .to_string()
+}
+
+// Drops downloads for any other version; the base for delta updates stays
+fn clean(dir: &Path, keep: &str) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if entry.file_name() != keep && entry.file_name() != delta::BASE_DIR {
+            let _ = std::fs::remove_dir_all(entry.path());
+        }
+    }
+}
+
+pub async fn fetch(app: &AppHandle, info: UpdateInfo, release: Release) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let updater = &state.updater;
+    if updater.downloading.swap(true, Ordering::SeqCst) {
+        return Ok(());
+    }
+    let result = download(app, &info, &release).await;
+    updater.downloading.store(false, Ordering::SeqCst);
+    let path = result?;
+
+    *updater.ready.lock().unwrap() = Some(Ready {
+        info: info.clone(),
+        path,
+        signature: release.signature,
+    });
+    logs::internal(
+        app,
+        Level::Info,
+        format!("Update {} downloaded, ready to install", info.version),
+    );
+    tray::rebuild(app);
+    notifications::update_ready(app, &info.version);
+    let _ = app.emit_all("update-ready", &info);
+    Ok(())
+}
+
+async fn download(
+    app: &AppHandle,
+    info: &UpdateInfo,
+    release: &Release,
+) -> Result<PathBuf, String> {
+    let root = updates_dir(app).ok_or("No app cache directory")?;
+    clean(&root, &info.version);
+    let dest = root.join(&info.version).join(file_name(&release.url));
+    let id = format!("update:{}", info.version);
+    // Left over from a run that didn't get to install it
+    if dest.exists() {
+        verify_or_remove(app, &dest, &release.signature).await?;
+        return Ok(dest);
+    }
+    // A patch is much smaller; any trouble with it falls back to the full package
+    match delta::fetch(app, info, &dest, &id).await {
+        Ok(false) => {}
+        Ok(true) => match verify_or_remove(app, &dest, &release.signature).await {
+            Ok(()) => return Ok(dest),
+            Err(e) => delta_failed(app, e),
+        },
+        Err(e) => delta_failed(app, e),
+    }
+    super::downloading(app, info, &id, false);
+    let request = app.state::<AppState>().http.client().get(&release.url);
+    transfer::download(app, request, dest.clone(), None, Some(id)).await?;
+    verify_or_remove(app, &dest, &release.signature).await?;
+    Ok(dest)
+}
+
+fn delta_failed(app: &AppHandle, error: String) {
+    logs::internal(
+        app,
+        Level::Warning,
+        format!(
+            "Delta update failed, downloading the full package: {}",
+            error
+        ),
+    );
+}
+
+async fn verify_or_remove(app: &AppHandle, path: &Path, signature: &str) -> Result<(), String> {
+    let (handle, file, signature) = (app.clone(), path.to_path_buf(), signature.to_string());
+    let verified = app
+        .state::<AppState>()
+        .workers
+        .run(Priority::Normal, "verify update", move || {
+            verify(&handle, &file, &signature)
+        })
+        .await;
+    if verified.is_err() {
+        let _ = std::fs::remove_file(path);
+    }
+    verified
+}
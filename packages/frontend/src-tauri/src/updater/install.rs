@@ -0,0 +1,130 @@
+// Installing a downloaded update. Tauri 1's updater can only download and
+// install in one step, so the file we already have is handed to it through a
+// one-shot server on 127.0.0.1: its per-platform installer (and signature
+// check) does the work without a second download.
+
+use serde_json::json;
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use super::{delta, Ready};
+use crate::backend::logs::{self, Level};
+use crate::crash;
+use crate::state::AppState;
+
+const MANIFEST_PATH: &str = "/manifest.json";
+const PACKAGE_PATH: &str = "/package";
+
+// Install and never return on success: Windows installers take over the
+// process, elsewhere the new version is started in our place
+pub async fn restart_to_apply(app: &AppHandle) -> Result<(), String> {
+    let ready = app
+        .state::<AppState>()
+        .updater
+        .ready()
+        .ok_or("No update has been downloaded")?;
+    let state = app.state::<AppState>();
+    state.windows.save();
+    // The installer replaces the sidecar too, so it must not be running
+    state.backend.shutdown().await;
+    if let Err(e) = install(app, &ready).await {
+        // The backend can't be brought back in this process; start over on
+        // the old version rather than stay half shut down
+        logs::internal(app, Level::Error, e.clone());
+        tracing::warn!("{}", e);
+    }
+    crash::clean_exit();
+    app.restart();
+    Ok(())
+}
+
+// Used on quit, after the backend has already stopped
+pub async fn install(app: &AppHandle, ready: &Ready) -> Result<(), String> {
+    let listener = TcpListener::bind(("127.0.0.1", 0))
+        .await
+        .map_err(|e| format!("Failed to stage update: {}", e))?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+    let manifest = json!({
+        "version": ready.info.version,
+        "notes": ready.info.notes,
+        "url": format!("http://127.0.0.1:{}{}", port, PACKAGE_PATH),
+        "signature": ready.signature,
+    })
+    .to_string();
+    // Kept for the next update's delta; a failed copy just means no delta.
+    // Copied, not moved: if the install fails, `ready` still has to point at
+    // a package so it can be tried again (and on Windows a successful install
+    // never returns here to move it afterwards)
+    if let Err(e) = delta::keep_as_base(app, &ready.path, &ready.info.version) {
+        tracing::warn!(
+            "Not keeping update {} for deltas: {}",
+            ready.info.version,
+            e
+        );
+    }
+    let package = ready.path.clone();
+    let server = tauri::async_runtime::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            serve(stream, &manifest, &package).await;
+        }
+    });
+
+    logs::internal(
+        app,
+        Level::Info,
+        format!("Installing update {}", ready.info.version),
+    );
+    let result = async {
+        let update = tauri::updater::builder(app.clone())
+            .skip_events()
+            .endpoints(&[format!("http://127.0.0.1:{}{}", port, MANIFEST_PATH)])
+            .check()
+            .await
+            .map_err(|e| format!("Failed to stage update: {}", e))?;
+        update
+            .download_and_install()
+            .await
+            .map_err(|e| format!("Failed to install update: {}", e))
+    }
+    .await;
+    server.abort();
+    result
+}
+
+async fn serve(mut stream: TcpStream, manifest: &str, package: &std::path::Path) {
+    let mut buf = [0u8; 4096];
+    let Ok(read) = stream.read(&mut buf).await else {
+        return;
+    };
+    let request = String::from_utf8_lossy(&buf[..read]);
+    let path = request.split(' ').nth(1).unwrap_or_default();
+    let (status, kind, body) = match path {
+        MANIFEST_PATH => (
+            "200 OK",
+            "application/json",
+            Ok(manifest.as_bytes().to_vec()),
+        ),
+        PACKAGE_PATH => (
+            "200 OK",
+            "application/octet-stream",
+            tokio::fs::read(package).await,
+        ),
+        _ => ("404 Not Found", "text/plain", Ok(Vec::new())),
+    };
+    let Ok(body) = body else {
+        let _ = stream
+            .write_all(b"HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\n\r\n")
+            .await;
+        return;
+    };
+    let head = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        kind,
+        body.len()
+    );
+    let _ = stream.write_all(head.as_bytes()).await;
+    let _ = stream.write_all(&body).await;
+    let _ = stream.shutdown().await;
+}
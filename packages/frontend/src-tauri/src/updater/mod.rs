@@ -0,0 +1,364 @@
+// Shell updates through the Tauri updater, with a stable and a beta channel.
+// Checks run at startup and then periodically; the result is broadcast as
+// `update-available` and shown in the tray so either can prompt the user.
+// Releases are verified against `updater.pubkey` in tauri.conf.json, the public
+// half of the key CI signs with. A build without that key makes no checks at
+// all, since nothing it found could be installed. (`updater.active` has to stay
+// on for Tauri's updater API; the feed is picked per channel below, so the
+// config carries no endpoints.)
+// Updates are downloaded in the background and installed on the next quit, or
+// sooner when the user picks "Restart to apply", so running jobs aren't cut off.
+
+pub mod delta;
+pub mod download;
+pub mod install;
+pub mod notes;
+
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::api::dialog;
+use tauri::updater::Error as UpdaterError;
+use tauri::{AppHandle, Manager, State};
+
+use crate::backend::logs::{self, Level};
+use crate::i18n;
+use crate::state::AppState;
+use crate::tray;
+use crate::window;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+// Wait before the first check so it doesn't compete with backend startup
+const STARTUP_DELAY: Duration = Duration::from_secs(30);
+
+// Stable follows the newest full release; beta a rolling prerelease tag
+const STABLE_FEED: &str =
+    "https://github.com/ind4skylivey/aetherframe/releases/latest/download/latest.json";
+const BETA_FEED: &str =
+    "https://github.com/ind4skylivey/aetherframe/releases/download/beta/latest.json";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Channel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UpdaterSettings {
+    pub channel: Channel,
+    // Off leaves checking to `check_for_updates`
+    pub auto_check: bool,
+    pub check_interval_hours: u64,
+    // Fetch updates as soon as they're found; off waits for `download_update`
+    pub auto_download: bool,
+    // Self-hosted feed instead of the channel's; `{{target}}`, `{{arch}}` and
+    // `{{current_version}}` are filled in by the updater
+    pub feed_url: Option<String>,
+    // Feed for `update_backend`; defaults to the project's backend releases
+    pub backend_feed_url: Option<String>,
+}
+
+impl Default for UpdaterSettings {
+    fn default() -> Self {
+        Self {
+            channel: Channel::Stable,
+            auto_check: true,
+            check_interval_hours: 24,
+            auto_download: true,
+            feed_url: None,
+            backend_feed_url: None,
+        }
+    }
+}
+
+impl UpdaterSettings {
+    fn feed(&self) -> String {
+        match (&self.feed_url, self.channel) {
+            (Some(url), _) => url.clone(),
+            (None, Channel::Stable) => STABLE_FEED.to_string(),
+            (None, Channel::Beta) => BETA_FEED.to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct UpdateInfo {
+    pub version: String,
+    pub current_version: String,
+    pub channel: Channel,
+    // RFC 3339
+    pub date: Option<String>,
+    pub notes: Option<String>,
+}
+
+// Where this platform's package is, from the feed
+#[derive(Clone)]
+pub struct Release {
+    pub url: String,
+    pub signature: String,
+}
+
+// A verified package waiting to be installed
+#[derive(Clone)]
+pub struct Ready {
+    pub info: UpdateInfo,
+    pub path: PathBuf,
+    pub signature: String,
+}
+
+#[derive(Default)]
+pub struct Updater {
+    available: Mutex<Option<(UpdateInfo, Option<Release>)>>,
+    ready: Mutex<Option<Ready>>,
+    downloading: AtomicBool,
+}
+
+impl Updater {
+    pub fn available(&self) -> Option<UpdateInfo> {
+        let available = self.available.lock().unwrap();
+        available.as_ref().map(|(info, _)| info.clone())
+    }
+
+    pub fn ready(&self) -> Option<Ready> {
+        self.ready.lock().unwrap().clone()
+    }
+}
+
+pub fn can_install(app: &AppHandle) -> bool {
+    !app.config().tauri.updater.pubkey.trim().is_empty()
+}
+
+// Ask the channel's feed for a newer version. Only newer versions count, so
+// switching from beta back to stable waits for stable to catch up.
+pub async fn check(app: &AppHandle) -> Result<Option<UpdateInfo>, String> {
+    if !can_install(app) {
+        return Err("This build has no update signing key".to_string());
+    }
+    let settings = app.state::<AppState>().settings.get().updater;
+    // The updater keeps the package URL to itself; catch it on the way past
+    let release = Arc::new(Mutex::new(None));
+    let captured = release.clone();
+    let target = tauri::updater::target();
+    let result = tauri::updater::builder(app.clone())
+        .skip_events()
+        .endpoints(&[settings.feed()])
+        .timeout(REQUEST_TIMEOUT)
+        .should_install(move |current, remote| {
+            if let Some(target) = target {
+                if let (Ok(url), Ok(signature)) =
+                    (remote.download_url(&target), remote.signature(&target))
+                {
+                    *captured.lock().unwrap() = Some(Release {
+                        url: url.to_string(),
+                        signature: signature.clone(),
+                    });
+                }
+            }
+            remote.version() > current
+        })
+        .check()
+        .await;
+    let update = match result {
+        Ok(update) if update.is_update_available() => Some(UpdateInfo {
+            version: update.latest_version().to_string(),
+            current_version: update.current_version().to_string(),
+            channel: settings.channel,
+            date: update.date().map(|date| date.to_string()),
+            notes: update.body().cloned(),
+        }),
+        Ok(_) | Err(UpdaterError::UpToDate) => None,
+        Err(e) => return Err(format!("Update check failed: {}", e)),
+    };
+
+    let state = app.state::<AppState>();
+    let release = release.lock().unwrap().take();
+    let previous = std::mem::replace(
+        &mut *state.updater.available.lock().unwrap(),
+        update.clone().map(|info| (info, release.clone())),
+    );
+    let version = update.as_ref().map(|u| &u.version);
+    let changed = previous.as_ref().map(|(u, _)| &u.version) != version;
+    // A newer release (or a channel switch) supersedes what was downloaded
+    let downloaded = {
+        let mut ready = state.updater.ready.lock().unwrap();
+        if ready.as_ref().map(|r| &r.info.version) != version {
+            *ready = None;
+        }
+        ready.is_some()
+    };
+    if changed {
+        tray::rebuild(app);
+        if let Some(update) = &update {
+            logs::internal(
+                app,
+                Level::Info,
+                format!(
+                    "Update available: {} ({:?} channel)",
+                    update.version, update.channel
+                ),
+            );
+            let _ = app.emit_all("update-available", update);
+            // For "What's new" once it's installed
+            notes::remember(app, update).await;
+        }
+    }
+    if let (Some(update), Some(release)) = (&update, release) {
+        if settings.auto_download && !downloaded {
+            start_download(app, update.clone(), release);
+        }
+    }
+    Ok(update)
+}
+
+// Fire-and-forget check, e.g. after the channel changes
+pub fn check_in_background(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = check(&app).await {
+            tracing::warn!("{}", e);
+        }
+    });
+}
+
+#[derive(Clone, Serialize)]
+struct Downloading<'a> {
+    version: &'a str,
+    // Follow "transfer-progress" events with this id
+    request_id: &'a str,
+    delta: bool,
+}
+
+// Announces each download attempt, since a failed delta is followed by a full one
+fn downloading(app: &AppHandle, info: &UpdateInfo, request_id: &str, delta: bool) {
+    let _ = app.emit_all(
+        "update-downloading",
+        Downloading {
+            version: &info.version,
+            request_id,
+            delta,
+        },
+    );
+}
+
+fn start_download(app: &AppHandle, info: UpdateInfo, release: Release) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = download::fetch(&app, info, release).await {
+            logs::internal(&app, Level::Error, format!("Update download failed: {}", e));
+        }
+    });
+}
+
+pub fn start(app: AppHandle) {
+    if !can_install(&app) {
+        tracing::info!("No update signing key in this build, not checking for updates");
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(STARTUP_DELAY).await;
+        loop {
+            let settings = app.state::<AppState>().settings.get().updater;
+            if settings.auto_check {
+                if let Err(e) = check(&app).await {
+                    tracing::warn!("{}", e);
+                }
+            }
+            let hours = settings.check_interval_hours.max(1);
+            tokio::time::sleep(Duration::from_secs(hours * 3600)).await;
+        }
+    });
+}
+
+// Tray "Restart to apply": confirm first if it would cut jobs off
+pub fn prompt_restart(app: &AppHandle) {
+    let running = app.state::<AppState>().jobs.running().len();
+    if running == 0 {
+        restart_in_background(app);
+        return;
+    }
+    let language = i18n::language(&app.state::<AppState>().settings.get());
+    let title = i18n::lookup(language, "update.restart_title");
+    let message = i18n::format(language, "update.restart_message", &[&running.to_string()]);
+    let main = window::activate(app);
+    let handle = app.clone();
+    dialog::ask(main.as_ref(), title, message, move |restart| {
+        if restart {
+            restart_in_background(&handle);
+        }
+    });
+}
+
+fn restart_in_background(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = install::restart_to_apply(&app).await {
+            tracing::warn!("{}", e);
+        }
+    });
+}
+
+// Called from `window::quit` once the backend is down
+pub async fn install_pending(app: &AppHandle) {
+    let Some(ready) = app.state::<AppState>().updater.ready() else {
+        return;
+    };
+    if let Err(e) = install::install(app, &ready).await {
+        logs::internal(app, Level::Error, e.clone());
+        tracing::warn!("{}", e);
+    }
+}
+
+#[tauri::command]
+pub async fn check_for_updates(app: AppHandle) -> Result<Option<UpdateInfo>, String> {
+    check(&app).await
+}
+
+// Last known result without hitting the network
+#[tauri::command]
+pub async fn get_available_update(
+    state: State<'_, AppState>,
+) -> Result<Option<UpdateInfo>, String> {
+    Ok(state.updater.available())
+}
+
+#[tauri::command]
+pub async fn download_update(app: AppHandle) -> Result<(), String> {
+    let available = app
+        .state::<AppState>()
+        .updater
+        .available
+        .lock()
+        .unwrap()
+        .clone();
+    let Some((info, release)) = available else {
+        return Err("No update is available".to_string());
+    };
+    let release = release.ok_or("The update feed has no package for this platform")?;
+    download::fetch(&app, info, release).await
+}
+
+// Notes for the versions after `since_version`. Without it, the versions since
+// the last call, so "What's new" shows once per update.
+#[tauri::command]
+pub async fn get_release_notes(
+    app: AppHandle,
+    since_version: Option<String>,
+) -> Result<notes::ReleaseNotes, String> {
+    notes::get(&app, since_version).await
+}
+
+// Without `force`, refuses while backend jobs are running so the UI can confirm
+#[tauri::command]
+pub async fn install_update(app: AppHandle, force: Option<bool>) -> Result<(), String> {
+    let running = app.state::<AppState>().jobs.running().len();
+    if running > 0 && !force.unwrap_or(false) {
+        return Err(format!("{} jobs are still running", running));
+    }
+    install::restart_to_apply(&app).await
+}
@@ -0,0 +1,161 @@
+// "What's new": release notes for the versions installed since the user last
+// looked. Notes are cached in the local store, from the update feed as updates
+// come in and from the GitHub releases list when it's reachable, so they're
+// there on the first launch after an update even offline.
+
+use std::time::Duration;
+
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::{AppHandle, Manager};
+
+use super::{Channel, UpdateInfo};
+use crate::state::AppState;
+use crate::storage::blocking;
+
+const NAMESPACE: &str = "release-notes";
+// Last version whose notes were shown
+const SEEN_NAMESPACE: &str = "release-notes-seen";
+const SEEN_KEY: &str = "version";
+const RELEASES_URL: &str = "https://api.github.com/repos/ind4skylivey/aetherframe/releases";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ReleaseNote {
+    pub version: String,
+    // RFC 3339
+    pub date: Option<String>,
+    pub notes: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ReleaseNotes {
+    pub current_version: String,
+    pub since_version: Option<String>,
+    // Newest first
+    pub releases: Vec<ReleaseNote>,
+}
+
+#[derive(Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    body: Option<String>,
+    published_at: Option<String>,
+    draft: bool,
+}
+
+fn parse(version: &str) -> Option<Version> {
+    Version::parse(version.trim_start_matches('v')).ok()
+}
+
+fn save(state: &AppState, note: &ReleaseNote) -> Result<(), String> {
+    let value = serde_json::to_value(note).map_err(|e| e.to_string())?;
+    state.store.put(NAMESPACE, &note.version, value, None)
+}
+
+// Keep the notes of an update the feed announced
+pub async fn remember(app: &AppHandle, info: &UpdateInfo) {
+    let note = ReleaseNote {
+        version: info.version.clone(),
+        date: info.date.clone(),
+        notes: info.notes.clone(),
+    };
+    if let Err(e) = blocking(app.clone(), move |state| save(state, &note)).await {
+        tracing::warn!("Failed to cache release notes: {}", e);
+    }
+}
+
+// Fill in versions the feed skipped over (e.g. when updating 1.1 -> 1.3)
+async fn refresh(app: &AppHandle) -> Result<(), String> {
+    let releases: Vec<GithubRelease> = app
+        .state::<AppState>()
+        .http
+        .client()
+        .get(RELEASES_URL)
+        .header("Accept", "application/vnd.github+json")
+        .header("User-Agent", "AetherFrame")
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Release notes unavailable: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Unexpected releases response: {}", e))?;
+    let notes: Vec<ReleaseNote> = releases
+        .into_iter()
+        .filter(|release| !release.draft)
+        .filter_map(|release| {
+            Some(ReleaseNote {
+                version: parse(&release.tag_name)?.to_string(),
+                date: release.published_at,
+                notes: release.body,
+            })
+        })
+        .collect();
+    blocking(app.clone(), move |state| {
+        notes.iter().try_for_each(|note| save(state, note))
+    })
+    .await
+}
+
+// Notes for versions after `since` up to and including the running one
+async fn cached(app: &AppHandle, since: &Version) -> Result<Vec<ReleaseNote>, String> {
+    let current = parse(&app.package_info().version.to_string());
+    let settings = app.state::<AppState>().settings.get().updater;
+    // Beta builds follow prereleases; stable users only get full releases
+    let prereleases =
+        settings.channel == Channel::Beta || current.as_ref().is_some_and(|v| !v.pre.is_empty());
+    let entries = blocking(app.clone(), |state| state.store.list(NAMESPACE)).await?;
+    let mut notes: Vec<(Version, ReleaseNote)> = entries
+        .into_iter()
+        .filter_map(|entry| {
+            let note: ReleaseNote = serde_json::from_value(entry.value).ok()?;
+            let version = parse(&note.version)?;
+            let wanted = version > *since
+                && current.as_ref().map_or(true, |c| version <= *c)
+                && (prereleases || version.pre.is_empty());
+            wanted.then_some((version, note))
+        })
+        .collect();
+    notes.sort_by(|(a, _), (b, _)| b.cmp(a));
+    Ok(notes.into_iter().map(|(_, note)| note).collect())
+}
+
+pub async fn get(app: &AppHandle, since: Option<String>) -> Result<ReleaseNotes, String> {
+    let current_version = app.package_info().version.to_string();
+    let explicit = since.is_some();
+    let since_version = match since {
+        Some(since) => Some(since),
+        None => blocking(app.clone(), |state| {
+            state.store.get(SEEN_NAMESPACE, SEEN_KEY)
+        })
+        .await?
+        .and_then(|value| value.as_str().map(str::to_string)),
+    };
+    // Nothing recorded yet means a fresh install: there's nothing "new" to show
+    let since = since_version.as_deref().unwrap_or(&current_version);
+    let since = parse(since).ok_or_else(|| format!("Invalid version: {}", since))?;
+
+    let current = parse(&current_version);
+    if current.as_ref().is_some_and(|c| *c > since) {
+        if let Err(e) = refresh(app).await {
+            tracing::warn!("{}", e);
+        }
+    }
+    let releases = cached(app, &since).await?;
+    // Asking without a version is "show what's new", which happens once
+    if !explicit {
+        let seen = json!(current_version);
+        blocking(app.clone(), move |state| {
+            state.store.put(SEEN_NAMESPACE, SEEN_KEY, seen, None)
+        })
+        .await?;
+    }
+    Ok(ReleaseNotes {
+        current_version,
+        since_version,
+        releases,
+    })
+}
@@ -0,0 +1,112 @@
+// Small helpers shared across modules
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Milliseconds since the Unix epoch, the timestamp format used in all event payloads
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+// Write to a sibling temp file and rename it over the target, so a crash
+// mid-write never leaves a truncated file behind
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    fs::write(&tmp, contents)?;
+    fs::rename(&tmp, path)
+}
+
+// What the OS should run to start the app again (login items, URL handlers).
+// An AppImage's own path is a temporary mount; the file the user launched is
+// in $APPIMAGE.
+pub fn launcher() -> Result<PathBuf, String> {
+    if cfg!(target_os = "linux") {
+        if let Some(appimage) = std::env::var_os("APPIMAGE") {
+            return Ok(PathBuf::from(appimage));
+        }
+    }
+    std::env::current_exe().map_err(|e| format!("Can't find the app executable: {}", e))
+}
+
+// Desktop entry Exec quoting: double quotes, with \ " ` $ escaped
+#[cfg(all(unix, not(target_os = "macos")))]
+pub fn exec_quote(value: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in value.chars() {
+        if matches!(c, '\\' | '"' | '`' | '$') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+// A REG_SZ value under HKEY_CURRENT_USER, creating the key if needed; no name
+// sets the key's default value
+#[cfg(windows)]
+pub fn set_registry_string(key: &str, name: Option<&str>, value: &str) -> Result<(), String> {
+    use windows::core::{HSTRING, PCWSTR};
+    use windows::Win32::Foundation::ERROR_SUCCESS;
+    use windows::Win32::System::Registry::{RegSetKeyValueW, HKEY_CURRENT_USER, REG_SZ};
+
+    let name = name.map(HSTRING::from);
+    let data: Vec<u16> = value.encode_utf16().chain(Some(0)).collect();
+    let status = unsafe {
+        RegSetKeyValueW(
+            HKEY_CURRENT_USER,
+            &HSTRING::from(key),
+            name.as_ref().map_or(PCWSTR::null(), PCWSTR::from),
+            REG_SZ.0,
+            data.as_ptr().cast(),
+            (data.len() * 2) as u32,
+        )
+    };
+    if status != ERROR_SUCCESS {
+        return Err(format!("Failed to write HKCU\\{}: error {}", key, status.0));
+    }
+    Ok(())
+}
+
+pub struct Rgba {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+// Normalise whatever colour type the PNG uses to 8-bit RGBA
+pub fn decode_png(bytes: &[u8]) -> Result<Rgba, png::DecodingError> {
+    let mut decoder = png::Decoder::new(bytes);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info()?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf)?;
+    buf.truncate(info.buffer_size());
+
+    let pixels = match info.color_type {
+        png::ColorType::Rgba => buf,
+        png::ColorType::Rgb => buf
+            .chunks_exact(3)
+            .flat_map(|p| [p[0], p[1], p[2], 255])
+            .collect(),
+        png::ColorType::GrayscaleAlpha => buf
+            .chunks_exact(2)
+            .flat_map(|p| [p[0], p[0], p[0], p[1]])
+            .collect(),
+        _ => buf.iter().flat_map(|&g| [g, g, g, 255]).collect(),
+    };
+    Ok(Rgba {
+        width: info.width,
+        height: info.height,
+        pixels,
+    })
+}
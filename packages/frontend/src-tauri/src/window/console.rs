@@ -0,0 +1,154 @@
+// Live log console window: a plain page fed from the log ring buffer, so
+// backend output can be watched without devtools. Like the splash it has no JS
+// API bundle; lines are batched and pushed in via eval.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State, Window};
+
+use crate::backend::logs::LogLine;
+use crate::state::AppState;
+
+use super::open::{self, WindowOptions};
+
+pub const LABEL: &str = "console";
+const FLUSH_INTERVAL: Duration = Duration::from_millis(250);
+// Matches the ring buffer; anything older is dropped while paused
+const MAX_PENDING: usize = 1000;
+
+#[derive(Default)]
+pub struct LogConsole {
+    pending: Mutex<Vec<LogLine>>,
+    paused: AtomicBool,
+    scroll_lock: AtomicBool,
+}
+
+#[derive(Clone, Serialize)]
+pub struct ConsoleState {
+    paused: bool,
+    scroll_lock: bool,
+}
+
+impl LogConsole {
+    // Only buffer while the window exists; it catches up from the ring buffer on open
+    pub fn queue(&self, app: &AppHandle, line: &LogLine) {
+        if app.get_window(LABEL).is_none() {
+            return;
+        }
+        let mut pending = self.pending.lock().unwrap();
+        if pending.len() == MAX_PENDING {
+            pending.remove(0);
+        }
+        pending.push(line.clone());
+    }
+
+    fn take(&self) -> Vec<LogLine> {
+        if self.paused.load(Ordering::SeqCst) {
+            return Vec::new();
+        }
+        std::mem::take(&mut *self.pending.lock().unwrap())
+    }
+
+    fn state(&self) -> ConsoleState {
+        ConsoleState {
+            paused: self.paused.load(Ordering::SeqCst),
+            scroll_lock: self.scroll_lock.load(Ordering::SeqCst),
+        }
+    }
+}
+
+fn options() -> WindowOptions {
+    WindowOptions {
+        title: Some("AetherFrame — Logs".to_string()),
+        width: 900.0,
+        height: 420.0,
+        min_width: Some(480.0),
+        min_height: Some(200.0),
+        ..Default::default()
+    }
+}
+
+pub fn open(app: &AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_window(LABEL) {
+        let _ = window.unminimize();
+        let _ = window.show();
+        return window.set_focus().map_err(|e| e.to_string());
+    }
+    let state = app.state::<AppState>();
+    state.console.pending.lock().unwrap().clear();
+    let window = open::open(app, LABEL, "console.html", &options())?;
+    start(app.clone(), window);
+    Ok(())
+}
+
+fn start(app: AppHandle, window: Window) {
+    tauri::async_runtime::spawn(async move {
+        // Give the page a moment to load before the backlog goes in
+        tokio::time::sleep(FLUSH_INTERVAL).await;
+        let state = app.state::<AppState>();
+        push_state(&window, &state.console.state());
+        append(&window, &state.logs.tail(MAX_PENDING));
+        while app.get_window(LABEL).is_some() {
+            tokio::time::sleep(FLUSH_INTERVAL).await;
+            let lines = state.console.take();
+            if !lines.is_empty() {
+                append(&window, &lines);
+            }
+        }
+    });
+}
+
+fn append(window: &Window, lines: &[LogLine]) {
+    if let Ok(json) = serde_json::to_string(lines) {
+        let _ = window.eval(&format!("window.appendLogs && window.appendLogs({})", json));
+    }
+}
+
+fn push_state(window: &Window, state: &ConsoleState) {
+    if let Ok(json) = serde_json::to_string(state) {
+        let _ = window.eval(&format!(
+            "window.setConsoleState && window.setConsoleState({})",
+            json
+        ));
+    }
+}
+
+#[tauri::command]
+pub async fn open_log_console(app: AppHandle) -> Result<(), String> {
+    open(&app)
+}
+
+// Paused lines are held back (up to MAX_PENDING) and flushed on resume
+#[tauri::command]
+pub fn console_set_paused(
+    window: Window,
+    state: State<'_, AppState>,
+    paused: bool,
+) -> ConsoleState {
+    state.console.paused.store(paused, Ordering::SeqCst);
+    let console = state.console.state();
+    push_state(&window, &console);
+    console
+}
+
+// With scroll lock on the page stops following new lines
+#[tauri::command]
+pub fn console_set_scroll_lock(
+    window: Window,
+    state: State<'_, AppState>,
+    locked: bool,
+) -> ConsoleState {
+    state.console.scroll_lock.store(locked, Ordering::SeqCst);
+    let console = state.console.state();
+    push_state(&window, &console);
+    console
+}
+
+#[tauri::command]
+pub fn console_clear(window: Window, state: State<'_, AppState>) {
+    state.console.pending.lock().unwrap().clear();
+    let _ = window.eval("window.clearLogs && window.clearLogs()");
+}
@@ -0,0 +1,104 @@
+// Native translucent backgrounds for the main window: mica/acrylic/blur on
+// Windows, vibrancy on macOS. Whatever the platform can't do falls back to the
+// next best effect, down to none; the caller is told what was actually applied.
+// Only those two platforms get a transparent main window (tauri.windows.conf.json
+// and tauri.macos.conf.json); elsewhere nothing would fill in behind it.
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Window};
+use tokio::sync::oneshot;
+#[cfg(target_os = "macos")]
+use window_vibrancy::NSVisualEffectMaterial;
+
+use crate::state::AppState;
+
+use super::MAIN;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WindowEffect {
+    #[default]
+    None,
+    Mica,
+    Acrylic,
+    Blur,
+    Vibrancy,
+}
+
+// Best first; the requested effect decides where in the chain we start
+fn candidates(effect: WindowEffect) -> &'static [WindowEffect] {
+    use WindowEffect::*;
+    if effect == None {
+        return &[];
+    }
+    if cfg!(target_os = "macos") {
+        return &[Vibrancy];
+    }
+    if cfg!(windows) {
+        return match effect {
+            Acrylic => &[Acrylic, Blur],
+            Blur => &[Blur],
+            _ => &[Mica, Acrylic, Blur],
+        };
+    }
+    &[]
+}
+
+fn try_apply(window: &Window, effect: WindowEffect, dark: Option<bool>) -> Result<(), String> {
+    let result = match effect {
+        WindowEffect::Mica => window_vibrancy::apply_mica(window, dark),
+        WindowEffect::Acrylic => window_vibrancy::apply_acrylic(window, None),
+        WindowEffect::Blur => window_vibrancy::apply_blur(window, None),
+        #[cfg(target_os = "macos")]
+        WindowEffect::Vibrancy => window_vibrancy::apply_vibrancy(
+            window,
+            NSVisualEffectMaterial::UnderWindowBackground,
+            None,
+            None,
+        ),
+        _ => return Err(format!("{:?} is not supported on this platform", effect)),
+    };
+    result.map_err(|e| e.to_string())
+}
+
+// Windows effects stack badly, so drop whatever was there first. macOS has no
+// way to remove vibrancy again; turning it off there applies on next launch.
+fn clear(window: &Window) {
+    if cfg!(windows) {
+        let _ = window_vibrancy::clear_mica(window);
+        let _ = window_vibrancy::clear_acrylic(window);
+        let _ = window_vibrancy::clear_blur(window);
+    }
+}
+
+// Must run on the main thread
+pub fn apply(window: &Window, effect: WindowEffect, dark: Option<bool>) -> WindowEffect {
+    clear(window);
+    for candidate in candidates(effect) {
+        match try_apply(window, *candidate, dark) {
+            Ok(()) => return *candidate,
+            Err(e) => tracing::warn!("Window effect {:?} unavailable: {}", candidate, e),
+        }
+    }
+    WindowEffect::None
+}
+
+#[tauri::command]
+pub async fn set_window_effect(
+    app: AppHandle,
+    effect: WindowEffect,
+    dark: Option<bool>,
+) -> Result<WindowEffect, String> {
+    let main = app.get_window(MAIN).ok_or("Main window is not available")?;
+    let (tx, rx) = oneshot::channel();
+    let window = main.clone();
+    main.run_on_main_thread(move || {
+        let _ = tx.send(apply(&window, effect, dark));
+    })
+    .map_err(|e| e.to_string())?;
+    let applied = rx.await.map_err(|e| e.to_string())?;
+    app.state::<AppState>()
+        .settings
+        .update(|s| s.window.effect = effect)?;
+    Ok(applied)
+}
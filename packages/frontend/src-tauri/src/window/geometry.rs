@@ -0,0 +1,180 @@
+// Remembers window size/position/maximized state across launches, kept in its
+// own file since it changes far more often than settings.json
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tauri::{Monitor, PhysicalPosition, PhysicalSize, Window};
+
+use crate::util::write_atomic;
+
+const FILE_NAME: &str = "window-state.json";
+// How much of the title bar must land on a monitor for a saved position to count
+const MIN_VISIBLE: i32 = 64;
+// Gap kept to the screen edge when a window has to be shrunk to fit
+const EDGE_MARGIN: u32 = 32;
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowGeometry {
+    // Physical pixels, as reported by the OS
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub maximized: bool,
+    // Monitor the window was last on, to find it again after displays are rearranged
+    pub monitor: Option<String>,
+}
+
+pub struct WindowStateStore {
+    path: PathBuf,
+    windows: Mutex<HashMap<String, WindowGeometry>>,
+}
+
+impl WindowStateStore {
+    pub fn load(dir: PathBuf) -> Self {
+        let path = dir.join(FILE_NAME);
+        let windows = fs::read_to_string(&path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            windows: Mutex::new(windows),
+        }
+    }
+
+    pub fn get(&self, label: &str) -> Option<WindowGeometry> {
+        self.windows.lock().unwrap().get(label).cloned()
+    }
+
+    // Track the latest geometry in memory; `save` writes it out
+    pub fn record(&self, window: &Window) {
+        if window.is_minimized().unwrap_or(false) {
+            return;
+        }
+        let mut windows = self.windows.lock().unwrap();
+        let entry = windows.entry(window.label().to_string()).or_default();
+        if let Ok(Some(monitor)) = window.current_monitor() {
+            entry.monitor = monitor.name().cloned();
+        }
+        // Keep the restored (un-maximized) bounds so un-maximizing after restore works
+        entry.maximized = window.is_maximized().unwrap_or(false);
+        if entry.maximized {
+            return;
+        }
+        if let (Ok(position), Ok(size)) = (window.outer_position(), window.inner_size()) {
+            entry.x = position.x;
+            entry.y = position.y;
+            entry.width = size.width;
+            entry.height = size.height;
+        }
+    }
+
+    pub fn save(&self) {
+        let windows = self.windows.lock().unwrap();
+        let result = serde_json::to_string_pretty(&*windows)
+            .map_err(|e| e.to_string())
+            .and_then(|json| write_atomic(&self.path, json.as_bytes()).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            tracing::warn!("Failed to save window state: {}", e);
+        }
+    }
+}
+
+fn visible_on(geometry: &WindowGeometry, monitor: &Monitor) -> bool {
+    let (pos, size) = (monitor.position(), monitor.size());
+    let left = geometry.x.max(pos.x);
+    let right = (geometry.x + geometry.width as i32).min(pos.x + size.width as i32);
+    let top = geometry.y;
+    right - left >= MIN_VISIBLE && top >= pos.y && top < pos.y + size.height as i32
+}
+
+// Shrink to the monitor (resolution may have dropped since) and pull the
+// window fully back on screen
+fn clamp_to(geometry: &mut WindowGeometry, monitor: &Monitor) {
+    let (pos, size) = (monitor.position(), monitor.size());
+    let max_width = size.width.saturating_sub(EDGE_MARGIN).max(1);
+    let max_height = size.height.saturating_sub(EDGE_MARGIN).max(1);
+    geometry.width = geometry.width.clamp(1, max_width);
+    geometry.height = geometry.height.clamp(1, max_height);
+    let right = pos.x + (size.width - geometry.width) as i32;
+    let bottom = pos.y + (size.height - geometry.height) as i32;
+    geometry.x = geometry.x.clamp(pos.x, right);
+    geometry.y = geometry.y.clamp(pos.y, bottom);
+}
+
+fn center_in(geometry: &mut WindowGeometry, monitor: &Monitor) {
+    let (pos, size) = (monitor.position(), monitor.size());
+    geometry.x = pos.x + (size.width as i32 - geometry.width as i32) / 2;
+    geometry.y = pos.y + (size.height as i32 - geometry.height as i32) / 2;
+    clamp_to(geometry, monitor);
+}
+
+fn place(window: &Window, geometry: &WindowGeometry) {
+    let _ = window.set_size(PhysicalSize::new(geometry.width, geometry.height));
+    let _ = window.set_position(PhysicalPosition::new(geometry.x, geometry.y));
+}
+
+// Apply saved geometry before the window is first shown. If the monitor it was
+// on is gone or the position no longer lands on any screen, it's re-centred on
+// the monitor it was on (when still attached) or the primary one.
+pub fn restore(window: &Window, store: &WindowStateStore) {
+    let Some(mut geometry) = store.get(window.label()) else {
+        return;
+    };
+    if geometry.width == 0 || geometry.height == 0 {
+        let Ok(size) = window.inner_size() else {
+            return;
+        };
+        geometry.width = size.width;
+        geometry.height = size.height;
+    }
+    let monitors = window.available_monitors().unwrap_or_default();
+    if let Some(monitor) = monitors.iter().find(|m| visible_on(&geometry, m)) {
+        clamp_to(&mut geometry, monitor);
+    } else {
+        let previous = geometry
+            .monitor
+            .as_ref()
+            .and_then(|name| monitors.iter().find(|m| m.name() == Some(name)));
+        let fallback = window.primary_monitor().ok().flatten();
+        match previous.or(fallback.as_ref()).or(monitors.first()) {
+            Some(monitor) => center_in(&mut geometry, monitor),
+            // No monitor info at all; keep the size and let the window centre itself
+            None => {
+                let size = PhysicalSize::new(geometry.width, geometry.height);
+                let _ = window.set_size(size);
+                return;
+            }
+        }
+    }
+    place(window, &geometry);
+    if geometry.maximized {
+        let _ = window.maximize();
+    }
+}
+
+// Centre the window on another display, keeping its size where it fits
+pub fn move_to(window: &Window, monitor: &Monitor) -> Result<(), String> {
+    let maximized = window.is_maximized().unwrap_or(false);
+    if maximized {
+        window.unmaximize().map_err(|e| e.to_string())?;
+    }
+    let size = window.inner_size().map_err(|e| e.to_string())?;
+    let mut geometry = WindowGeometry {
+        width: size.width,
+        height: size.height,
+        ..Default::default()
+    };
+    center_in(&mut geometry, monitor);
+    place(window, &geometry);
+    if maximized {
+        window.maximize().map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
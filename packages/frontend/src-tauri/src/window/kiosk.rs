@@ -0,0 +1,114 @@
+// Kiosk mode for demos and presentations: borderless fullscreen main window
+// that can't be hidden or closed from the usual places
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Window};
+
+use crate::state::AppState;
+
+use super::MAIN;
+
+// Swallows the webview-level ways out (reload, close, fullscreen/devtools keys);
+// OS-level switches like Alt+Tab can't be blocked from here
+const BLOCK_SHORTCUTS: &str = r#"
+if (!window.__aetherframeKiosk) {
+  window.__aetherframeKiosk = { block: true };
+  window.addEventListener('keydown', function (e) {
+    if (!window.__aetherframeKiosk.block) return;
+    var key = e.key.toLowerCase();
+    var mod = e.ctrlKey || e.metaKey;
+    if (key === 'escape' || key === 'f5' || key === 'f11' || key === 'f12' ||
+        (mod && ['w', 'q', 'r', 'n', 't'].indexOf(key) !== -1) ||
+        (e.altKey && key === 'f4')) {
+      e.preventDefault();
+      e.stopImmediatePropagation();
+    }
+  }, true);
+} else {
+  window.__aetherframeKiosk.block = true;
+}
+"#;
+const UNBLOCK_SHORTCUTS: &str =
+    "if (window.__aetherframeKiosk) window.__aetherframeKiosk.block = false;";
+
+#[derive(Default)]
+pub struct KioskMode {
+    active: AtomicBool,
+}
+
+impl KioskMode {
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::SeqCst)
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct KioskChanged {
+    active: bool,
+    block_shortcuts: bool,
+}
+
+fn main_window(app: &AppHandle) -> Result<Window, String> {
+    app.get_window(MAIN)
+        .ok_or_else(|| "Main window is not available".to_string())
+}
+
+pub fn enter(app: &AppHandle, block_shortcuts: bool) -> Result<(), String> {
+    let main = main_window(app)?;
+    let state = app.state::<AppState>();
+    // Keep the windowed geometry from before, not the fullscreen one
+    state.windows.record(&main);
+    state.kiosk.active.store(true, Ordering::SeqCst);
+
+    let _ = main.unminimize();
+    main.show().map_err(|e| e.to_string())?;
+    main.set_decorations(false).map_err(|e| e.to_string())?;
+    main.set_fullscreen(true).map_err(|e| e.to_string())?;
+    let _ = main.set_always_on_top(true);
+    let _ = main.set_focus();
+    let _ = main.eval(if block_shortcuts {
+        BLOCK_SHORTCUTS
+    } else {
+        UNBLOCK_SHORTCUTS
+    });
+    let _ = app.emit_all(
+        "kiosk-changed",
+        KioskChanged {
+            active: true,
+            block_shortcuts,
+        },
+    );
+    Ok(())
+}
+
+pub fn exit(app: &AppHandle) -> Result<(), String> {
+    let main = main_window(app)?;
+    let state = app.state::<AppState>();
+    if !state.kiosk.active.swap(false, Ordering::SeqCst) {
+        return Ok(());
+    }
+    let _ = main.eval(UNBLOCK_SHORTCUTS);
+    main.set_fullscreen(false).map_err(|e| e.to_string())?;
+    main.set_decorations(true).map_err(|e| e.to_string())?;
+    let _ = main.set_always_on_top(state.settings.get().window.always_on_top);
+    let _ = app.emit_all(
+        "kiosk-changed",
+        KioskChanged {
+            active: false,
+            block_shortcuts: false,
+        },
+    );
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn enter_kiosk_mode(app: AppHandle, block_shortcuts: Option<bool>) -> Result<(), String> {
+    enter(&app, block_shortcuts.unwrap_or(false))
+}
+
+#[tauri::command]
+pub async fn exit_kiosk_mode(app: AppHandle) -> Result<(), String> {
+    exit(&app)
+}
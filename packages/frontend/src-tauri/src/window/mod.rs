@@ -0,0 +1,269 @@
+// Window management: event handling, geometry persistence and secondary windows
+
+pub mod console;
+pub mod effects;
+pub mod geometry;
+pub mod kiosk;
+pub mod open;
+pub mod widget;
+
+use serde::{Deserialize, Serialize};
+use tauri::api::dialog;
+use tauri::{AppHandle, GlobalWindowEvent, Manager, Window, WindowEvent};
+
+use crate::crash;
+use crate::health;
+use crate::i18n;
+use crate::ingest;
+use crate::menu;
+use crate::notifications;
+use crate::state::AppState;
+use crate::theme;
+use crate::tray;
+use crate::updater;
+
+pub const MAIN: &str = "main";
+
+// What the main window's close button does when a tray is available
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CloseAction {
+    Hide,
+    Quit,
+    Ask,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowSettings {
+    pub always_on_top: bool,
+    // None until the user has answered the first-close prompt
+    pub close_action: Option<CloseAction>,
+    pub start_minimized: bool,
+    pub effect: effects::WindowEffect,
+    // Switch native title bars (and GTK widgets) with the OS light/dark mode
+    pub match_system_theme: bool,
+}
+
+// Re-apply persisted window preferences at startup
+pub fn apply_settings(app: &AppHandle) {
+    let settings = app.state::<AppState>().settings.get();
+    if let Some(main) = app.get_window(MAIN) {
+        let _ = main.set_always_on_top(settings.window.always_on_top);
+        // Setup runs on the main thread, which the effects require
+        effects::apply(&main, settings.window.effect, None);
+    }
+}
+
+// Single way back into the existing UI for anything outside it (tray, recent
+// items, a second launch or deep link), so nothing spawns a duplicate window
+pub fn activate(app: &AppHandle) -> Option<Window> {
+    let main = app.get_window(MAIN)?;
+    let _ = main.unminimize();
+    let _ = main.show();
+    let _ = main.set_focus();
+    Some(main)
+}
+
+// Bring the main window forward and have the frontend route to `route`
+pub fn navigate(app: &AppHandle, route: &str) {
+    if let Some(main) = activate(app) {
+        let _ = main.emit("navigate", route);
+    }
+}
+
+// Shared by the command and the tray checkbox
+pub fn pin(app: &AppHandle, on_top: bool) -> Result<(), String> {
+    let main = app.get_window(MAIN).ok_or("Main window is not available")?;
+    // Kiosk mode keeps the window on top; the setting applies once it exits
+    if !app.state::<AppState>().kiosk.is_active() {
+        main.set_always_on_top(on_top).map_err(|e| e.to_string())?;
+    }
+    app.state::<AppState>()
+        .settings
+        .update(|s| s.window.always_on_top = on_top)?;
+    tray::rebuild(app);
+    menu::refresh(app);
+    Ok(())
+}
+
+pub fn on_event(event: GlobalWindowEvent, tray_available: bool) {
+    let window = event.window();
+    // Events can arrive before setup has registered the state
+    let Some(state) = window.try_state::<AppState>() else {
+        return;
+    };
+    match event.event() {
+        // Fullscreen kiosk geometry isn't worth restoring
+        WindowEvent::Moved(_) | WindowEvent::Resized(_) if !in_kiosk(window) => {
+            state.windows.record(window)
+        }
+        WindowEvent::Focused(true) => {
+            state.idle.touch();
+            // Whatever the badge was counting is in front of the user now
+            if window.label() == MAIN {
+                notifications::badge::clear(&window.app_handle());
+                health::on_focus(&window.app_handle());
+            }
+        }
+        WindowEvent::CloseRequested { api, .. } if in_kiosk(window) => api.prevent_close(),
+        WindowEvent::CloseRequested { api, .. } => {
+            state.windows.record(window);
+            state.windows.save();
+            // Without a tray there'd be no way back to a hidden window
+            if window.label() != MAIN || !tray_available {
+                return;
+            }
+            api.prevent_close();
+            match state.settings.get().window.close_action {
+                Some(CloseAction::Hide) => {
+                    let _ = window.hide();
+                }
+                Some(CloseAction::Quit) => quit(&window.app_handle()),
+                Some(CloseAction::Ask) => ask(window, false),
+                None => ask(window, true),
+            }
+        }
+        WindowEvent::FileDrop(drop) => ingest::on_file_drop(window, drop),
+        WindowEvent::ThemeChanged(theme) => theme::on_window_theme(&window.app_handle(), *theme),
+        WindowEvent::Destroyed if window.label() != MAIN => {
+            open::on_destroyed(&window.app_handle(), window.label());
+        }
+        _ => {}
+    }
+}
+
+fn in_kiosk(window: &Window) -> bool {
+    window.label() == MAIN && window.state::<AppState>().kiosk.is_active()
+}
+
+// Yes hides to the tray, No quits; the first answer is kept unless the user
+// explicitly chose to be asked every time
+fn ask(window: &Window, remember: bool) {
+    let app = window.app_handle();
+    let title = i18n::text(&app, "close.title");
+    let message = i18n::text(&app, "close.message");
+    let target = window.clone();
+    dialog::ask(Some(window), title, message, move |hide| {
+        if remember {
+            let action = if hide {
+                CloseAction::Hide
+            } else {
+                CloseAction::Quit
+            };
+            let result = app
+                .state::<AppState>()
+                .settings
+                .update(|s| s.window.close_action = Some(action));
+            if let Err(e) = result {
+                tracing::warn!("{}", e);
+            }
+        }
+        if hide {
+            let _ = target.hide();
+        } else {
+            quit(&app);
+        }
+    });
+}
+
+// Stop the backend off the event loop, then exit through Tauri
+pub fn quit(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        // app.exit() skips RunEvent::Exit, so flush here as well
+        state.windows.save();
+        state.backend.shutdown().await;
+        // A downloaded update goes in now, to be there on the next launch
+        updater::install_pending(&app).await;
+        crash::clean_exit();
+        app.exit(0);
+    });
+}
+
+// Lets secondary windows (widget, console) jump back into the main UI
+#[tauri::command]
+pub async fn focus_main_window(app: AppHandle, route: Option<String>) -> Result<(), String> {
+    match route {
+        Some(route) => navigate(&app, &route),
+        None => {
+            activate(&app).ok_or("Main window is not available")?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+pub struct MonitorInfo {
+    index: usize,
+    name: Option<String>,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    scale_factor: f64,
+    primary: bool,
+}
+
+// Physical pixels; `index` is what `move_to_monitor` takes
+#[tauri::command]
+pub async fn list_monitors(app: AppHandle) -> Result<Vec<MonitorInfo>, String> {
+    let main = app.get_window(MAIN).ok_or("Main window is not available")?;
+    let primary = main.primary_monitor().ok().flatten();
+    let monitors = main.available_monitors().map_err(|e| e.to_string())?;
+    Ok(monitors
+        .iter()
+        .enumerate()
+        .map(|(index, monitor)| MonitorInfo {
+            index,
+            name: monitor.name().cloned(),
+            x: monitor.position().x,
+            y: monitor.position().y,
+            width: monitor.size().width,
+            height: monitor.size().height,
+            scale_factor: monitor.scale_factor(),
+            primary: primary.as_ref().map(|p| p.name() == monitor.name()) == Some(true),
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub async fn move_to_monitor(
+    app: AppHandle,
+    index: usize,
+    label: Option<String>,
+) -> Result<(), String> {
+    let label = label.as_deref().unwrap_or(MAIN);
+    let window = app
+        .get_window(label)
+        .ok_or_else(|| format!("No such window: {}", label))?;
+    let monitors = window.available_monitors().map_err(|e| e.to_string())?;
+    let monitor = monitors
+        .get(index)
+        .ok_or_else(|| format!("No monitor at index {}", index))?;
+    geometry::move_to(&window, monitor)?;
+    let state = app.state::<AppState>();
+    state.windows.record(&window);
+    state.windows.save();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_close_action(app: AppHandle, action: Option<CloseAction>) -> Result<(), String> {
+    app.state::<AppState>()
+        .settings
+        .update(|s| s.window.close_action = action)
+}
+
+#[tauri::command]
+pub async fn set_start_minimized(app: AppHandle, enabled: bool) -> Result<(), String> {
+    app.state::<AppState>()
+        .settings
+        .update(|s| s.window.start_minimized = enabled)
+}
+
+#[tauri::command]
+pub async fn set_always_on_top(app: AppHandle, on_top: bool) -> Result<(), String> {
+    pin(&app, on_top)
+}
@@ -0,0 +1,160 @@
+// Secondary windows (log console, results viewer, ...) opened on demand
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager, Window, WindowBuilder, WindowUrl};
+
+use crate::menu;
+use crate::state::AppState;
+
+use super::{console, geometry, widget, MAIN};
+
+// Windows the app defines itself; `open_window` can't create or replace these
+const RESERVED: [&str; 4] = [MAIN, "splash", widget::LABEL, console::LABEL];
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowOptions {
+    pub title: Option<String>,
+    pub width: f64,
+    pub height: f64,
+    pub min_width: Option<f64>,
+    pub min_height: Option<f64>,
+    pub resizable: bool,
+    pub decorations: bool,
+    pub always_on_top: bool,
+    pub skip_taskbar: bool,
+}
+
+impl Default for WindowOptions {
+    fn default() -> Self {
+        Self {
+            title: None,
+            width: 900.0,
+            height: 600.0,
+            min_width: None,
+            min_height: None,
+            resizable: true,
+            decorations: true,
+            always_on_top: false,
+            skip_taskbar: false,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct WindowPayload {
+    label: String,
+}
+
+fn check_label(label: &str) -> Result<(), String> {
+    let valid = !label.is_empty()
+        && label
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '/' | ':'));
+    if !valid {
+        return Err(format!("Invalid window label: {:?}", label));
+    }
+    if RESERVED.contains(&label) {
+        return Err(format!("Window label is reserved: {}", label));
+    }
+    Ok(())
+}
+
+// Focus the window if it already exists (telling it to navigate), otherwise
+// create it with any geometry saved from last time
+pub fn open(
+    app: &AppHandle,
+    label: &str,
+    route: &str,
+    options: &WindowOptions,
+) -> Result<Window, String> {
+    if let Some(window) = app.get_window(label) {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+        let _ = window.emit("navigate", route);
+        return Ok(window);
+    }
+
+    let url = WindowUrl::App(route.trim_start_matches('/').into());
+    let mut builder = WindowBuilder::new(app, label, url)
+        .title(options.title.as_deref().unwrap_or("AetherFrame"))
+        .inner_size(options.width, options.height)
+        .resizable(options.resizable)
+        .decorations(options.decorations)
+        .always_on_top(options.always_on_top)
+        .skip_taskbar(options.skip_taskbar)
+        .visible(false);
+    if let (Some(width), Some(height)) = (options.min_width, options.min_height) {
+        builder = builder.min_inner_size(width, height);
+    }
+    let window = builder
+        .build()
+        .map_err(|e| format!("Failed to open window {}: {}", label, e))?;
+    menu::detach(&window);
+
+    geometry::restore(&window, &app.state::<AppState>().windows);
+    let _ = window.show();
+    let _ = window.set_focus();
+    let _ = app.emit_all(
+        "window-opened",
+        WindowPayload {
+            label: label.to_string(),
+        },
+    );
+    Ok(window)
+}
+
+// Tell the rest of the app a secondary window is gone
+pub fn on_destroyed(app: &AppHandle, label: &str) {
+    let _ = app.emit_all(
+        "window-closed",
+        WindowPayload {
+            label: label.to_string(),
+        },
+    );
+}
+
+// Async: creating windows from a sync command deadlocks on Windows
+#[tauri::command]
+pub async fn open_window(
+    app: AppHandle,
+    label: String,
+    route: String,
+    options: Option<WindowOptions>,
+) -> Result<(), String> {
+    check_label(&label)?;
+    open(&app, &label, &route, &options.unwrap_or_default()).map(|_| ())
+}
+
+#[tauri::command]
+pub async fn close_window(app: AppHandle, label: String) -> Result<(), String> {
+    check_label(&label)?;
+    match app.get_window(&label) {
+        Some(window) => window.close().map_err(|e| e.to_string()),
+        None => Ok(()),
+    }
+}
+
+#[tauri::command]
+pub async fn list_windows(app: AppHandle) -> Result<Vec<String>, String> {
+    let mut labels: Vec<String> = app.windows().into_keys().collect();
+    labels.sort();
+    Ok(labels)
+}
+
+// Deliver an event to one window only, instead of broadcasting to all
+#[tauri::command]
+pub async fn emit_to_window(
+    app: AppHandle,
+    label: String,
+    event: String,
+    payload: Option<Value>,
+) -> Result<(), String> {
+    if app.get_window(&label).is_none() {
+        return Err(format!("No window with label {}", label));
+    }
+    app.emit_to(&label, &event, payload)
+        .map_err(|e| format!("Failed to emit {} to {}: {}", event, label, e))
+}
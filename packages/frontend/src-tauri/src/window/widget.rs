@@ -0,0 +1,86 @@
+// Compact always-on-top widget with backend status and job progress.
+// widget.html has no JS API bundle, so state is pushed in via eval like the splash.
+
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Window};
+
+use crate::health::HealthState;
+use crate::state::AppState;
+use crate::tray;
+
+use super::open::{self, WindowOptions};
+
+pub const LABEL: &str = "widget";
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Serialize)]
+struct WidgetState {
+    health: HealthState,
+    status: String,
+    progress: Option<f64>,
+}
+
+fn options() -> WindowOptions {
+    WindowOptions {
+        title: Some("AetherFrame".to_string()),
+        width: 300.0,
+        height: 64.0,
+        resizable: false,
+        decorations: false,
+        always_on_top: true,
+        skip_taskbar: true,
+        ..Default::default()
+    }
+}
+
+pub fn toggle(app: &AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_window(LABEL) {
+        return window.close().map_err(|e| e.to_string());
+    }
+    let window = open::open(app, LABEL, "widget.html", &options())?;
+    start(app.clone(), window);
+    Ok(())
+}
+
+// Feed the widget until it closes
+fn start(app: AppHandle, window: Window) {
+    tauri::async_runtime::spawn(async move {
+        while app.get_window(LABEL).is_some() {
+            push(&app, &window);
+            tokio::time::sleep(REFRESH_INTERVAL).await;
+        }
+    });
+}
+
+fn push(app: &AppHandle, window: &Window) {
+    let state = app.state::<AppState>();
+    let widget = WidgetState {
+        health: state.health.snapshot().state,
+        status: tray::status::summary(app),
+        progress: state.jobs.overall(),
+    };
+    if let Ok(json) = serde_json::to_string(&widget) {
+        let _ = window.eval(&format!(
+            "window.setWidgetState && window.setWidgetState({})",
+            json
+        ));
+    }
+}
+
+#[tauri::command]
+pub async fn toggle_widget(app: AppHandle) -> Result<(), String> {
+    toggle(&app)
+}
+
+// The widget is frameless, so it moves itself from a mousedown handler
+#[tauri::command]
+pub fn widget_start_drag(window: Window) -> Result<(), String> {
+    window.start_dragging().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn widget_close(window: Window) -> Result<(), String> {
+    window.close().map_err(|e| e.to_string())
+}
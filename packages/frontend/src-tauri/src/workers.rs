@@ -0,0 +1,190 @@
+// A few threads of our own for CPU-heavy work (hashing, compression, image
+// encoding), so it never runs on the main thread or an async runtime worker
+// where it would hold up IPC and events. Jobs queue by priority: High for what
+// the user is waiting on right now (a clipboard image), Normal for most work,
+// Low for what can wait (a diagnostics bundle); equal priorities run in the
+// order they came. The threads start with the first job.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{self, AtomicU64, AtomicUsize};
+use std::sync::{Arc, Condvar, Mutex, Once};
+use std::time::Instant;
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::state::AppState;
+
+// Leaves a core for the UI, and doesn't take over big machines
+const MAX_WORKERS: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    Low,
+    Normal,
+    High,
+}
+
+struct Job {
+    priority: Priority,
+    sequence: u64,
+    name: &'static str,
+    queued_at: Instant,
+    run: Box<dyn FnOnce() + Send>,
+}
+
+// Highest priority first, then oldest first
+impl Ord for Job {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+impl PartialOrd for Job {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Job {
+    fn eq(&self, other: &Self) -> bool {
+        self.sequence == other.sequence
+    }
+}
+
+impl Eq for Job {}
+
+#[derive(Default)]
+struct Shared {
+    queue: Mutex<BinaryHeap<Job>>,
+    ready: Condvar,
+    running: AtomicU64,
+    completed: AtomicU64,
+}
+
+#[derive(Serialize)]
+pub struct WorkerStatus {
+    pub workers: usize,
+    pub queued: usize,
+    pub running: u64,
+    pub completed: u64,
+}
+
+pub struct WorkerPool {
+    shared: Arc<Shared>,
+    size: usize,
+    started: Once,
+    // Threads that actually came up
+    spawned: AtomicUsize,
+    sequence: AtomicU64,
+}
+
+impl Default for WorkerPool {
+    fn default() -> Self {
+        let cores = std::thread::available_parallelism().map_or(2, |n| n.get());
+        Self {
+            shared: Arc::default(),
+            size: cores.saturating_sub(1).clamp(1, MAX_WORKERS),
+            started: Once::new(),
+            spawned: AtomicUsize::new(0),
+            sequence: AtomicU64::new(0),
+        }
+    }
+}
+
+fn work(shared: &Shared) {
+    loop {
+        let job = {
+            let mut queue = shared.queue.lock().unwrap();
+            loop {
+                if let Some(job) = queue.pop() {
+                    break job;
+                }
+                queue = shared.ready.wait(queue).unwrap();
+            }
+        };
+        let waited = job.queued_at.elapsed();
+        shared.running.fetch_add(1, atomic::Ordering::SeqCst);
+        let started = Instant::now();
+        (job.run)();
+        shared.running.fetch_sub(1, atomic::Ordering::SeqCst);
+        shared.completed.fetch_add(1, atomic::Ordering::SeqCst);
+        tracing::debug!(
+            "{} ({:?}) waited {:?}, ran {:?}",
+            job.name,
+            job.priority,
+            waited,
+            started.elapsed()
+        );
+    }
+}
+
+impl WorkerPool {
+    fn start(&self) {
+        self.started.call_once(|| {
+            for index in 0..self.size {
+                let shared = self.shared.clone();
+                let spawned = std::thread::Builder::new()
+                    .name(format!("worker-{}", index))
+                    .spawn(move || work(&shared));
+                match spawned {
+                    Ok(_) => {
+                        self.spawned.fetch_add(1, atomic::Ordering::SeqCst);
+                    }
+                    Err(e) => tracing::error!("Failed to start a worker thread: {}", e),
+                }
+            }
+        });
+    }
+
+    // Runs `f` on the pool and waits for it without blocking the caller's
+    // thread. A panic in `f` comes back as an error, and the worker lives on.
+    pub async fn run<T, F>(&self, priority: Priority, name: &'static str, f: F) -> Result<T, String>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> Result<T, String> + Send + 'static,
+    {
+        self.start();
+        // Without a single worker, the job would wait forever
+        if self.spawned.load(atomic::Ordering::SeqCst) == 0 {
+            return tauri::async_runtime::spawn_blocking(f)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+        let (done, result) = tokio::sync::oneshot::channel();
+        let job = Job {
+            priority,
+            sequence: self.sequence.fetch_add(1, atomic::Ordering::Relaxed),
+            name,
+            queued_at: Instant::now(),
+            run: Box::new(move || {
+                let _ = done.send(catch_unwind(AssertUnwindSafe(f)));
+            }),
+        };
+        self.shared.queue.lock().unwrap().push(job);
+        self.shared.ready.notify_one();
+        match result.await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(format!("{} failed unexpectedly", name)),
+            Err(_) => Err(format!("{} was dropped", name)),
+        }
+    }
+
+    pub fn status(&self) -> WorkerStatus {
+        WorkerStatus {
+            workers: self.spawned.load(atomic::Ordering::SeqCst),
+            queued: self.shared.queue.lock().unwrap().len(),
+            running: self.shared.running.load(atomic::Ordering::SeqCst),
+            completed: self.shared.completed.load(atomic::Ordering::SeqCst),
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn get_worker_status(state: State<'_, AppState>) -> Result<WorkerStatus, String> {
+    Ok(state.workers.status())
+}
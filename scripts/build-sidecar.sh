@@ -0,0 +1,23 @@
+#!/bin/bash
+
+# AetherFrame - Build the Python backend as a Tauri sidecar
+# Produces packages/frontend/src-tauri/binaries/aetherframe-backend-<target-triple>
+
+set -e
+
+ROOT="$(cd "$(dirname "$0")/.." && pwd)"
+BIN_DIR="$ROOT/packages/frontend/src-tauri/binaries"
+TARGET="${1:-$(rustc -vV | sed -n 's/^host: //p')}"
+EXT=""
+if [[ "$TARGET" == *windows* ]]; then
+    EXT=".exe"
+fi
+
+echo "📦 Building backend sidecar for $TARGET..."
+cd "$ROOT/AetherFrame"
+pip install -q pyinstaller -r requirements.txt
+pyinstaller --noconfirm --onefile --name aetherframe-backend main.py
+
+mkdir -p "$BIN_DIR"
+cp "dist/aetherframe-backend$EXT" "$BIN_DIR/aetherframe-backend-$TARGET$EXT"
+echo "✅ Sidecar ready: $BIN_DIR/aetherframe-backend-$TARGET$EXT"